@@ -1,3 +1,5 @@
+use crate::hal::adc::{Channel, OneShot};
+
 /// The division factor between the system clock frequency and the input clock to the AD converter.
 ///
 /// To get 10bit precision, clock from 50kHz to 200kHz must be supplied. If you need less precision, you can supply higher clock.
@@ -20,6 +22,9 @@ impl Default for ClockRateDivision {
 
 /// Select the voltage reference for the ADC peripheral
 ///
+/// These are the references of the ATmega devices; chips with different options define their own
+/// `ReferenceVoltage` in their `adc` module.
+///
 /// The internal voltage reference options may not be used if an external reference voltage is being applied to the AREF pin.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReferenceVoltage {
@@ -37,10 +42,690 @@ impl Default for ReferenceVoltage {
     }
 }
 
+/// Event which starts a conversion in auto-triggered mode
+///
+/// These are the trigger sources of the ATmega devices; chips with different sources define their
+/// own `TriggerSource` in their `adc` module.  The discriminant is the value of the `ADTS` bits.
+///
+/// When a source other than [`FreeRunning`](TriggerSource::FreeRunning) is used, a conversion is
+/// started on the rising edge of the corresponding interrupt flag.  If the interrupt for that
+/// event is not enabled, the flag must be cleared manually (e.g. from the `ADC` interrupt) so the
+/// next event can trigger another conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TriggerSource {
+    /// Start the next conversion as soon as the previous one is completed.
+    FreeRunning = 0b000,
+    /// Analog comparator output toggled.
+    AnalogComparator = 0b001,
+    /// External interrupt request 0.
+    ExternalInterrupt0 = 0b010,
+    /// Timer/Counter0 compare match A.
+    Timer0CompareA = 0b011,
+    /// Timer/Counter0 overflow.
+    Timer0Overflow = 0b100,
+    /// Timer/Counter1 compare match B.
+    Timer1CompareB = 0b101,
+    /// Timer/Counter1 overflow.
+    Timer1Overflow = 0b110,
+    /// Timer/Counter1 input capture event.
+    Timer1Capture = 0b111,
+}
+
+impl Default for TriggerSource {
+    fn default() -> Self {
+        Self::FreeRunning
+    }
+}
+
+/// ADC settings
+///
+/// The types of the reference voltage and the trigger source depend on the chip; they default to
+/// the ATmega ones.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
-pub struct AdcSettings {
+pub struct AdcSettings<REF = ReferenceVoltage, TRIG = TriggerSource> {
     pub clock_divider: ClockRateDivision,
-    pub ref_voltage: ReferenceVoltage,
+    pub ref_voltage: REF,
+    /// Trigger source used for continuous conversions (see `Adc::start_continuous()`)
+    pub trigger_source: TRIG,
+}
+
+/// Ring buffer for samples collected in the `ADC` interrupt
+///
+/// The buffer does not allocate; it is backed by a slice which usually lives in a `static`.  When
+/// the buffer is full, new samples are dropped and an overrun is recorded.
+///
+/// # Example
+/// ```ignore
+/// static mut SAMPLES: [u16; 64] = [0; 64];
+/// static BUFFER: Mutex<RefCell<Option<SampleBuffer<'static>>>> = Mutex::new(RefCell::new(None));
+///
+/// // In main:
+/// avr_device::interrupt::free(|cs| {
+///     BUFFER.borrow(cs).replace(Some(SampleBuffer::new(unsafe { &mut SAMPLES })));
+/// });
+///
+/// #[avr_device::interrupt(atmega328p)]
+/// fn ADC() {
+///     avr_device::interrupt::free(|cs| {
+///         if let Some(buffer) = BUFFER.borrow(cs).borrow_mut().as_mut() {
+///             buffer.push(unsafe { Adc::interrupt_sample() });
+///         }
+///     })
+/// }
+/// ```
+pub struct SampleBuffer<'a> {
+    samples: &'a mut [u16],
+    start: usize,
+    len: usize,
+    overrun: bool,
+}
+
+impl<'a> SampleBuffer<'a> {
+    /// Create an empty buffer backed by `samples`.
+    pub fn new(samples: &'a mut [u16]) -> Self {
+        SampleBuffer {
+            samples,
+            start: 0,
+            len: 0,
+            overrun: false,
+        }
+    }
+
+    /// Append a sample, dropping it if the buffer is full.
+    pub fn push(&mut self, sample: u16) {
+        if self.is_full() {
+            self.overrun = true;
+            return;
+        }
+        let mut index = self.start + self.len;
+        if index >= self.samples.len() {
+            index -= self.samples.len();
+        }
+        self.samples[index] = sample;
+        self.len += 1;
+    }
+
+    /// Remove and return the oldest sample.
+    pub fn pop(&mut self) -> Option<u16> {
+        if self.is_empty() {
+            return None;
+        }
+        let sample = self.samples[self.start];
+        self.start += 1;
+        if self.start == self.samples.len() {
+            self.start = 0;
+        }
+        self.len -= 1;
+        Some(sample)
+    }
+
+    /// Number of samples currently stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Maximum number of samples which can be stored.
+    pub fn capacity(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == self.samples.len()
+    }
+
+    /// Check whether samples were dropped since the last call, and reset the flag.
+    pub fn take_overrun(&mut self) -> bool {
+        core::mem::replace(&mut self.overrun, false)
+    }
+
+    /// Discard all stored samples.
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+        self.overrun = false;
+    }
+}
+
+/// Nominal voltage of the internal bandgap reference in millivolts
+pub const BANDGAP_MILLIVOLTS: u16 = 1100;
+
+/// Calculate the supply voltage in millivolts from a conversion of the bandgap channel
+///
+/// `raw` must be measured with [`ReferenceVoltage::AVcc`] as the reference.  `bandgap_mv` is the
+/// voltage of the bandgap reference; use [`BANDGAP_MILLIVOLTS`] unless you have calibrated it for
+/// your part.
+pub fn vcc_millivolts(raw: u16, bandgap_mv: u16) -> u16 {
+    if raw == 0 {
+        return u16::MAX;
+    }
+    let vcc = bandgap_mv as u32 * 1024 / raw as u32;
+    if vcc > u16::MAX as u32 {
+        u16::MAX
+    } else {
+        vcc as u16
+    }
+}
+
+/// Calibration of the internal temperature sensor
+///
+/// The temperature is calculated as `T = (raw - offset) / gain`, where `raw` is the conversion
+/// result, `offset` is the result at 0 °C and `gain` is given in thousandths of an LSB per °C.
+/// Each chip provides typical values from its datasheet as `adc::TEMPERATURE_TYPICAL`; for
+/// accurate readings, the sensor needs to be calibrated for each part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemperatureCalibration {
+    offset: u16,
+    gain: u16,
+}
+
+impl TemperatureCalibration {
+    /// Create a calibration from the conversion result at 0 °C and the sensor gain in 1/1000 LSB
+    /// per °C (i.e. `1000` means 1 LSB/°C).
+    ///
+    /// Returns `None` for a gain of zero.
+    pub fn new(offset: u16, gain: u16) -> Option<Self> {
+        if gain == 0 {
+            None
+        } else {
+            Some(TemperatureCalibration { offset, gain })
+        }
+    }
+
+    #[doc(hidden)]
+    pub const fn typical(offset: u16, gain: u16) -> Self {
+        // Fails to compile when evaluated for a zero gain
+        let _ = [()][(gain == 0) as usize];
+        TemperatureCalibration { offset, gain }
+    }
+
+    /// Conversion result at 0 °C.
+    pub fn offset(&self) -> u16 {
+        self.offset
+    }
+
+    /// Sensor gain in 1/1000 LSB per °C.
+    pub fn gain(&self) -> u16 {
+        self.gain
+    }
+
+    /// Convert a conversion result of the temperature sensor to tenths of a degree Celsius.
+    ///
+    /// The result saturates at the limits of `i16`.
+    pub fn decicelsius(&self, raw: u16) -> i16 {
+        self.convert(raw, 10_000)
+    }
+
+    /// Convert a conversion result of the temperature sensor to degrees Celsius.
+    ///
+    /// The result saturates at the limits of `i16`.
+    pub fn celsius(&self, raw: u16) -> i16 {
+        self.convert(raw, 1000)
+    }
+
+    fn convert(&self, raw: u16, scale: i32) -> i16 {
+        let delta = raw as i32 - self.offset as i32;
+        let value = delta * scale / self.gain as i32;
+        value.max(i16::MIN as i32).min(i16::MAX as i32) as i16
+    }
+}
+
+/// State of a multi-channel scan
+///
+/// A scan converts a list of channels one after another and stores the results in a buffer
+/// provided by the caller.  It is driven by `Adc::scan()`, either by polling it (e.g. with
+/// `nb::block!()`) or by calling it from the `ADC` interrupt after `Adc::listen()`.
+///
+/// Channels which need time to settle after being selected (e.g. the bandgap reference or
+/// differential channels with gain, depending on the chip) are converted twice and the first
+/// result is discarded.  The same is done for the first conversion after the ADC was
+/// (re)configured.
+///
+/// # Example
+/// ```ignore
+/// let channels = [
+///     Adc::channel_id(&a0),
+///     Adc::channel_id(&a1),
+///     Adc::channel_id(&adc::channel::Vbg),
+/// ];
+/// let mut results = [0u16; 3];
+///
+/// let mut scan = adc::Scan::new(&channels, &mut results);
+/// nb::block!(adc.scan(&mut scan)).void_unwrap();
+///
+/// let [a0, a1, vbg] = results;
+/// ```
+pub struct Scan<'a, ID> {
+    channels: &'a [ID],
+    results: &'a mut [u16],
+    index: usize,
+    state: ScanState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Idle,
+    Converting { discard: bool },
+    Done,
+}
+
+impl<'a, ID: Copy + PartialEq> Scan<'a, ID> {
+    /// Create a scan of `channels`, storing the results in `results`.
+    ///
+    /// # Panics
+    /// Panics if `results` is shorter than `channels`.
+    pub fn new(channels: &'a [ID], results: &'a mut [u16]) -> Self {
+        assert!(results.len() >= channels.len());
+        Scan {
+            channels,
+            results,
+            index: 0,
+            state: if channels.is_empty() {
+                ScanState::Done
+            } else {
+                ScanState::Idle
+            },
+        }
+    }
+
+    /// Check whether all channels have been converted.
+    pub fn is_done(&self) -> bool {
+        self.state == ScanState::Done
+    }
+
+    /// Results of the channels which have been converted so far.
+    pub fn results(&self) -> &[u16] {
+        &self.results[..self.index]
+    }
+
+    /// Start over with the first channel.
+    ///
+    /// Must not be called while a conversion of this scan is in progress.
+    pub fn restart(&mut self) {
+        self.index = 0;
+        self.state = if self.channels.is_empty() {
+            ScanState::Done
+        } else {
+            ScanState::Idle
+        };
+    }
+
+    /// Release the result buffer.
+    pub fn release(self) -> &'a mut [u16] {
+        self.results
+    }
+
+    /// Channel which is converted next, if the scan has not yet started it.
+    #[doc(hidden)]
+    pub fn pending_channel(&self) -> Option<ID> {
+        match self.state {
+            ScanState::Idle => Some(self.channels[self.index]),
+            _ => None,
+        }
+    }
+
+    /// Whether the pending channel differs from the previously converted one.
+    #[doc(hidden)]
+    pub fn channel_changed(&self) -> bool {
+        self.index == 0 || self.channels[self.index - 1] != self.channels[self.index]
+    }
+
+    /// Record that a conversion of the pending channel was started.
+    #[doc(hidden)]
+    pub fn started(&mut self, discard: bool) {
+        self.state = ScanState::Converting { discard };
+    }
+
+    /// Whether a conversion is in progress, and if so, whether its result is discarded.
+    #[doc(hidden)]
+    pub fn converting(&self) -> Option<bool> {
+        match self.state {
+            ScanState::Converting { discard } => Some(discard),
+            _ => None,
+        }
+    }
+
+    /// Store the result of the current conversion and advance to the next channel.
+    #[doc(hidden)]
+    pub fn complete(&mut self, value: u16) {
+        self.results[self.index] = value;
+        self.index += 1;
+        self.state = if self.index == self.channels.len() {
+            ScanState::Done
+        } else {
+            ScanState::Idle
+        };
+    }
+}
+
+/// Filter used by [`Oversample`] to combine multiple conversions into one result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// Arithmetic mean of `samples` conversions, rounded to the nearest integer.
+    Average { samples: u16 },
+    /// Oversample and decimate to a resolution of `bits` (11 to 16).
+    ///
+    /// This takes `4^(bits - 10)` conversions, i.e. 4 for 11 bits, 64 for 13 bits and 4096 for
+    /// 16 bits.  The added resolution is only real if there is some noise on the input; see
+    /// Atmel application note AVR121 for details.
+    Decimate { bits: u8 },
+    /// Median of `samples` conversions.
+    ///
+    /// This filter needs a buffer for the samples, see [`Oversample::with_buffer()`].
+    Median { samples: u8 },
+    /// Mean of `samples` conversions after dropping the `trim` lowest and `trim` highest ones.
+    ///
+    /// This filter needs a buffer for the samples, see [`Oversample::with_buffer()`].
+    TrimmedMean { samples: u8, trim: u8 },
+}
+
+impl Filter {
+    /// Number of conversions needed for one result.
+    pub fn samples(&self) -> u16 {
+        match *self {
+            Filter::Average { samples } => samples,
+            Filter::Decimate { bits } => 1 << (2 * bits.saturating_sub(10).min(7)),
+            Filter::Median { samples } => samples as u16,
+            Filter::TrimmedMean { samples, .. } => samples as u16,
+        }
+    }
+
+    /// Whether this filter sorts the samples and thus needs a buffer.
+    pub fn is_sorting(&self) -> bool {
+        matches!(self, Filter::Median { .. } | Filter::TrimmedMean { .. })
+    }
+}
+
+/// Invalid [`Filter`] parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterError {
+    /// The filter takes no samples
+    NoSamples,
+    /// The resolution of [`Filter::Decimate`] is outside of 11 to 16 bits
+    Resolution,
+    /// [`Filter::TrimmedMean`] trims away all samples
+    TrimmedAll,
+    /// The buffer is too small for the samples of a sorting filter
+    BufferTooSmall,
+}
+
+/// ADC oversampling
+///
+/// Takes multiple conversions of a channel and combines them into one result using a
+/// [`Filter`].  The result is a `u16` which, in case of [`Filter::Decimate`], has more than
+/// 10 bits of resolution.
+///
+/// The `Oversample` only holds the filter state; the ADC and the channel are passed on each read.
+/// [`read()`](Oversample::read) works with any `OneShot` ADC and is non-blocking like
+/// `OneShot::read()` itself.  [`read_noise_reduced()`](Oversample::read_noise_reduced)
+/// puts the CPU into ADC noise reduction sleep for each conversion.
+///
+/// The sorting filters ([`Filter::Median`] and [`Filter::TrimmedMean`]) keep all samples of a
+/// reading in a buffer `BUF`, which is passed to [`with_buffer()`](Oversample::with_buffer).
+/// The other filters need no buffer and are created with [`new()`](Oversample::new).
+///
+/// # Example
+/// ```ignore
+/// let mut oversample = adc::Oversample::new(adc::Filter::Decimate { bits: 12 }).unwrap();
+/// let value: u16 = nb::block!(oversample.read(&mut adc, &mut a0)).void_unwrap();
+///
+/// let mut median =
+///     adc::Oversample::with_buffer(adc::Filter::Median { samples: 9 }, [0; 9]).unwrap();
+/// let value: u16 = nb::block!(median.read(&mut adc, &mut a0)).void_unwrap();
+/// ```
+pub struct Oversample<BUF = [u16; 0]> {
+    filter: Filter,
+    count: u16,
+    sum: u32,
+    sorted: BUF,
+}
+
+impl Oversample {
+    /// Create a new oversampler with a filter which needs no buffer.
+    ///
+    /// Returns an error if the filter parameters are invalid, e.g. zero samples or a `Decimate`
+    /// resolution outside of 11 to 16 bits, and for the sorting filters.
+    pub fn new(filter: Filter) -> Result<Self, FilterError> {
+        Self::with_buffer(filter, [])
+    }
+}
+
+impl<BUF: AsMut<[u16]>> Oversample<BUF> {
+    /// Create a new oversampler with the given filter, keeping the samples in `buffer`.
+    ///
+    /// The buffer must hold at least as many samples as a sorting filter takes.  Returns an error
+    /// if it is too small or if the filter parameters are invalid, e.g. zero samples, a
+    /// `Decimate` resolution outside of 11 to 16 bits, or trimming away all samples.
+    pub fn with_buffer(filter: Filter, mut buffer: BUF) -> Result<Self, FilterError> {
+        match filter {
+            Filter::Average { samples: 0 }
+            | Filter::Median { samples: 0 }
+            | Filter::TrimmedMean { samples: 0, .. } => return Err(FilterError::NoSamples),
+            Filter::Decimate { bits } if bits <= 10 || bits > 16 => {
+                return Err(FilterError::Resolution)
+            }
+            Filter::TrimmedMean { samples, trim } if samples as u16 <= 2 * trim as u16 => {
+                return Err(FilterError::TrimmedAll)
+            }
+            _ => (),
+        }
+        if filter.is_sorting() && buffer.as_mut().len() < filter.samples() as usize {
+            return Err(FilterError::BufferTooSmall);
+        }
+
+        Ok(Oversample {
+            filter,
+            count: 0,
+            sum: 0,
+            sorted: buffer,
+        })
+    }
+
+    /// The filter used by this oversampler.
+    pub fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    /// Discard the samples of a partially completed read.
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.sum = 0;
+    }
+
+    /// Take a filtered reading of `pin`.
+    ///
+    /// This returns `WouldBlock` until all conversions were done.  The same `pin` must be passed
+    /// until a result is returned; use [`reset()`](Self::reset) to abort a read.
+    pub fn read<ADC, PIN, E>(&mut self, adc: &mut ADC, pin: &mut PIN) -> nb::Result<u16, E>
+    where
+        ADC: OneShot<ADC, u16, PIN, Error = E>,
+        PIN: Channel<ADC>,
+    {
+        loop {
+            let sample = adc.read(pin)?;
+            if let Some(result) = self.push(sample) {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Take a filtered reading of `pin`, doing each conversion in ADC noise reduction sleep.
+    ///
+    /// This blocks until the result is available.  See [`NoiseReduction`] for the requirements.
+    pub fn read_noise_reduced<ADC, PIN>(&mut self, adc: &mut ADC, pin: &mut PIN) -> u16
+    where
+        ADC: NoiseReduction,
+        PIN: Channel<ADC, ID = ADC::ID>,
+    {
+        self.reset();
+        loop {
+            let sample = adc.read_noise_reduced(pin);
+            if let Some(result) = self.push(sample) {
+                return result;
+            }
+        }
+    }
+
+    /// Add a sample, returning the result once enough samples were collected.
+    fn push(&mut self, sample: u16) -> Option<u16> {
+        self.sum += sample as u32;
+        if self.filter.is_sorting() {
+            // Insertion sort as the samples come in
+            let sorted = self.sorted.as_mut();
+            let mut i = self.count as usize;
+            while i > 0 && sorted[i - 1] > sample {
+                sorted[i] = sorted[i - 1];
+                i -= 1;
+            }
+            sorted[i] = sample;
+        }
+        self.count += 1;
+
+        if self.count < self.filter.samples() {
+            return None;
+        }
+
+        let count = self.count as u32;
+        let result = match self.filter {
+            Filter::Average { .. } => (self.sum + count / 2) / count,
+            Filter::Decimate { bits } => self.sum >> (bits - 10),
+            Filter::Median { .. } => {
+                let sorted = self.sorted.as_mut();
+                let mid = count as usize / 2;
+                if count % 2 == 0 {
+                    (sorted[mid - 1] as u32 + sorted[mid] as u32 + 1) / 2
+                } else {
+                    sorted[mid] as u32
+                }
+            }
+            Filter::TrimmedMean { trim, .. } => {
+                let kept = &self.sorted.as_mut()[trim as usize..count as usize - trim as usize];
+                let sum: u32 = kept.iter().map(|s| *s as u32).sum();
+                let n = kept.len() as u32;
+                (sum + n / 2) / n
+            }
+        };
+        self.reset();
+        Some(result as u16)
+    }
+}
+
+/// Conversions in ADC noise reduction sleep mode
+///
+/// In this sleep mode, the CPU and most I/O clocks are stopped while the ADC is converting, which
+/// reduces the noise picked up by the measurement.  The CPU is woken up by the `ADC` interrupt,
+/// so **interrupts need to be enabled globally and an `ADC` interrupt handler must be defined**
+/// (it can be empty).  Otherwise the CPU will never wake up again or reset.  Other interrupts
+/// which occur during the conversion also wake the CPU; the conversion is then finished while
+/// awake.
+pub trait NoiseReduction: Sized {
+    type ID;
+
+    /// Convert `pin` in ADC noise reduction sleep mode, blocking until the result is available.
+    fn read_noise_reduced<PIN>(&mut self, pin: &mut PIN) -> u16
+    where
+        PIN: Channel<Self, ID = Self::ID>;
+}
+
+/// Gain settings for [`Differential`] channels
+pub mod gain {
+    /// 1x gain
+    pub struct X1;
+    /// 10x gain
+    pub struct X10;
+    /// 20x gain
+    pub struct X20;
+    /// 40x gain
+    pub struct X40;
+    /// 200x gain
+    pub struct X200;
+}
+
+/// Differential input channel
+///
+/// Measures the voltage of the `POS` pin relative to the `NEG` pin, amplified by `GAIN` (see
+/// [`gain`]).  Only the pin/gain combinations supported by the hardware implement
+/// `Channel`; those are listed in the `adc` module of each chip.
+///
+/// The result is a signed two's complement number; use `Adc::read_differential()` to get it as
+/// an `i16`.
+///
+/// # Example
+/// ```ignore
+/// let a0 = pins.a0.into_analog_input(&mut adc);
+/// let a1 = pins.a1.into_analog_input(&mut adc);
+///
+/// let mut shunt = adc::Differential::<_, _, adc::gain::X10>::new(a1, a0);
+/// let value: i16 = nb::block!(adc.read_differential(&mut shunt)).void_unwrap();
+/// ```
+pub struct Differential<POS, NEG, GAIN> {
+    pos: POS,
+    neg: NEG,
+    _gain: core::marker::PhantomData<GAIN>,
+}
+
+impl<POS, NEG, GAIN> Differential<POS, NEG, GAIN> {
+    /// Create a differential channel from its positive and negative input pins.
+    pub fn new(pos: POS, neg: NEG) -> Self {
+        Differential {
+            pos,
+            neg,
+            _gain: core::marker::PhantomData,
+        }
+    }
+
+    /// Release the input pins.
+    pub fn release(self) -> (POS, NEG) {
+        (self.pos, self.neg)
+    }
+}
+
+/// Implement `Channel` for the supported [`Differential`] pin/gain combinations of an ADC
+#[macro_export]
+macro_rules! impl_adc_differential {
+    (
+        adc: $Adc:ty,
+        channel_id: $ID:ty,
+        pairs: {
+            $($Pos:ident - $Neg:ident, $Gain:ident: $ChannelID:expr,)+
+        }
+    ) => {
+        $(
+            impl $crate::hal::adc::Channel<$Adc> for $crate::adc::Differential<
+                $Pos<$crate::port::mode::Analog>,
+                $Neg<$crate::port::mode::Analog>,
+                $crate::adc::gain::$Gain,
+            > {
+                type ID = $ID;
+                fn channel() -> Self::ID {
+                    $ChannelID
+                }
+            }
+        )+
+    };
+}
+
+/// Select the reference voltage in `ADMUX`, either with a chip specific implementation or the
+/// common ATmega layout.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __adc_set_reference {
+    ($peripheral:expr, $reference:expr, |$periph_var:ident, $ref_var:ident| $set_reference:block) => {{
+        let $periph_var = $peripheral;
+        let $ref_var = $reference;
+        $set_reference
+    }};
+    ($peripheral:expr, $reference:expr,) => {
+        $peripheral.admux.modify(|_, w| match $reference {
+            $crate::adc::ReferenceVoltage::Aref => w.refs().aref(),
+            $crate::adc::ReferenceVoltage::AVcc => w.refs().avcc(),
+            $crate::adc::ReferenceVoltage::Internal => w.refs().internal(),
+        })
+    };
 }
 
 #[macro_export]
@@ -48,13 +733,20 @@ macro_rules! impl_adc {
     (
         pub struct $Adc:ident {
             type ChannelID = $ID:ty;
+            type ReferenceVoltage = $Ref:ty;
+            type TriggerSource = $Trig:ty;
             peripheral: $ADC:ty,
             set_mux: |$periph_var:ident, $id_var:ident| $set_mux:block,
+            $(settling: |$settle_var:ident| $settling:block,)?
+            $(bandgap: $bandgap:expr,)?
+            $(temperature: $temperature:expr,)?
+            $(set_reference: |$ref_periph_var:ident, $ref_var:ident| $set_reference:block,)?
+            $(sleep_control: ($CPU:ty, $sleep_reg:ident),)?
             pins: {
                 $($pxi:ident: ($PXi:ident, $ChannelID:expr, $didr:ident::$didr_method:ident),)+
             }
         }
-    ) => {
+    ) => {$crate::paste::paste! {
 
         use $crate::void::Void;
         use $crate::hal::adc::{Channel, OneShot};
@@ -65,16 +757,17 @@ macro_rules! impl_adc {
         pub struct $Adc {
             peripheral: $ADC,
             reading_channel: Option<$ID>,
+            settle: bool,
         }
 
         impl $Adc {
-            pub fn new(peripheral: $ADC, settings: AdcSettings) -> $Adc {
-                let s = Self { peripheral, reading_channel: None } ;
+            pub fn new(peripheral: $ADC, settings: AdcSettings<$Ref, $Trig>) -> $Adc {
+                let s = Self { peripheral, reading_channel: None, settle: true } ;
                 s.enable(settings);
                 s
             }
 
-            fn enable(&self, settings: AdcSettings) {
+            fn enable(&self, settings: AdcSettings<$Ref, $Trig>) {
                 self.peripheral.adcsra.write(|w| {
                     w.aden().set_bit();
                     match settings.clock_divider {
@@ -86,12 +779,12 @@ macro_rules! impl_adc {
                         ClockRateDivision::Factor64 => w.adps().prescaler_64(),
                         ClockRateDivision::Factor128 => w.adps().prescaler_128(),
                     }});
-                self.peripheral.admux.write(|w| match settings.ref_voltage {
-                    ReferenceVoltage::Aref => w.refs().aref(),
-                    ReferenceVoltage::AVcc => w.refs().avcc(),
-                    ReferenceVoltage::Internal => w.refs().internal(),
+                self.peripheral.admux.reset();
+                set_reference(&self.peripheral, settings.ref_voltage);
+                // ADTS[2:0] are the low bits of ADCSRB
+                self.peripheral.adcsrb.modify(|r, w| unsafe {
+                    w.bits((r.bits() & !0x07) | settings.trigger_source as u8)
                 });
-
             }
 
             fn disable(&self) {
@@ -102,6 +795,272 @@ macro_rules! impl_adc {
                 self.disable();
                 self.peripheral
             }
+
+            /// Use the ADC multiplexer as the negative input of the analog comparator.
+            ///
+            /// The ADC has to be disabled for this, so it is consumed.  `pin` selects the input
+            /// which is compared; only the ADC pins can be used for this, the other channels are
+            /// not available to the comparator.  Use
+            /// [`from_comparator_input()`](Self::from_comparator_input) to get the ADC back.
+            pub fn into_comparator_input<PIN>(
+                mut self,
+                _pin: &mut PIN,
+            ) -> $crate::comparator::MultiplexedInput<$ADC>
+            where
+                PIN: Channel<$Adc, ID=$ID>,
+            {
+                self.disable();
+                {
+                    let $periph_var = &mut self.peripheral;
+                    let $id_var = PIN::channel();
+
+                    $set_mux
+                }
+                self.peripheral.adcsrb.modify(|_, w| w.acme().set_bit());
+                $crate::comparator::MultiplexedInput::new(self.peripheral)
+            }
+
+            /// Re-enable the ADC after its multiplexer was used by the analog comparator.
+            pub fn from_comparator_input(
+                input: $crate::comparator::MultiplexedInput<$ADC>,
+                settings: AdcSettings<$Ref, $Trig>,
+            ) -> $Adc {
+                let peripheral = input.into_inner();
+                peripheral.adcsrb.modify(|_, w| w.acme().clear_bit());
+                Self::new(peripheral, settings)
+            }
+
+            /// Switch to 8-bit mode for faster conversions.
+            ///
+            /// The conversion result is left adjusted (`ADLAR`) so only the high byte (`ADCH`)
+            /// needs to be read.  8 bits of resolution are still achieved with an ADC clock of up
+            /// to about 1 MHz, so a faster `clock_divider` can be used (e.g. `Factor16` at 16 MHz,
+            /// which gives roughly 75k samples per second in free running mode).
+            pub fn into_fast(self, clock_divider: ClockRateDivision) -> [<$Adc Fast>] {
+                set_clock_divider(&self.peripheral, clock_divider);
+                self.peripheral.admux.modify(|_, w| w.adlar().set_bit());
+                [<$Adc Fast>] {
+                    peripheral: self.peripheral,
+                    reading_channel: None,
+                }
+            }
+
+            /// Channel ID of `pin`, for building the channel list of a [`Scan`].
+            pub fn channel_id<PIN>(_pin: &PIN) -> $ID
+            where
+                PIN: Channel<$Adc, ID=$ID>,
+            {
+                PIN::channel()
+            }
+
+            /// Whether the first conversion after selecting channel `id` must be discarded.
+            #[allow(unused_variables)]
+            fn needs_settling(id: $ID) -> bool {
+                $(
+                    let $settle_var = id;
+                    if $settling {
+                        return true;
+                    }
+                )?
+                false
+            }
+
+            /// Advance a multi-channel [`Scan`].
+            ///
+            /// Returns `Ok(())` once all channels of the scan were converted.  Otherwise, the
+            /// next conversion is started if none is in progress and `WouldBlock` is returned.
+            /// This can either be polled (e.g. with `nb::block!()`) or called from the `ADC`
+            /// interrupt after [`listen()`](Self::listen) to convert all channels in the
+            /// background.
+            ///
+            /// While a scan is in progress, `OneShot` reads and continuous conversions must not be
+            /// used.
+            pub fn scan(&mut self, scan: &mut Scan<$ID>) -> nb::Result<(), Void> {
+                if let Some(discard) = scan.converting() {
+                    if self.peripheral.adcsra.read().adsc().bit_is_set() {
+                        return Err(nb::Error::WouldBlock);
+                    }
+                    if discard {
+                        // The channel is still selected, convert it again
+                        scan.started(false);
+                        self.peripheral.adcsra.modify(|_, w| w.adsc().set_bit().adif().clear_bit());
+                        return Err(nb::Error::WouldBlock);
+                    }
+                    scan.complete(self.peripheral.adc.read().bits());
+                }
+
+                match scan.pending_channel() {
+                    Some(id) => {
+                        let discard = self.settle || (scan.channel_changed() && Self::needs_settling(id));
+                        self.settle = false;
+                        self.reading_channel = None;
+                        {
+                            let $periph_var = &mut self.peripheral;
+                            let $id_var = id;
+
+                            $set_mux
+                        }
+                        scan.started(discard);
+                        self.peripheral.adcsra.modify(|_, w| w.adsc().set_bit().adif().clear_bit());
+                        Err(nb::Error::WouldBlock)
+                    }
+                    None => Ok(()),
+                }
+            }
+
+            /// Convert channel `id` with the given reference in a blocking fashion.
+            ///
+            /// After switching the reference and the channel, `delay` is used to wait for them to
+            /// settle and the first conversion is discarded.  The previous reference is restored
+            /// afterwards.
+            #[allow(dead_code)]
+            fn read_settled<D>(&mut self, id: $ID, reference: $Ref, delay: &mut D) -> u16
+            where
+                D: $crate::hal::blocking::delay::DelayMs<u8>,
+            {
+                let admux = self.peripheral.admux.read().bits();
+
+                self.reading_channel = None;
+                set_reference(&self.peripheral, reference);
+                {
+                    let $periph_var = &mut self.peripheral;
+                    let $id_var = id;
+
+                    $set_mux
+                }
+                delay.delay_ms(2);
+
+                let mut value = 0;
+                for _ in 0..2 {
+                    self.peripheral.adcsra.modify(|_, w| w.adsc().set_bit().adif().clear_bit());
+                    while self.peripheral.adcsra.read().adsc().bit_is_set() {}
+                    value = self.peripheral.adc.read().bits();
+                }
+
+                self.peripheral.admux.write(|w| unsafe { w.bits(admux) });
+                // The restored reference needs to settle again before the next scan
+                self.settle = true;
+                value
+            }
+
+            /// Read a [`Differential`] channel.
+            ///
+            /// The conversion result is a 10-bit two's complement number which is sign-extended
+            /// into an `i16`, i.e. the range is `-512..=511`.
+            pub fn read_differential<POS, NEG, GAIN>(
+                &mut self,
+                channel: &mut Differential<POS, NEG, GAIN>,
+            ) -> nb::Result<i16, Void>
+            where
+                Differential<POS, NEG, GAIN>: Channel<$Adc, ID=$ID>,
+            {
+                let raw: u16 = OneShot::<$Adc, u16, _>::read(self, channel)?;
+                Ok(((raw << 6) as i16) >> 6)
+            }
+
+            /// Start continuous conversions of `PIN`.
+            ///
+            /// Conversions are started by the [`TriggerSource`] from the [`AdcSettings`] this
+            /// ADC was created with.  For [`TriggerSource::FreeRunning`], the first conversion is
+            /// started immediately.  Results can be polled with
+            /// [`take_sample()`](Self::take_sample) or collected from the `ADC` interrupt (see
+            /// [`listen()`](Self::listen)).
+            ///
+            /// While continuous conversions are running, `OneShot` reads must not be used; call
+            /// [`stop_continuous()`](Self::stop_continuous) first.
+            pub fn start_continuous<PIN>(&mut self, _pin: &mut PIN)
+            where
+                PIN: Channel<$Adc, ID=$ID>,
+            {
+                self.reading_channel = None;
+                {
+                    let $periph_var = &mut self.peripheral;
+                    let $id_var = PIN::channel();
+
+                    $set_mux
+                }
+                let free_running = self.peripheral.adcsrb.read().bits() & 0x07 == 0;
+                self.peripheral.adcsra.modify(|_, w| {
+                    w.adif().set_bit();
+                    w.adate().set_bit();
+                    w.adsc().bit(free_running)
+                });
+            }
+
+            /// Stop continuous conversions.
+            ///
+            /// A conversion which is in progress will still complete.
+            pub fn stop_continuous(&mut self) {
+                // ADIF is explicitly written as zero here and below so a pending result is not
+                // discarded by the read-modify-write.
+                self.peripheral.adcsra.modify(|_, w| w.adate().clear_bit().adif().clear_bit());
+            }
+
+            /// Take the result of the last conversion, if a new one is available.
+            pub fn take_sample(&mut self) -> Option<u16> {
+                if self.peripheral.adcsra.read().adif().bit_is_clear() {
+                    return None;
+                }
+                let sample = self.peripheral.adc.read().bits();
+                // Writing a one clears the flag
+                self.peripheral.adcsra.modify(|_, w| w.adif().set_bit());
+                Some(sample)
+            }
+
+            /// Enable the `ADC` (conversion complete) interrupt.
+            pub fn listen(&mut self) {
+                self.peripheral.adcsra.modify(|_, w| w.adie().set_bit().adif().clear_bit());
+            }
+
+            /// Disable the `ADC` (conversion complete) interrupt.
+            pub fn unlisten(&mut self) {
+                self.peripheral.adcsra.modify(|_, w| w.adie().clear_bit().adif().clear_bit());
+            }
+
+            /// Read the result of the last conversion from within the `ADC` interrupt handler.
+            ///
+            /// This allows the interrupt handler to collect samples (e.g. into a
+            /// [`SampleBuffer`]) without needing access to the `Adc` itself.  The interrupt flag
+            /// is cleared by hardware when the handler is executed.
+            ///
+            /// # Safety
+            /// This must only be called from the `ADC` interrupt handler while continuous
+            /// conversions are running.
+            pub unsafe fn interrupt_sample() -> u16 {
+                (*<$ADC>::ptr()).adc.read().bits()
+            }
+        }
+
+        impl $Adc {
+            /// Non-blocking conversion of channel `id`, shared by the `OneShot` implementations.
+            fn read_channel(&mut self, id: $ID) -> nb::Result<u16, Void> {
+                match (self.reading_channel, self.peripheral.adcsra.read().adsc().bit_is_set()) {
+                    // Measurement on current pin is ongoing
+                    (Some(channel), true) if channel == id => Err(nb::Error::WouldBlock),
+                    // Measurement on current pin completed
+                    (Some(channel), false) if channel == id => {
+                        self.reading_channel = None;
+                        Ok(self.peripheral.adc.read().bits())
+                    },
+                    // Measurement on other pin is ongoing
+                    (Some(_), _) => {
+                        self.reading_channel = None;
+                        Err(nb::Error::WouldBlock)
+                    },
+                    // Start measurement
+                    (None, _) => {
+                        self.reading_channel = Some(id);
+                        {
+                            let $periph_var = &mut self.peripheral;
+                            let $id_var = id;
+
+                            $set_mux
+                        }
+                        self.peripheral.adcsra.modify(|_, w| w.adsc().set_bit());
+                        Err(nb::Error::WouldBlock)
+                    },
+                }
+            }
         }
 
         impl<WORD, PIN> OneShot<$Adc, WORD, PIN> for $Adc
@@ -111,21 +1070,188 @@ macro_rules! impl_adc {
         {
             type Error = Void;
 
+            fn read(&mut self, _pin: &mut PIN) -> nb::Result<WORD, Self::Error> {
+                self.read_channel(PIN::channel()).map(WORD::from)
+            }
+        }
+
+        /// Analog input with its pin type erased
+        ///
+        /// Every analog input pin (and every other channel, like `channel::Vbg`) can be converted
+        /// into an `AnalogPin` using `.into()`.  It stores the channel ID so it can be read using
+        /// the `read_pin()` method of the ADC.  This allows storing analog inputs in an array:
+        ///
+        /// ```ignore
+        /// let mut inputs: [adc::AnalogPin; 3] = [
+        ///     portc.pc0.into_analog_input(&mut adc).into(),
+        ///     portc.pc1.into_analog_input(&mut adc).into(),
+        ///     adc::channel::Vbg.into(),
+        /// ];
+        ///
+        /// for input in inputs.iter_mut() {
+        ///     let value = nb::block!(adc.read_pin(input)).void_unwrap();
+        /// }
+        /// ```
+        pub struct AnalogPin {
+            channel: $ID,
+        }
+
+        impl AnalogPin {
+            /// Channel ID of this input.
+            pub fn channel(&self) -> $ID {
+                self.channel
+            }
+        }
+
+        impl<PIN> From<PIN> for AnalogPin
+        where
+            PIN: Channel<$Adc, ID=$ID>,
+        {
+            fn from(_pin: PIN) -> Self {
+                AnalogPin { channel: PIN::channel() }
+            }
+        }
+
+        impl $Adc {
+            /// Non-blocking conversion of a type-erased [`AnalogPin`].
+            ///
+            /// This works like `OneShot::read()`, which cannot be implemented for `AnalogPin` as
+            /// its channel is only known at runtime.
+            pub fn read_pin(&mut self, pin: &mut AnalogPin) -> nb::Result<u16, Void> {
+                self.read_channel(pin.channel)
+            }
+        }
+
+        fn set_reference(peripheral: &$ADC, reference: $Ref) {
+            $crate::__adc_set_reference!(
+                peripheral,
+                reference,
+                $(|$ref_periph_var, $ref_var| $set_reference)?
+            );
+        }
+
+        fn set_clock_divider(peripheral: &$ADC, clock_divider: ClockRateDivision) {
+            peripheral.adcsra.modify(|_, w| {
+                w.adif().clear_bit();
+                match clock_divider {
+                    ClockRateDivision::Factor2 => w.adps().prescaler_2(),
+                    ClockRateDivision::Factor4 => w.adps().prescaler_4(),
+                    ClockRateDivision::Factor8 => w.adps().prescaler_8(),
+                    ClockRateDivision::Factor16 => w.adps().prescaler_16(),
+                    ClockRateDivision::Factor32 => w.adps().prescaler_32(),
+                    ClockRateDivision::Factor64 => w.adps().prescaler_64(),
+                    ClockRateDivision::Factor128 => w.adps().prescaler_128(),
+                }});
+        }
+
+        /// Read only the high byte of the conversion result.
+        fn read_adch(adc: &<$ADC as core::ops::Deref>::Target) -> u8 {
+            // ADCH is the upper byte of the 16-bit ADC register.
+            unsafe { core::ptr::read_volatile((&adc.adc as *const _ as *const u8).add(1)) }
+        }
+
+        /// ADC in 8-bit mode
+        ///
+        /// Created using `into_fast()` on the ADC.  Conversions only have 8 bits of resolution but
+        /// can run with a faster ADC clock.  The same channels as in 10-bit mode are supported.
+        pub struct [<$Adc Fast>] {
+            peripheral: $ADC,
+            reading_channel: Option<$ID>,
+        }
+
+        impl [<$Adc Fast>] {
+            /// Switch back to 10-bit mode with the given clock divider.
+            pub fn into_normal(self, clock_divider: ClockRateDivision) -> $Adc {
+                set_clock_divider(&self.peripheral, clock_divider);
+                self.peripheral.admux.modify(|_, w| w.adlar().clear_bit());
+                $Adc {
+                    peripheral: self.peripheral,
+                    reading_channel: None,
+                    settle: true,
+                }
+            }
+
+            pub fn release(self) -> $ADC {
+                self.peripheral.adcsra.reset();
+                self.peripheral.admux.modify(|_, w| w.adlar().clear_bit());
+                self.peripheral
+            }
+
+            /// Start continuous conversions of `PIN`.
+            ///
+            /// This works like `start_continuous()` in 10-bit mode.
+            pub fn start_continuous<PIN>(&mut self, _pin: &mut PIN)
+            where
+                PIN: Channel<$Adc, ID=$ID>,
+            {
+                self.reading_channel = None;
+                {
+                    let $periph_var = &mut self.peripheral;
+                    let $id_var = PIN::channel();
+
+                    $set_mux
+                }
+                let free_running = self.peripheral.adcsrb.read().bits() & 0x07 == 0;
+                self.peripheral.adcsra.modify(|_, w| {
+                    w.adif().set_bit();
+                    w.adate().set_bit();
+                    w.adsc().bit(free_running)
+                });
+            }
+
+            /// Stop continuous conversions.
+            pub fn stop_continuous(&mut self) {
+                self.peripheral.adcsra.modify(|_, w| w.adate().clear_bit().adif().clear_bit());
+            }
+
+            /// Take the result of the last conversion, if a new one is available.
+            pub fn take_sample(&mut self) -> Option<u8> {
+                if self.peripheral.adcsra.read().adif().bit_is_clear() {
+                    return None;
+                }
+                let sample = read_adch(&self.peripheral);
+                self.peripheral.adcsra.modify(|_, w| w.adif().set_bit());
+                Some(sample)
+            }
+
+            /// Enable the `ADC` (conversion complete) interrupt.
+            pub fn listen(&mut self) {
+                self.peripheral.adcsra.modify(|_, w| w.adie().set_bit().adif().clear_bit());
+            }
+
+            /// Disable the `ADC` (conversion complete) interrupt.
+            pub fn unlisten(&mut self) {
+                self.peripheral.adcsra.modify(|_, w| w.adie().clear_bit().adif().clear_bit());
+            }
+
+            /// Read the result of the last conversion from within the `ADC` interrupt handler.
+            ///
+            /// # Safety
+            /// This must only be called from the `ADC` interrupt handler while continuous
+            /// conversions are running.
+            pub unsafe fn interrupt_sample() -> u8 {
+                read_adch(&*<$ADC>::ptr())
+            }
+        }
+
+        impl<WORD, PIN> OneShot<$Adc, WORD, PIN> for [<$Adc Fast>]
+        where
+            WORD: From<u8>,
+            PIN: Channel<$Adc, ID=$ID>,
+        {
+            type Error = Void;
+
             fn read(&mut self, _pin: &mut PIN) -> nb::Result<WORD, Self::Error> {
                 match (self.reading_channel, self.peripheral.adcsra.read().adsc().bit_is_set()) {
-                    // Measurement on current pin is ongoing
                     (Some(channel), true) if channel == PIN::channel() => Err(nb::Error::WouldBlock),
-                    // Measurement on current pin completed
                     (Some(channel), false) if channel == PIN::channel() => {
                         self.reading_channel = None;
-                        Ok(self.peripheral.adc.read().bits().into())
+                        Ok(read_adch(&self.peripheral).into())
                     },
-                    // Measurement on other pin is ongoing
                     (Some(_), _) => {
                         self.reading_channel = None;
                         Err(nb::Error::WouldBlock)
                     },
-                    // Start measurement
                     (None, _) => {
                         self.reading_channel = Some(PIN::channel());
                         {
@@ -141,6 +1267,89 @@ macro_rules! impl_adc {
             }
         }
 
+        $(
+            impl $Adc {
+                /// Measure the supply voltage (AVcc) in millivolts.
+                ///
+                /// The bandgap reference is converted with AVcc as the reference voltage.
+                /// `bandgap_mv` is the voltage of the bandgap; use [`BANDGAP_MILLIVOLTS`] unless
+                /// it was calibrated for this part.  `delay` is used to let the reference settle
+                /// which takes a few milliseconds.
+                ///
+                /// This blocks until the measurement is done and must not be used while a scan or
+                /// continuous conversions are in progress.
+                pub fn read_vcc_millivolts<D>(&mut self, delay: &mut D, bandgap_mv: u16) -> u16
+                where
+                    D: $crate::hal::blocking::delay::DelayMs<u8>,
+                {
+                    let raw = self.read_settled($bandgap, <$Ref>::AVcc, delay);
+                    vcc_millivolts(raw, bandgap_mv)
+                }
+            }
+        )?
+
+        $(
+            impl $Adc {
+                /// Measure the chip temperature in degrees Celsius.
+                ///
+                /// The temperature sensor is converted with the internal reference.  See
+                /// [`TemperatureCalibration`] for how the result is calculated; typical values
+                /// are available as `TEMPERATURE_TYPICAL`.  `delay` is used to let the
+                /// reference settle which takes a few milliseconds.
+                ///
+                /// This blocks until the measurement is done and must not be used while a scan or
+                /// continuous conversions are in progress.
+                pub fn read_temperature<D>(
+                    &mut self,
+                    delay: &mut D,
+                    calibration: &TemperatureCalibration,
+                ) -> i16
+                where
+                    D: $crate::hal::blocking::delay::DelayMs<u8>,
+                {
+                    let raw = self.read_settled($temperature, <$Ref>::Internal, delay);
+                    calibration.celsius(raw)
+                }
+            }
+        )?
+
+        $(
+            impl NoiseReduction for $Adc {
+                type ID = $ID;
+
+                fn read_noise_reduced<PIN>(&mut self, _pin: &mut PIN) -> u16
+                where
+                    PIN: Channel<$Adc, ID=$ID>,
+                {
+                    self.reading_channel = None;
+                    {
+                        let $periph_var = &mut self.peripheral;
+                        let $id_var = PIN::channel();
+
+                        $set_mux
+                    }
+
+                    // SAFETY: Only the sleep mode is changed here and the previous setting is
+                    // restored before returning.
+                    let cpu = unsafe { &*<$CPU>::ptr() };
+                    let sleep_control = cpu.$sleep_reg.read().bits();
+                    let adie = self.peripheral.adcsra.read().adie().bit();
+
+                    cpu.$sleep_reg.modify(|_, w| w.sm().adc().se().set_bit());
+                    self.peripheral.adcsra.modify(|_, w| w.adie().set_bit().adif().clear_bit());
+
+                    // Entering the sleep mode starts the conversion
+                    $crate::avr_device::asm::sleep();
+                    while self.peripheral.adcsra.read().adsc().bit_is_set() {}
+                    let value = self.peripheral.adc.read().bits();
+
+                    self.peripheral.adcsra.modify(|_, w| w.adie().bit(adie).adif().clear_bit());
+                    cpu.$sleep_reg.write(|w| unsafe { w.bits(sleep_control) });
+                    value
+                }
+            }
+        )?
+
         $(
             impl Channel<$Adc> for $PXi<Analog> {
                 type ID = $ID;
@@ -157,5 +1366,5 @@ macro_rules! impl_adc {
                     }
             }
         )+
-    }
+    }}
 }
@@ -0,0 +1,384 @@
+//! Input capture for 16-bit timers
+//!
+//! On an edge at the `ICPn` pin, the timer copies its current value into the `ICRn` register and
+//! raises the `TIMERn_CAPT` interrupt.  The input capture driver collects these timestamps in a
+//! small buffer and extends them to 32 bits by counting timer overflows, so long periods can be
+//! measured as well.
+//!
+//! The application needs to forward the capture and overflow interrupts to the driver:
+//!
+//! ```
+//! let mut capture = InputCapture1::<MHz16, _>::new(dp.TC1, pins.d8, CaptureSettings::default());
+//! unsafe { avr_device::interrupt::enable() };
+//!
+//! loop {
+//!     let period = nb::block!(capture.period()).void_unwrap();
+//! }
+//!
+//! #[avr_device::interrupt(atmega328p)]
+//! fn TIMER1_CAPT() {
+//!     InputCapture1::<MHz16, Floating>::on_capture();
+//! }
+//!
+//! #[avr_device::interrupt(atmega328p)]
+//! fn TIMER1_OVF() {
+//!     InputCapture1::<MHz16, Floating>::on_overflow();
+//! }
+//! ```
+//!
+//! Timestamps are given in timer ticks.  The helper methods `period()`, `pulse_width()` and
+//! `frequency()` convert them into physical units.
+
+pub use crate::pwm::Prescaler;
+
+/// Number of timestamps which can be buffered before they must be read.
+pub const BUFFER_SIZE: usize = 8;
+
+/// Signal edge which triggers a capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// Capture on rising edges.
+    Rising,
+    /// Capture on falling edges.
+    Falling,
+    /// Capture on both edges.
+    ///
+    /// The edge detector is switched after each capture.  For very short pulses, the second edge
+    /// might pass before the detector is switched and is lost.
+    Both,
+}
+
+/// Settings for the input capture unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureSettings {
+    /// Edges which trigger a capture.
+    pub edge: Edge,
+    /// Enable the noise canceler.
+    ///
+    /// With the noise canceler, the input must be stable for four clock cycles before an edge is
+    /// detected.  This delays each capture by four clock cycles.
+    pub noise_canceler: bool,
+    /// Prescaler of the timer.  Determines resolution and overflow period of the timestamps.
+    pub prescaler: Prescaler,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            edge: Edge::Rising,
+            noise_canceler: false,
+            prescaler: Prescaler::Prescale8,
+        }
+    }
+}
+
+/// A captured timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capture {
+    /// Timer ticks since the input capture was started.  Wraps around after 2^32 ticks.
+    pub ticks: u32,
+    /// `true` if this was a rising edge, `false` for a falling edge.
+    pub rising: bool,
+}
+
+/// Division factor and clock-select bits of a prescaler.
+#[doc(hidden)]
+pub fn prescaler_config(prescaler: Prescaler) -> (u32, u8) {
+    match prescaler {
+        Prescaler::Direct => (1, 0b001),
+        Prescaler::Prescale8 => (8, 0b010),
+        Prescaler::Prescale64 => (64, 0b011),
+        Prescaler::Prescale256 => (256, 0b100),
+        Prescaler::Prescale1024 => (1024, 0b101),
+    }
+}
+
+/// Shared state between the capture interrupt and the driver.
+#[doc(hidden)]
+pub struct CaptureState {
+    overflows: u16,
+    buffer: [Capture; BUFFER_SIZE],
+    start: u8,
+    len: u8,
+    overrun: bool,
+    alternate: bool,
+}
+
+impl CaptureState {
+    pub const fn new() -> Self {
+        CaptureState {
+            overflows: 0,
+            buffer: [Capture {
+                ticks: 0,
+                rising: false,
+            }; BUFFER_SIZE],
+            start: 0,
+            len: 0,
+            overrun: false,
+            alternate: false,
+        }
+    }
+
+    pub fn reset(&mut self, alternate: bool) {
+        *self = Self::new();
+        self.alternate = alternate;
+    }
+
+    /// Whether the edge detector must be switched after each capture.
+    pub fn alternate(&self) -> bool {
+        self.alternate
+    }
+
+    pub fn overflow(&mut self) {
+        self.overflows = self.overflows.wrapping_add(1);
+    }
+
+    /// Extend a 16-bit timer value to 32 bits.
+    ///
+    /// `overflow_pending` is the state of the overflow flag.  If it is set and the value is small,
+    /// the overflow happened before the value was latched but was not counted yet.
+    pub fn extend(&self, value: u16, overflow_pending: bool) -> u32 {
+        let mut overflows = self.overflows;
+        if overflow_pending && value < 0x8000 {
+            overflows = overflows.wrapping_add(1);
+        }
+        (overflows as u32) << 16 | value as u32
+    }
+
+    pub fn push(&mut self, capture: Capture) {
+        if self.len as usize == BUFFER_SIZE {
+            self.overrun = true;
+            return;
+        }
+        let i = (self.start as usize + self.len as usize) % BUFFER_SIZE;
+        self.buffer[i] = capture;
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<Capture> {
+        if self.len == 0 {
+            return None;
+        }
+        let capture = self.buffer[self.start as usize];
+        self.start = ((self.start as usize + 1) % BUFFER_SIZE) as u8;
+        self.len -= 1;
+        Some(capture)
+    }
+
+    pub fn take_overrun(&mut self) -> bool {
+        core::mem::replace(&mut self.overrun, false)
+    }
+}
+
+/// Implement input capture for a 16-bit timer
+#[macro_export]
+macro_rules! impl_input_capture {
+    (
+        $(#[$capture_attr:meta])*
+        pub struct $InputCapture:ident {
+            peripheral: $TIMER:ty,
+            register_suffix: $n:expr,
+            pin: $pinmod:ident::$ICP:ident,
+        }
+    ) => {
+        $crate::paste::paste! {
+            $(#[$capture_attr])*
+            pub struct $InputCapture<CLOCK, IMODE: $crate::port::mode::InputMode> {
+                timer: $TIMER,
+                pin: $pinmod::$ICP<$crate::port::mode::Input<IMODE>>,
+                divider: u32,
+                last: [Option<u32>; 2],
+                _clock: core::marker::PhantomData<CLOCK>,
+            }
+
+            impl<CLOCK, IMODE: $crate::port::mode::InputMode> $InputCapture<CLOCK, IMODE> {
+                fn state() -> &'static $crate::avr_device::interrupt::Mutex<
+                    core::cell::RefCell<$crate::capture::CaptureState>
+                > {
+                    static STATE: $crate::avr_device::interrupt::Mutex<
+                        core::cell::RefCell<$crate::capture::CaptureState>
+                    > = $crate::avr_device::interrupt::Mutex::new(
+                        core::cell::RefCell::new($crate::capture::CaptureState::new())
+                    );
+                    &STATE
+                }
+
+                /// Record a timestamp.
+                ///
+                /// This function must be called from the `TIMERn_CAPT` interrupt.  The type
+                /// parameters do not matter here.
+                pub fn on_capture() {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        // SAFETY: The ICR register and the flags are only read here.  The edge
+                        // select bit is only changed here once the driver is set up.
+                        let timer = unsafe { &*<$TIMER>::ptr() };
+
+                        let value = timer.[<icr $n>].read().bits();
+                        let rising = timer.[<tccr $n b>].read().[<ices $n>]().bit_is_set();
+                        let overflow = timer.[<tifr $n>].read().[<tov $n>]().bit_is_set();
+
+                        let mut state = Self::state().borrow(cs).borrow_mut();
+                        let ticks = state.extend(value, overflow);
+                        state.push($crate::capture::Capture { ticks, rising });
+
+                        if state.alternate() {
+                            timer.[<tccr $n b>].modify(|_, w| w.[<ices $n>]().bit(!rising));
+                            timer.[<tifr $n>].write(|w| w.[<icf $n>]().set_bit());
+                        }
+                    })
+                }
+
+                /// Count a timer overflow.
+                ///
+                /// This function must be called from the `TIMERn_OVF` interrupt.
+                pub fn on_overflow() {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        Self::state().borrow(cs).borrow_mut().overflow();
+                    })
+                }
+            }
+
+            impl<CLOCK: $crate::clock::Clock, IMODE: $crate::port::mode::InputMode>
+                $InputCapture<CLOCK, IMODE>
+            {
+                /// Start capturing timestamps on the `ICP` pin.
+                ///
+                /// The timer runs in normal mode and the capture and overflow interrupts are
+                /// enabled.  Interrupts must be enabled globally for captures to be recorded.
+                pub fn new(
+                    timer: $TIMER,
+                    pin: $pinmod::$ICP<$crate::port::mode::Input<IMODE>>,
+                    settings: $crate::capture::CaptureSettings,
+                ) -> Self {
+                    let (divider, cs_bits) = $crate::capture::prescaler_config(settings.prescaler);
+
+                    $crate::avr_device::interrupt::free(|cs| {
+                        Self::state()
+                            .borrow(cs)
+                            .borrow_mut()
+                            .reset(settings.edge == $crate::capture::Edge::Both);
+
+                        timer.[<tccr $n b>].reset();
+                        timer.[<tccr $n a>].reset();
+                        timer.[<tcnt $n>].reset();
+
+                        let ices = match settings.edge {
+                            $crate::capture::Edge::Falling => 0,
+                            _ => 1 << 6,
+                        };
+                        let icnc = if settings.noise_canceler { 1 << 7 } else { 0 };
+                        timer.[<tccr $n b>].write(|w| unsafe { w.bits(icnc | ices | cs_bits) });
+
+                        // Changing the edge can trigger a capture, so clear the flags afterwards
+                        timer.[<tifr $n>].write(|w| {
+                            w.[<icf $n>]().set_bit().[<tov $n>]().set_bit()
+                        });
+                        timer.[<timsk $n>].write(|w| {
+                            w.[<icie $n>]().set_bit().[<toie $n>]().set_bit()
+                        });
+                    });
+
+                    $InputCapture {
+                        timer,
+                        pin,
+                        divider,
+                        last: [None, None],
+                        _clock: core::marker::PhantomData,
+                    }
+                }
+
+                /// Stop the timer and return the peripheral and the pin.
+                pub fn release(
+                    self,
+                ) -> ($TIMER, $pinmod::$ICP<$crate::port::mode::Input<IMODE>>) {
+                    self.timer.[<timsk $n>].reset();
+                    self.timer.[<tccr $n b>].reset();
+                    (self.timer, self.pin)
+                }
+
+                /// Read the oldest captured timestamp.
+                pub fn read(
+                    &mut self,
+                ) -> $crate::nb::Result<$crate::capture::Capture, $crate::void::Void> {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        Self::state().borrow(cs).borrow_mut().pop()
+                    })
+                    .ok_or($crate::nb::Error::WouldBlock)
+                }
+
+                /// Check whether captures were lost because the buffer was full.
+                ///
+                /// The flag is reset by this call.
+                pub fn take_overrun(&mut self) -> bool {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        Self::state().borrow(cs).borrow_mut().take_overrun()
+                    })
+                }
+
+                /// Current timer value, extended to 32 bits like the captured timestamps.
+                pub fn now(&self) -> u32 {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        let value = self.timer.[<tcnt $n>].read().bits();
+                        let overflow = self.timer.[<tifr $n>].read().[<tov $n>]().bit_is_set();
+                        Self::state().borrow(cs).borrow().extend(value, overflow)
+                    })
+                }
+
+                /// Convert a number of timer ticks to microseconds.
+                ///
+                /// The result saturates at `u32::MAX` microseconds.
+                pub fn ticks_to_us(&self, ticks: u32) -> $crate::timer::Microseconds {
+                    let us = ticks as u64 * self.divider as u64 * 1_000_000 / CLOCK::FREQ as u64;
+                    $crate::timer::Microseconds(us.min(u32::MAX as u64) as u32)
+                }
+
+                /// Measure the time between two consecutive edges of the same direction.
+                ///
+                /// Each call consumes captured timestamps until a period is found.
+                pub fn period(
+                    &mut self,
+                ) -> $crate::nb::Result<$crate::timer::Microseconds, $crate::void::Void> {
+                    self.period_ticks().map(|ticks| self.ticks_to_us(ticks))
+                }
+
+                /// Measure the frequency of the input signal from one period.
+                pub fn frequency(
+                    &mut self,
+                ) -> $crate::nb::Result<$crate::timer::Hertz, $crate::void::Void> {
+                    let ticks = self.period_ticks()?;
+                    Ok($crate::timer::Hertz(CLOCK::FREQ / self.divider / ticks.max(1)))
+                }
+
+                /// Measure the width of a pulse starting with the given edge.
+                ///
+                /// For high pulses, pass `Edge::Rising`, for low pulses `Edge::Falling`.  This
+                /// requires the input capture to be configured for `Edge::Both`.
+                pub fn pulse_width(
+                    &mut self,
+                    start: $crate::capture::Edge,
+                ) -> $crate::nb::Result<$crate::timer::Microseconds, $crate::void::Void> {
+                    let start_rising = start != $crate::capture::Edge::Falling;
+                    loop {
+                        let capture = self.read()?;
+                        self.last[capture.rising as usize] = Some(capture.ticks);
+                        if capture.rising != start_rising {
+                            if let Some(begin) = self.last[start_rising as usize].take() {
+                                return Ok(self.ticks_to_us(capture.ticks.wrapping_sub(begin)));
+                            }
+                        }
+                    }
+                }
+
+                fn period_ticks(&mut self) -> $crate::nb::Result<u32, $crate::void::Void> {
+                    loop {
+                        let capture = self.read()?;
+                        let last = self.last[capture.rising as usize].replace(capture.ticks);
+                        if let Some(last) = last {
+                            return Ok(capture.ticks.wrapping_sub(last));
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
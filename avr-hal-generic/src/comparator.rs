@@ -0,0 +1,227 @@
+//! Analog Comparator
+//!
+//! The analog comparator compares the voltages on its positive and negative input and sets its
+//! output when the positive input is higher.  The positive input is either the `AIN0` pin or the
+//! internal bandgap reference ([`Bandgap`]).  The negative input is either the `AIN1` pin or one
+//! of the ADC input pins, selected via the ADC multiplexer ([`MultiplexedInput`]).
+//!
+//! # Example
+//! ```ignore
+//! let ain0 = pins.d6.into_comparator_input(&mut dp.AC);
+//! let ain1 = pins.d7.into_comparator_input(&mut dp.AC);
+//!
+//! let mut comparator = comparator::AnalogComparator::new(dp.AC, ain0, ain1);
+//! comparator.listen(comparator::Edge::Rising);
+//!
+//! if comparator.output() {
+//!     // AIN0 is higher than AIN1
+//! }
+//! ```
+
+/// Output edge which triggers the comparator interrupt (or sets its flag)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// Any change of the comparator output.
+    Toggle,
+    /// Output changes from high to low.
+    Falling,
+    /// Output changes from low to high.
+    Rising,
+}
+
+impl Edge {
+    /// Value of the `ACIS` bits for this edge.
+    #[doc(hidden)]
+    pub fn acis_bits(self) -> u8 {
+        match self {
+            Edge::Toggle => 0b00,
+            Edge::Falling => 0b10,
+            Edge::Rising => 0b11,
+        }
+    }
+}
+
+/// The internal bandgap reference as positive comparator input
+pub struct Bandgap;
+
+/// The ADC multiplexer as negative comparator input
+///
+/// This is created from the ADC using its `into_comparator_input()` method.  The ADC is disabled
+/// while its multiplexer is used by the comparator; to get it back, pass this to
+/// `Adc::from_comparator_input()`.
+pub struct MultiplexedInput<ADC> {
+    adc: ADC,
+}
+
+impl<ADC> MultiplexedInput<ADC> {
+    #[doc(hidden)]
+    pub fn new(adc: ADC) -> Self {
+        MultiplexedInput { adc }
+    }
+
+    #[doc(hidden)]
+    pub fn into_inner(self) -> ADC {
+        self.adc
+    }
+}
+
+/// Inputs which can be used as the positive input of the comparator `AC`
+pub trait PositiveInput<AC> {
+    /// Whether this input is the internal bandgap reference (`ACBG`).
+    #[doc(hidden)]
+    const BANDGAP: bool;
+}
+
+/// Inputs which can be used as the negative input of the comparator `AC`
+pub trait NegativeInput<AC> {}
+
+impl<AC> PositiveInput<AC> for Bandgap {
+    const BANDGAP: bool = true;
+}
+
+impl<AC, ADC> NegativeInput<AC> for MultiplexedInput<ADC> {}
+
+#[macro_export]
+macro_rules! impl_analog_comparator {
+    (
+        pub struct $Comparator:ident {
+            peripheral: $AC:ty,
+            ain0: ($AIN0:ident, $didr0:ident::$didr0_method:ident),
+            $(ain1: ($AIN1:ident, $didr1:ident::$didr1_method:ident),)?
+            $(input_capture: $input_capture:tt,)?
+        }
+    ) => {
+        pub use $crate::comparator::*;
+        use $crate::port::mode::Analog;
+
+        /// Analog comparator with positive input `POS` and negative input `NEG`
+        pub struct $Comparator<POS, NEG> {
+            peripheral: $AC,
+            positive: POS,
+            negative: NEG,
+        }
+
+        impl<POS, NEG> $Comparator<POS, NEG>
+        where
+            POS: PositiveInput<$AC>,
+            NEG: NegativeInput<$AC>,
+        {
+            /// Enable the comparator with the given inputs.
+            ///
+            /// The comparator interrupt is disabled initially (see
+            /// [`listen()`](Self::listen)).
+            pub fn new(peripheral: $AC, positive: POS, negative: NEG) -> Self {
+                peripheral.acsr.write(|w| {
+                    w.acd().clear_bit();
+                    w.acbg().bit(POS::BANDGAP);
+                    // Clear a pending flag
+                    w.aci().set_bit()
+                });
+                $Comparator {
+                    peripheral,
+                    positive,
+                    negative,
+                }
+            }
+
+            /// Disable the comparator and return the peripheral and its inputs.
+            pub fn release(self) -> ($AC, POS, NEG) {
+                self.peripheral.acsr.write(|w| w.acd().set_bit().aci().set_bit());
+                (self.peripheral, self.positive, self.negative)
+            }
+
+            /// Current comparator output.
+            ///
+            /// `true` if the positive input is higher than the negative input.
+            pub fn output(&self) -> bool {
+                self.peripheral.acsr.read().aco().bit_is_set()
+            }
+
+            /// Select which output edge sets the interrupt flag.
+            pub fn set_edge(&mut self, edge: Edge) {
+                // Changing ACIS can trigger a spurious interrupt, so it is disabled meanwhile
+                let acie = self.peripheral.acsr.read().acie().bit();
+                self.peripheral.acsr.modify(|_, w| w.acie().clear_bit().aci().clear_bit());
+                // Writing a one to ACI clears a flag set by the change
+                self.peripheral.acsr.modify(|_, w| unsafe {
+                    w.acis().bits(edge.acis_bits()).aci().set_bit()
+                });
+                self.peripheral.acsr.modify(|_, w| w.acie().bit(acie).aci().clear_bit());
+            }
+
+            /// Check whether the selected edge occurred since the last call and clear the flag.
+            ///
+            /// When the interrupt is enabled, the flag is cleared by hardware when the
+            /// `ANALOG_COMP` interrupt handler is executed instead.
+            pub fn check_edge(&mut self) -> bool {
+                if self.peripheral.acsr.read().aci().bit_is_set() {
+                    self.peripheral.acsr.modify(|_, w| w.aci().set_bit());
+                    true
+                } else {
+                    false
+                }
+            }
+
+            /// Enable the comparator interrupt for the given output edge.
+            pub fn listen(&mut self, edge: Edge) {
+                self.set_edge(edge);
+                self.peripheral.acsr.modify(|_, w| w.acie().set_bit().aci().clear_bit());
+            }
+
+            /// Disable the comparator interrupt.
+            pub fn unlisten(&mut self) {
+                self.peripheral.acsr.modify(|_, w| w.acie().clear_bit().aci().clear_bit());
+            }
+        }
+
+        $($crate::__comparator_input_capture!($input_capture, $Comparator, $AC);)?
+
+        impl PositiveInput<$AC> for $AIN0<Analog> {
+            const BANDGAP: bool = false;
+        }
+
+        impl<MODE> $AIN0<MODE> {
+            /// Make this pin the `AIN0` comparator input and disable its digital input buffer
+            pub fn into_comparator_input(self, ac: &mut $AC) -> $AIN0<Analog> {
+                ac.$didr0.modify(|_, w| w.$didr0_method().set_bit());
+                $AIN0 { _mode: core::marker::PhantomData }
+            }
+        }
+
+        $(
+            impl NegativeInput<$AC> for $AIN1<Analog> {}
+
+            impl<MODE> $AIN1<MODE> {
+                /// Make this pin the `AIN1` comparator input and disable its digital input buffer
+                pub fn into_comparator_input(self, ac: &mut $AC) -> $AIN1<Analog> {
+                    ac.$didr1.modify(|_, w| w.$didr1_method().set_bit());
+                    $AIN1 { _mode: core::marker::PhantomData }
+                }
+            }
+        )?
+    };
+}
+
+/// Implement `set_input_capture()` for comparators which can trigger the `TC1` input capture unit.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __comparator_input_capture {
+    (true, $Comparator:ident, $AC:ty) => {
+        impl<POS, NEG> $Comparator<POS, NEG>
+        where
+            POS: PositiveInput<$AC>,
+            NEG: NegativeInput<$AC>,
+        {
+            /// Connect the comparator output to the Timer/Counter1 input capture unit.
+            ///
+            /// When enabled, the comparator output (filtered by the edge selection of the timer)
+            /// triggers input captures instead of the `ICP1` pin.
+            pub fn set_input_capture(&mut self, enable: bool) {
+                self.peripheral
+                    .acsr
+                    .modify(|_, w| w.acic().bit(enable).aci().clear_bit());
+            }
+        }
+    };
+    (false, $Comparator:ident, $AC:ty) => {};
+}
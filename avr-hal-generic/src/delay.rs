@@ -13,9 +13,12 @@ use hal::blocking::delay;
 /// delay.delay_ms(1000);
 /// ```
 ///
-/// # Warning
-/// The delay is not accurate for values above 4095µs because of a loop whose
-/// overhead is not accounted for.  This will be fixed in a future version.
+/// The number of loop iterations is calculated from the clock frequency, so the delay works for
+/// every `Clock` type.  The time spent calculating it is accounted for, which limits the shortest
+/// possible delay to a few dozen CPU cycles: about 3µs at 16 MHz, but about 50µs with a 1 MHz
+/// clock.  Shorter delays return immediately.  Interrupts which occur during the delay extend it.
+///
+/// For exact timing in bit-banging code, see [`delay_cycles()`].
 #[derive(Debug, Clone, Copy)]
 pub struct Delay<SPEED> {
     _speed: marker::PhantomData<SPEED>,
@@ -29,12 +32,39 @@ impl<SPEED> Delay<SPEED> {
     }
 }
 
-// based on https://github.com/arduino/ArduinoCore-avr/blob/master/cores/arduino/wiring.c
+/// Cycles per iteration of `busy_loop()`
+const LOOP_CYCLES: u32 = 4;
+
+/// Cycles spent in `delay_us()` outside of `busy_loop()`, including the call itself
+///
+/// Most of this is the 32-bit multiplication.
+const CALL_OVERHEAD: u32 = 48;
+
+/// Cycles spent for each chunk of a long delay outside of `busy_loop()`
+const CHUNK_OVERHEAD: u32 = 12;
+
+impl<SPEED: crate::clock::Clock> Delay<SPEED> {
+    /// Iterations of `busy_loop()` per microsecond, as a 16.16 fixed point number
+    const ITERS_PER_US: u32 =
+        ((SPEED::FREQ as u64 * 65536) / (LOOP_CYCLES as u64 * 1_000_000)) as u32;
+
+    /// Longest delay in microseconds which fits into a single `busy_loop()`
+    const CHUNK_US: u32 = (0xffff << 16) / Self::ITERS_PER_US;
+
+    /// Iterations of `busy_loop()` for one chunk, minus the time spent between chunks
+    const CHUNK_ITERS: u16 =
+        (((Self::CHUNK_US * Self::ITERS_PER_US) >> 16) - CHUNK_OVERHEAD / LOOP_CYCLES) as u16;
+}
 
 cfg_if::cfg_if! {
     if #[cfg(target_arch = "avr")] {
+        /// Busy-wait for `4 * c` cycles.
         #[allow(unused_assignments)]
         fn busy_loop(mut c: u16) {
+            // The loop would wrap around for zero iterations
+            if c == 0 {
+                return;
+            }
             unsafe {
                 llvm_asm!("1: sbiw $0,1\n\tbrne 1b"
                      : "=w"(c)
@@ -51,181 +81,48 @@ cfg_if::cfg_if! {
     }
 }
 
-// Clock-Specific Delay Implementations ----------------------------------- {{{
-impl delay::DelayUs<u16> for Delay<crate::clock::MHz24> {
-    fn delay_us(&mut self, mut us: u16) {
-        // for the 24 crate::clock::MHz clock for the aventurous ones, trying to overclock
-
-        // zero delay fix
-        if us == 0 {
-            return;
-        } // = 3 cycles, (4 when true)
-
-        // the following loop takes a 1/6 of a microsecond (4 cycles)
-        // per iteration, so execute it six times for each microsecond of
-        // delay requested.
-        us *= 6; // x6 us, = 7 cycles
-
-        // account for the time taken in the preceeding commands.
-        // we just burned 22 (24) cycles above, remove 5, (5*4=20)
-        // us is at least 6 so we can substract 5
-        us -= 5; //=2 cycles
-
-        busy_loop(us);
-    }
-}
-
-impl delay::DelayUs<u16> for Delay<crate::clock::MHz20> {
-    fn delay_us(&mut self, mut us: u16) {
-        // for the 20 crate::clock::MHz clock on rare Arduino boards
-
-        // for a one-microsecond delay, simply return.  the overhead
-        // of the function call takes 18 (20) cycles, which is 1us
-        unsafe {
-            llvm_asm!("nop\nnop\nnop\nnop" :::: "volatile");
-        } //just waiting 4 cycles
-
-        if us <= 1 {
-            return;
-        } // = 3 cycles, (4 when true)
-
-        // the following loop takes a 1/5 of a microsecond (4 cycles)
-        // per iteration, so execute it five times for each microsecond of
-        // delay requested.
-        us = (us << 2) + us; // x5 us, = 7 cycles
-
-        // account for the time taken in the preceeding commands.
-        // we just burned 26 (28) cycles above, remove 7, (7*4=28)
-        // us is at least 10 so we can substract 7
-        us -= 7; // 2 cycles
-
-        busy_loop(us);
-    }
-}
-
-impl delay::DelayUs<u16> for Delay<crate::clock::MHz16> {
-    fn delay_us(&mut self, mut us: u16) {
-        // for the 16 crate::clock::MHz clock on most Arduino boards
-
-        // for a one-microsecond delay, simply return.  the overhead
-        // of the function call takes 14 (16) cycles, which is 1us
-        if us <= 1 {
-            return;
-        } // = 3 cycles, (4 when true)
-
-        // the following loop takes 1/4 of a microsecond (4 cycles)
-        // per iteration, so execute it four times for each microsecond of
-        // delay requested.
-        us <<= 2; // x4 us, = 4 cycles
-
-        // account for the time taken in the preceeding commands.
-        // we just burned 19 (21) cycles above, remove 5, (5*4=20)
-        // us is at least 8 so we can substract 5
-        us -= 5; // = 2 cycles,
-
-        busy_loop(us);
-    }
-}
-
-impl delay::DelayUs<u16> for Delay<crate::clock::MHz12> {
-    fn delay_us(&mut self, mut us: u16) {
-        // for the 12 crate::clock::MHz clock if somebody is working with USB
-
-        // for a 1 microsecond delay, simply return.  the overhead
-        // of the function call takes 14 (16) cycles, which is 1.5us
-        if us <= 1 {
-            return;
-        } // = 3 cycles, (4 when true)
-
-        // the following loop takes 1/3 of a microsecond (4 cycles)
-        // per iteration, so execute it three times for each microsecond of
-        // delay requested.
-        us = (us << 1) + us; // x3 us, = 5 cycles
-
-        // account for the time taken in the preceeding commands.
-        // we just burned 20 (22) cycles above, remove 5, (5*4=20)
-        // us is at least 6 so we can substract 5
-        us -= 5; //2 cycles
-
-        busy_loop(us);
-    }
-}
-
-impl delay::DelayUs<u16> for Delay<crate::clock::MHz8> {
-    fn delay_us(&mut self, mut us: u16) {
-        // for the 8 crate::clock::MHz internal clock
-
-        // for a 1 and 2 microsecond delay, simply return.  the overhead
-        // of the function call takes 14 (16) cycles, which is 2us
-        if us <= 2 {
-            return;
-        } // = 3 cycles, (4 when true)
-
-        // the following loop takes 1/2 of a microsecond (4 cycles)
-        // per iteration, so execute it twice for each microsecond of
-        // delay requested.
-        us <<= 1; //x2 us, = 2 cycles
-
-        // account for the time taken in the preceeding commands.
-        // we just burned 17 (19) cycles above, remove 4, (4*4=16)
-        // us is at least 6 so we can substract 4
-        us -= 4; // = 2 cycles
+impl<SPEED: crate::clock::Clock> delay::DelayUs<u32> for Delay<SPEED> {
+    fn delay_us(&mut self, mut us: u32) {
+        // Long delays are split into chunks which fit into the 16-bit loop counter
+        while us > Self::CHUNK_US {
+            busy_loop(Self::CHUNK_ITERS);
+            us -= Self::CHUNK_US;
+        }
 
-        busy_loop(us);
+        let iters = (us * Self::ITERS_PER_US) >> 16;
+        busy_loop(iters.saturating_sub(CALL_OVERHEAD / LOOP_CYCLES) as u16);
     }
 }
 
-impl delay::DelayUs<u16> for Delay<crate::clock::MHz1> {
-    fn delay_us(&mut self, mut us: u16) {
-        // for the 1 crate::clock::MHz internal clock (default settings for common Atmega microcontrollers)
-
-        // the overhead of the function calls is 14 (16) cycles
-        if us <= 16 {
-            return;
-        } //= 3 cycles, (4 when true)
-        if us <= 25 {
-            return;
-        } //= 3 cycles, (4 when true), (must be at least 25 if we want to substract 22)
-
-        // compensate for the time taken by the preceeding and next commands (about 22 cycles)
-        us -= 22; // = 2 cycles
-                  // the following loop takes 4 microseconds (4 cycles)
-                  // per iteration, so execute it us/4 times
-                  // us is at least 4, divided by 4 gives us 1 (no zero delay bug)
-        us >>= 2; // us div 4, = 4 cycles
-
-        busy_loop(us);
+impl<SPEED> delay::DelayUs<u16> for Delay<SPEED>
+where
+    Delay<SPEED>: delay::DelayUs<u32>,
+{
+    fn delay_us(&mut self, us: u16) {
+        delay::DelayUs::<u32>::delay_us(self, us as u32);
     }
 }
 
-// ------------------------------------------------------------------------ }}}
-
 impl<SPEED> delay::DelayUs<u8> for Delay<SPEED>
 where
-    Delay<SPEED>: delay::DelayUs<u16>,
+    Delay<SPEED>: delay::DelayUs<u32>,
 {
     fn delay_us(&mut self, us: u8) {
-        delay::DelayUs::<u16>::delay_us(self, us as u16);
+        delay::DelayUs::<u32>::delay_us(self, us as u32);
     }
 }
 
-impl<SPEED> delay::DelayUs<u32> for Delay<SPEED>
+impl<SPEED> delay::DelayMs<u32> for Delay<SPEED>
 where
-    Delay<SPEED>: delay::DelayUs<u16>,
+    Delay<SPEED>: delay::DelayUs<u32>,
 {
-    fn delay_us(&mut self, us: u32) {
-        // TODO: Somehow fix the overhead induced by this loop
-        // This was previously a range-based for loop, but that would
-        // compile down to fairly poor code. This is slightly better,
-        // but still has some overhead and may not lead to cycle-accurate
-        // delays.
-        let iters = us >> 12;
-        let mut i = 0;
-        while i < iters {
-            delay::DelayUs::<u16>::delay_us(self, 0xfff);
-            i += 1;
+    fn delay_ms(&mut self, mut ms: u32) {
+        // Stay within the range of `delay_us()`, the overhead of this loop is negligible
+        while ms > 1_000_000 {
+            delay::DelayUs::<u32>::delay_us(self, 1_000_000_000);
+            ms -= 1_000_000;
         }
-        delay::DelayUs::<u16>::delay_us(self, (us & 0xfff) as u16);
+        delay::DelayUs::<u32>::delay_us(self, ms * 1000);
     }
 }
 
@@ -238,11 +135,87 @@ where
     }
 }
 
-impl <SPEED> delay::DelayMs<u8> for Delay<SPEED>
+impl<SPEED> delay::DelayMs<u8> for Delay<SPEED>
 where
-    Delay<SPEED>: delay::DelayMs<u16>,
+    Delay<SPEED>: delay::DelayUs<u32>,
 {
     fn delay_ms(&mut self, ms: u8) {
-        delay::DelayMs::<u16>::delay_ms(self, ms as u16);
+        delay::DelayUs::<u32>::delay_us(self, ms as u32 * 1000);
+    }
+}
+
+/// Split of a cycle count into a 32-bit loop and single instructions
+#[cfg(target_arch = "avr")]
+struct Cycles<const N: u32>;
+
+#[cfg(target_arch = "avr")]
+impl<const N: u32> Cycles<N> {
+    /// Iterations of the loop, which takes `6 * LOOPS + 3` cycles including its setup
+    const LOOPS: u32 = if N >= 9 { (N - 3) / 6 } else { 0 };
+    /// Cycles left for single instructions, at most 8
+    const REST: u32 = if N >= 9 { (N - 3) % 6 } else { N };
+
+    const LOOPS_0: u8 = Self::LOOPS as u8;
+    const LOOPS_1: u8 = (Self::LOOPS >> 8) as u8;
+    const LOOPS_2: u8 = (Self::LOOPS >> 16) as u8;
+    const LOOPS_3: u8 = (Self::LOOPS >> 24) as u8;
+}
+
+/// Busy-wait for exactly `CYCLES` CPU cycles.
+///
+/// The number of loop iterations and additional instructions is calculated at compile time, so
+/// there is no overhead apart from the loop itself.  This makes it suitable for bit-banging
+/// protocols with tight timing.  Interrupts which occur during the delay extend it, so disable
+/// them where exact timing matters.
+///
+/// # Example
+/// ```rust
+/// // 1.5µs at 16 MHz
+/// delay::delay_cycles::<24>();
+/// ```
+#[inline(always)]
+pub fn delay_cycles<const CYCLES: u32>() {
+    #[cfg(target_arch = "avr")]
+    {
+        if Cycles::<CYCLES>::LOOPS != 0 {
+            let (_b0, _b1, _b2, _b3): (u8, u8, u8, u8);
+            unsafe {
+                // 4 cycles of setup, then 6 cycles per iteration, minus 1 for the last branch
+                llvm_asm!("ldi $0, $4
+                           ldi $1, $5
+                           ldi $2, $6
+                           ldi $3, $7
+                        1: subi $0, 1
+                           sbci $1, 0
+                           sbci $2, 0
+                           sbci $3, 0
+                           brne 1b"
+                    : "=&d"(_b0), "=&d"(_b1), "=&d"(_b2), "=&d"(_b3)
+                    : "M"(Cycles::<CYCLES>::LOOPS_0), "M"(Cycles::<CYCLES>::LOOPS_1),
+                      "M"(Cycles::<CYCLES>::LOOPS_2), "M"(Cycles::<CYCLES>::LOOPS_3)
+                    :
+                    : "volatile"
+                );
+            }
+        }
+
+        unsafe {
+            if Cycles::<CYCLES>::REST & 1 != 0 {
+                llvm_asm!("nop" :::: "volatile");
+            }
+            // A relative jump to the next instruction takes 2 cycles in a single word
+            if Cycles::<CYCLES>::REST & 2 != 0 {
+                llvm_asm!("rjmp .+0" :::: "volatile");
+            }
+            if Cycles::<CYCLES>::REST & 4 != 0 {
+                llvm_asm!("rjmp .+0\n\trjmp .+0" :::: "volatile");
+            }
+            if Cycles::<CYCLES>::REST & 8 != 0 {
+                llvm_asm!("rjmp .+0\n\trjmp .+0\n\trjmp .+0\n\trjmp .+0" :::: "volatile");
+            }
+        }
     }
+
+    #[cfg(not(target_arch = "avr"))]
+    unimplemented!("Implementation is only available for avr targets!")
 }
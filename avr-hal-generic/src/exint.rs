@@ -0,0 +1,418 @@
+//! External Interrupts (INTn) and Pin-Change Interrupts (PCINTn)
+//!
+//! # External Interrupts
+//! Each external interrupt is tied to one specific pin.  Converting that pin into an `ExtInt`
+//! takes ownership of it, so the interrupt configuration cannot be changed behind the back of
+//! the code which handles it.  The pin can still be read through the interrupt handle.
+//!
+//! ```ignore
+//! let mut exint = dp.EXINT;
+//! let mut int0 = pins.d2.into_ext_interrupt(&mut exint, exint::Sense::Falling);
+//! int0.enable();
+//!
+//! unsafe { avr_device::interrupt::enable() };
+//!
+//! #[avr_device::interrupt(atmega328p)]
+//! fn INT0() {
+//!     // D2 went low
+//! }
+//! ```
+//!
+//! # Pin-Change Interrupts
+//! Any change on a pin which is watched by a pin-change bank triggers the bank's interrupt.  The
+//! pins of a bank share one interrupt, so the handler needs to find out which of them changed
+//! using `changes()`.
+//!
+//! The banks are split off the `EXINT` peripheral with `split()`, which hands out one token per
+//! bank.  The remaining `Exint` can still be used for the external interrupts.
+//!
+//! ```ignore
+//! let (mut exint, banks) = exint::split(dp.EXINT);
+//! let mut bank = exint::PinChange2::new(banks.pin_change2);
+//! bank.watch(&button_a);
+//! bank.watch(&button_b);
+//! bank.enable();
+//!
+//! // In the PCINT2 interrupt handler
+//! let changes = bank.changes();
+//! if changes.contains(&button_a) {
+//!     // ...
+//! }
+//! ```
+
+/// Condition which triggers an external interrupt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sense {
+    /// The interrupt is triggered for as long as the pin is held low.
+    ///
+    /// No interrupt flag is set in this mode, so `ExtInt::is_pending()` always returns `false`.
+    LowLevel,
+    /// Any logical change on the pin triggers the interrupt.
+    AnyEdge,
+    /// A falling edge on the pin triggers the interrupt.
+    Falling,
+    /// A rising edge on the pin triggers the interrupt.
+    Rising,
+}
+
+impl Sense {
+    /// Value of the `ISCn` bits for this sense mode.
+    #[doc(hidden)]
+    pub fn isc_bits(self) -> u8 {
+        match self {
+            Sense::LowLevel => 0b00,
+            Sense::AnyEdge => 0b01,
+            Sense::Falling => 0b10,
+            Sense::Rising => 0b11,
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! impl_exint {
+    (
+        pub struct $ExtInt:ident {
+            peripheral: $EXINT:ty,
+            interrupts: {$(
+                $port:ident::$PXi:ident: $INT:ident {
+                    sense: $SENSE:ty => $sense_reg:ident[$sense_shift:expr],
+                    enable: $mask_reg:ident[$mask_bit:expr],
+                    flag: $flag_reg:ident[$flag_bit:expr],
+                },
+            )+},
+        }
+    ) => {
+        pub use $crate::exint::*;
+
+        /// External interrupt on pin `PIN`
+        ///
+        /// Created using the `into_ext_interrupt()` method of the pin.  The interrupt is disabled
+        /// initially, call [`enable()`] to start receiving it.
+        ///
+        /// [`enable()`]: #method.enable
+        pub struct $ExtInt<PIN> {
+            pin: PIN,
+        }
+
+        $(
+            impl<MODE> $port::$PXi<MODE> {
+                /// Use this pin as an external interrupt (`INTn`).
+                ///
+                /// The interrupt is configured for `sense` but not yet enabled.
+                pub fn into_ext_interrupt(
+                    self,
+                    _exint: &mut $EXINT,
+                    sense: Sense,
+                ) -> $ExtInt<$port::$PXi<MODE>> {
+                    let mut int = $ExtInt { pin: self };
+                    int.disable();
+                    int.set_sense(sense);
+                    int
+                }
+            }
+
+            impl<MODE> $ExtInt<$port::$PXi<MODE>> {
+                /// Change the condition which triggers the interrupt.
+                ///
+                /// Changing the sense mode can set the interrupt flag spuriously, so the
+                /// interrupt is disabled meanwhile and the flag is cleared afterwards.
+                pub fn set_sense(&mut self, sense: Sense) {
+                    // SAFETY: The registers are shared with the other external interrupts, so
+                    // the read-modify-write sequences are wrapped in a critical section.
+                    $crate::avr_device::interrupt::free(|_| {
+                        let exint = unsafe { &*<$EXINT>::ptr() };
+                        let enabled = exint.$mask_reg.read().bits() & (1 << $mask_bit) != 0;
+                        exint.$mask_reg.modify(|r, w| unsafe {
+                            w.bits(r.bits() & !(1 << $mask_bit))
+                        });
+
+                        let control = unsafe { &*<$SENSE>::ptr() };
+                        control.$sense_reg.modify(|r, w| unsafe {
+                            w.bits(
+                                (r.bits() & !(0b11 << $sense_shift))
+                                    | (sense.isc_bits() << $sense_shift),
+                            )
+                        });
+
+                        self.clear();
+                        if enabled {
+                            exint.$mask_reg.modify(|r, w| unsafe {
+                                w.bits(r.bits() | (1 << $mask_bit))
+                            });
+                        }
+                    })
+                }
+
+                /// Enable the interrupt.
+                pub fn enable(&mut self) {
+                    // SAFETY: See `set_sense()`.
+                    $crate::avr_device::interrupt::free(|_| {
+                        let exint = unsafe { &*<$EXINT>::ptr() };
+                        exint.$mask_reg.modify(|r, w| unsafe {
+                            w.bits(r.bits() | (1 << $mask_bit))
+                        });
+                    })
+                }
+
+                /// Disable the interrupt.
+                ///
+                /// The interrupt flag is still set when the configured condition occurs.
+                pub fn disable(&mut self) {
+                    // SAFETY: See `set_sense()`.
+                    $crate::avr_device::interrupt::free(|_| {
+                        let exint = unsafe { &*<$EXINT>::ptr() };
+                        exint.$mask_reg.modify(|r, w| unsafe {
+                            w.bits(r.bits() & !(1 << $mask_bit))
+                        });
+                    })
+                }
+
+                /// Check whether the interrupt condition occurred and has not been handled yet.
+                ///
+                /// The flag is cleared by hardware when the interrupt handler is executed.
+                pub fn is_pending(&self) -> bool {
+                    let exint = unsafe { &*<$EXINT>::ptr() };
+                    exint.$flag_reg.read().bits() & (1 << $flag_bit) != 0
+                }
+
+                /// Clear the interrupt flag.
+                pub fn clear(&mut self) {
+                    // SAFETY: Flags are cleared by writing a one, writing zeros to the flags of
+                    // the other interrupts has no effect.
+                    let exint = unsafe { &*<$EXINT>::ptr() };
+                    exint.$flag_reg.write(|w| unsafe { w.bits(1 << $flag_bit) });
+                }
+
+                /// Access the pin, e.g. to read its current level.
+                pub fn pin(&self) -> &$port::$PXi<MODE> {
+                    &self.pin
+                }
+
+                /// Disable the interrupt and return the pin.
+                pub fn release(mut self) -> $port::$PXi<MODE> {
+                    self.disable();
+                    self.pin
+                }
+            }
+        )+
+    };
+}
+
+/// Pins which can trigger the pin-change interrupt of bank `BANK`
+pub trait PinChangePin<BANK> {
+    /// Bit of this pin in the bank's mask register.
+    #[doc(hidden)]
+    const MASK: u8;
+}
+
+/// Set of pins of bank `BANK` which changed their level
+///
+/// Returned by the `changes()` method of the pin-change bank.
+pub struct Changes<BANK> {
+    bits: u8,
+    _bank: core::marker::PhantomData<BANK>,
+}
+
+// Deriving would require `BANK: Copy`, which the bank types are not.
+impl<BANK> Clone for Changes<BANK> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<BANK> Copy for Changes<BANK> {}
+
+impl<BANK> Changes<BANK> {
+    #[doc(hidden)]
+    pub fn new(bits: u8) -> Self {
+        Changes {
+            bits,
+            _bank: core::marker::PhantomData,
+        }
+    }
+
+    /// Check whether `pin` changed.
+    pub fn contains<PIN: PinChangePin<BANK>>(&self, _pin: &PIN) -> bool {
+        self.bits & PIN::MASK != 0
+    }
+
+    /// Whether no watched pin changed.
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Changed pins as a bitmask in the order of the bank's mask register.
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+}
+
+#[macro_export]
+macro_rules! impl_pin_change {
+    (
+        peripheral: $EXINT:ty,
+        banks: {$(
+            $(#[$bank_attr:meta])*
+            pub struct $PinChange:ident {
+                enable: $enable_reg:ident[$enable_bit:expr],
+                flag: $flag_reg:ident[$flag_bit:expr],
+                mask: $mask_reg:ident,
+                state: || $state:block,
+                pins: {$(
+                    $port:ident::$PXi:ident: $bit:expr,
+                )+},
+            }
+        )+},
+    ) => {$crate::paste::paste! {
+        pub use $crate::exint::*;
+
+        /// The `EXINT` peripheral after the pin-change banks were split off
+        ///
+        /// Dereferences to the peripheral, so it can still be passed to `into_ext_interrupt()`.
+        pub struct Exint {
+            exint: $EXINT,
+        }
+
+        impl core::ops::Deref for Exint {
+            type Target = $EXINT;
+
+            fn deref(&self) -> &$EXINT {
+                &self.exint
+            }
+        }
+
+        impl core::ops::DerefMut for Exint {
+            fn deref_mut(&mut self) -> &mut $EXINT {
+                &mut self.exint
+            }
+        }
+
+        /// Tokens for the pin-change banks, each of which can only be used by one bank driver
+        pub struct PinChangeBanks {
+            $(
+                pub [<$PinChange:snake>]: [<$PinChange Token>],
+            )+
+        }
+
+        /// Split the pin-change banks off the `EXINT` peripheral.
+        pub fn split(exint: $EXINT) -> (Exint, PinChangeBanks) {
+            (
+                Exint { exint },
+                PinChangeBanks {
+                    $(
+                        [<$PinChange:snake>]: [<$PinChange Token>] { _private: () },
+                    )+
+                },
+            )
+        }
+
+        $(
+            /// Token for a pin-change bank, see [`split()`]
+            pub struct [<$PinChange Token>] {
+                _private: (),
+            }
+
+            $(#[$bank_attr])*
+            pub struct $PinChange {
+                last: u8,
+                token: [<$PinChange Token>],
+            }
+
+            impl $PinChange {
+                /// Take control of this pin-change bank.
+                ///
+                /// No pins are watched and the interrupt is disabled initially.
+                pub fn new(token: [<$PinChange Token>]) -> Self {
+                    let mut bank = $PinChange { last: 0, token };
+                    bank.disable();
+                    // SAFETY: The mask register is exclusively used by this bank.
+                    unsafe { &*<$EXINT>::ptr() }.$mask_reg.write(|w| unsafe { w.bits(0) });
+                    bank.clear();
+                    bank.last = bank.state();
+                    bank
+                }
+
+                /// Current level of all pins in this bank as a bitmask.
+                pub fn state(&self) -> u8 $state
+
+                /// Let `pin` trigger the pin-change interrupt of this bank.
+                pub fn watch<PIN: PinChangePin<Self>>(&mut self, _pin: &PIN) {
+                    let state = self.state();
+                    self.last = (self.last & !PIN::MASK) | (state & PIN::MASK);
+                    unsafe { &*<$EXINT>::ptr() }.$mask_reg.modify(|r, w| unsafe {
+                        w.bits(r.bits() | PIN::MASK)
+                    });
+                }
+
+                /// Stop `pin` from triggering the pin-change interrupt.
+                pub fn unwatch<PIN: PinChangePin<Self>>(&mut self, _pin: &PIN) {
+                    unsafe { &*<$EXINT>::ptr() }.$mask_reg.modify(|r, w| unsafe {
+                        w.bits(r.bits() & !PIN::MASK)
+                    });
+                }
+
+                /// Compute which watched pins changed since the last call.
+                ///
+                /// Call this from the interrupt handler to find out which pin(s) triggered it.
+                /// Pulses which are shorter than the interrupt latency can go unnoticed.
+                pub fn changes(&mut self) -> Changes<Self> {
+                    let mask = unsafe { &*<$EXINT>::ptr() }.$mask_reg.read().bits();
+                    let state = self.state();
+                    let changed = (state ^ self.last) & mask;
+                    self.last = state;
+                    Changes::new(changed)
+                }
+
+                /// Enable the pin-change interrupt of this bank.
+                pub fn enable(&mut self) {
+                    // SAFETY: The enable register is shared with the other banks, so the
+                    // read-modify-write sequence is wrapped in a critical section.
+                    $crate::avr_device::interrupt::free(|_| {
+                        let exint = unsafe { &*<$EXINT>::ptr() };
+                        exint.$enable_reg.modify(|r, w| unsafe {
+                            w.bits(r.bits() | (1 << $enable_bit))
+                        });
+                    })
+                }
+
+                /// Disable the pin-change interrupt of this bank.
+                pub fn disable(&mut self) {
+                    // SAFETY: See `enable()`.
+                    $crate::avr_device::interrupt::free(|_| {
+                        let exint = unsafe { &*<$EXINT>::ptr() };
+                        exint.$enable_reg.modify(|r, w| unsafe {
+                            w.bits(r.bits() & !(1 << $enable_bit))
+                        });
+                    })
+                }
+
+                /// Check whether a watched pin changed and the interrupt has not been handled yet.
+                pub fn is_pending(&self) -> bool {
+                    let exint = unsafe { &*<$EXINT>::ptr() };
+                    exint.$flag_reg.read().bits() & (1 << $flag_bit) != 0
+                }
+
+                /// Clear the interrupt flag.
+                pub fn clear(&mut self) {
+                    // SAFETY: Flags are cleared by writing a one, writing zeros to the other
+                    // flags has no effect.
+                    let exint = unsafe { &*<$EXINT>::ptr() };
+                    exint.$flag_reg.write(|w| unsafe { w.bits(1 << $flag_bit) });
+                }
+
+                /// Disable the interrupt, stop watching all pins and return the token.
+                pub fn release(mut self) -> [<$PinChange Token>] {
+                    self.disable();
+                    unsafe { &*<$EXINT>::ptr() }.$mask_reg.write(|w| unsafe { w.bits(0) });
+                    self.token
+                }
+            }
+
+            $(
+                impl<MODE> PinChangePin<$PinChange> for $port::$PXi<MODE> {
+                    const MASK: u8 = 1 << $bit;
+                }
+            )+
+        )+
+    }};
+}
@@ -13,6 +13,9 @@ pub extern crate ufmt;
 pub extern crate paste;
 #[doc(hidden)]
 pub extern crate avr_device;
+#[cfg(feature = "embedded-time")]
+#[doc(hidden)]
+pub extern crate embedded_time;
 
 pub mod clock;
 pub mod delay;
@@ -21,8 +24,17 @@ pub mod usart;
 pub mod i2c;
 pub mod spi;
 pub mod adc;
+pub mod comparator;
+pub mod exint;
 pub mod pwm;
+pub mod timer;
+pub mod tone;
+pub mod capture;
+pub mod servo;
+pub mod systime;
+pub mod rtc;
 pub mod wdt;
+pub mod sdcard;
 
 /// Prelude containing all HAL traits
 pub mod prelude {
@@ -31,6 +43,8 @@ pub mod prelude {
     pub use hal::digital::v2::InputPin as _;
     pub use hal::digital::v2::StatefulOutputPin as _;
     pub use hal::digital::v2::ToggleableOutputPin as _;
+    pub use crate::port::DynamicPin as _;
+    pub use crate::timer::TimeExt as _;
     pub use void::ResultVoidExt as _;
     pub use void::ResultVoidErrExt as _;
     pub use ufmt::uWrite as _;
@@ -96,6 +96,40 @@
 //! pd2.is_low().void_unwrap();
 //! ```
 //!
+//! ### Dynamic Mode
+//! Pins in dynamic mode (i.e. where `MODE` = `mode::Dynamic`) can be switched between input and
+//! output at runtime, without changing their type.  The output methods return an error when the
+//! pin is currently an input.  See [`DynamicPin`] for details.
+//!
+//! ```ignore
+//! let mut pd2 = pd2.into_dynamic();
+//!
+//! pd2.set_mode(DynamicMode::Output);
+//! pd2.set_low().unwrap();
+//!
+//! pd2.set_mode(DynamicMode::FloatingInput);
+//! pd2.is_high().void_unwrap();
+//! ```
+//!
+//! ### Port Groups
+//! Pins of the same port can be combined into a `Group`, which reads or writes all of them in a
+//! single register access.  This is useful for parallel buses where glitches between individual
+//! pin writes are not acceptable:
+//!
+//! ```ignore
+//! let pd4 = portd.pd4.into_output(&mut portd.ddr);
+//! let pd5 = portd.pd5.into_output(&mut portd.ddr);
+//! let pd6 = portd.pd6.into_output(&mut portd.ddr);
+//!
+//! let mut bus = portd::Group::new((pd4, pd5, pd6));
+//!
+//! // Bits correspond to the port bits, bits outside the group are ignored
+//! bus.write(0b0101_0000);
+//! ```
+//!
+//! When a group contains all pins of its port, `write_byte()` and `read_byte()` access the whole
+//! port register directly.
+//!
 //! ### Other Modes
 //! Apart from input and output, certain pins can have other functionality associated with them.
 //! E.g. some pins can be used for PWM output, others as ADC inputs.  For those pins, specific
@@ -127,6 +161,14 @@
 //!
 //! let pins: [Pin<mode::Output>; 2] = [pd2.downgrade(), pd3.downgrade()];
 //! ```
+//!
+//! PWM outputs and analog inputs are type-erased differently, as they need to remember their
+//! timer or ADC channel:
+//!
+//! ```ignore
+//! let leds: [pwm::PwmOutput; 2] = [pd5_pwm.into(), pd3_pwm.into()];
+//! let inputs: [adc::AnalogPin; 2] = [pc0_analog.into(), pc1_analog.into()];
+//! ```
 
 /// IO Modes
 pub mod mode {
@@ -149,13 +191,17 @@ pub mod mode {
     }
     /// Pin configured in open drain mode.
     pub struct TriState;
+    /// Pin whose mode is changed at runtime, see [`DynamicPin`](super::DynamicPin).
+    pub struct Dynamic;
 
     impl private::Unimplementable for Output {}
     impl<M: InputMode> private::Unimplementable for Input<M> {}
     impl private::Unimplementable for TriState {}
+    impl private::Unimplementable for Dynamic {}
     impl DigitalIO for Output {}
     impl<M: InputMode> DigitalIO for Input<M> {}
     impl DigitalIO for TriState {}
+    impl DigitalIO for Dynamic {}
 
     /// Pin input configured **without** internal pull-up
     pub struct Floating;
@@ -172,6 +218,126 @@ pub mod mode {
     }
 }
 
+/// Runtime mode of a [`DynamicPin`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicMode {
+    /// Digital output.  The pin is driven to the level last set.
+    Output,
+    /// Digital input **without** the internal pull-up.
+    FloatingInput,
+    /// Digital input with the internal pull-up enabled.
+    PullUpInput,
+}
+
+/// Error returned when a [`DynamicPin`] is not in the right mode for an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeError {
+    /// Mode the pin was in when the operation was attempted.
+    pub mode: DynamicMode,
+}
+
+impl ModeError {
+    #[doc(hidden)]
+    pub fn check_output(mode: DynamicMode) -> Result<(), ModeError> {
+        match mode {
+            DynamicMode::Output => Ok(()),
+            mode => Err(ModeError { mode }),
+        }
+    }
+}
+
+/// Pin whose direction and pull-up can be changed at runtime
+///
+/// Pins are converted into dynamic pins using `.into_dynamic()`.  This is useful where the
+/// typestate of a pin cannot be tracked statically, e.g. for bit-banged bidirectional protocols
+/// or when charlieplexing.  Both specific pins (`PD2<mode::Dynamic>`) and downgraded pins
+/// (`Pin<mode::Dynamic>`) implement this trait.
+///
+/// The mode is stored in the hardware registers only, so it is always in sync with the pin's
+/// actual configuration.  The output methods (`set_high()`, `set_low()`, `toggle()`, ...)
+/// return a [`ModeError`] if the pin is not an output, as writing the output level of an input
+/// pin would change its pull-up instead.  Reading the pin level works in all modes.
+///
+/// # Example
+/// ```ignore
+/// let mut pin = portd.pd2.into_dynamic();
+///
+/// pin.set_mode(DynamicMode::Output);
+/// pin.set_low()?;
+///
+/// pin.set_mode(DynamicMode::PullUpInput);
+/// let level = pin.is_high().void_unwrap();
+///
+/// // Fails, the pin is an input
+/// assert!(pin.set_high().is_err());
+/// ```
+pub trait DynamicPin:
+    crate::hal::digital::v2::OutputPin<Error = ModeError>
+    + crate::hal::digital::v2::InputPin<Error = crate::void::Void>
+{
+    /// Change the mode of this pin.
+    ///
+    /// When switching to [`DynamicMode::Output`], the output level is taken from the previous
+    /// mode: A pin that was a pull-up input starts out high, a floating input starts out low.
+    fn set_mode(&mut self, mode: DynamicMode);
+
+    /// Current mode of this pin.
+    fn mode(&self) -> DynamicMode;
+}
+
+/// Set of pins of port `PORT` which are all in mode `MODE`
+///
+/// Implemented for single pins and for tuples of up to 8 pins.  Used to build a port `Group`.
+pub trait GroupPins<PORT, MODE> {
+    /// Bits of the pins in the port registers.
+    #[doc(hidden)]
+    const MASK: u8;
+}
+
+macro_rules! impl_group_pins_tuple {
+    ($($T:ident),+) => {
+        impl<PORT, MODE, $($T: GroupPins<PORT, MODE>),+> GroupPins<PORT, MODE> for ($($T,)+) {
+            const MASK: u8 = $($T::MASK)|+;
+        }
+    };
+}
+
+impl_group_pins_tuple!(A, B);
+impl_group_pins_tuple!(A, B, C);
+impl_group_pins_tuple!(A, B, C, D);
+impl_group_pins_tuple!(A, B, C, D, E);
+impl_group_pins_tuple!(A, B, C, D, E, F);
+impl_group_pins_tuple!(A, B, C, D, E, F, G);
+impl_group_pins_tuple!(A, B, C, D, E, F, G, H);
+
+/// Error when taking a board pin by its number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinNumberError {
+    /// The board has no pin with this number.
+    InvalidNumber,
+    /// The pin was already taken.
+    AlreadyTaken,
+}
+
+/// Number of a board pin named `<prefix><number>`, e.g. `d13` or `a0`.
+#[doc(hidden)]
+pub const fn board_pin_number(name: &str, prefix: u8) -> Option<u8> {
+    let name = name.as_bytes();
+    if name.len() < 2 || name[0] != prefix {
+        return None;
+    }
+    let mut number: u8 = 0;
+    let mut i = 1;
+    while i < name.len() {
+        if name[i] < b'0' || name[i] > b'9' {
+            return None;
+        }
+        number = number * 10 + (name[i] - b'0');
+        i += 1;
+    }
+    Some(number)
+}
+
 /// Create a generic pin to be used for downgrading
 #[macro_export]
 macro_rules! impl_generic_pin {
@@ -194,7 +360,9 @@ macro_rules! impl_generic_pin {
             ///
             /// The generic pin implements all the same digital IO methods (`set_high()`,
             /// `set_low()`, `is_high()`, `is_low()`, etc.) as specific pin types, except that it
-            /// cannot be used for pin-specific functions (e.g. PWM, ADC Channel).
+            /// cannot be used for pin-specific functions.  For PWM outputs and analog inputs,
+            /// convert the pin into a `pwm::PwmOutput` or `adc::AnalogPin` with `.into()`
+            /// instead.
             ///
             /// [1]: ../../avr_hal_generic/port/index.html
             pub enum $GenericPin<MODE> {
@@ -354,6 +522,144 @@ macro_rules! impl_generic_pin {
                     })
                 }
             }
+
+            // Dynamic mode implementations --------------------------- {{{
+            impl $crate::port::DynamicPin for $GenericPin<mode::Dynamic> {
+                fn set_mode(&mut self, mode: $crate::port::DynamicMode) {
+                    // SAFETY: The other pins of the port might be changed from an interrupt, so
+                    // the read-modify-write sequences are wrapped in a critical section.
+                    $crate::avr_device::interrupt::free(|_| match self {
+                        $(
+                            $GenericPin::$PortEnum(i, _) => unsafe {
+                                let port = &*<$PORTX>::ptr();
+                                match mode {
+                                    $crate::port::DynamicMode::Output => {
+                                        port.$reg_ddr.modify(|r, w| w.bits(r.bits() | (1 << *i)));
+                                    }
+                                    $crate::port::DynamicMode::FloatingInput => {
+                                        port.$reg_ddr.modify(|r, w| w.bits(r.bits() & !(1 << *i)));
+                                        port.$reg_port.modify(|r, w| w.bits(r.bits() & !(1 << *i)));
+                                    }
+                                    $crate::port::DynamicMode::PullUpInput => {
+                                        port.$reg_ddr.modify(|r, w| w.bits(r.bits() & !(1 << *i)));
+                                        port.$reg_port.modify(|r, w| w.bits(r.bits() | (1 << *i)));
+                                    }
+                                }
+                            },
+                        )+
+                    })
+                }
+
+                fn mode(&self) -> $crate::port::DynamicMode {
+                    let (ddr, port) = match self {
+                        $(
+                            $GenericPin::$PortEnum(i, _) => unsafe {
+                                let port = &*<$PORTX>::ptr();
+                                (
+                                    port.$reg_ddr.read().bits() & (1 << *i) != 0,
+                                    port.$reg_port.read().bits() & (1 << *i) != 0,
+                                )
+                            },
+                        )+
+                    };
+                    match (ddr, port) {
+                        (true, _) => $crate::port::DynamicMode::Output,
+                        (false, false) => $crate::port::DynamicMode::FloatingInput,
+                        (false, true) => $crate::port::DynamicMode::PullUpInput,
+                    }
+                }
+            }
+
+            impl digital::OutputPin for $GenericPin<mode::Dynamic> {
+                type Error = $crate::port::ModeError;
+
+                fn set_high(&mut self) -> Result<(), Self::Error> {
+                    $crate::port::ModeError::check_output($crate::port::DynamicPin::mode(self))?;
+                    match self {
+                        $(
+                            $GenericPin::$PortEnum(i, _) => unsafe {
+                                (*<$PORTX>::ptr())
+                                    .$reg_port
+                                    .modify(|r, w| {
+                                        w.bits(r.bits() | (1 << *i))
+                                    })
+                            },
+                        )+
+                    }
+                    Ok(())
+                }
+
+                fn set_low(&mut self) -> Result<(), Self::Error> {
+                    $crate::port::ModeError::check_output($crate::port::DynamicPin::mode(self))?;
+                    match self {
+                        $(
+                            $GenericPin::$PortEnum(i, _) => unsafe {
+                                (*<$PORTX>::ptr())
+                                    .$reg_port
+                                    .modify(|r, w| {
+                                        w.bits(r.bits() & !(1 << *i))
+                                    })
+                            },
+                        )+
+                    }
+                    Ok(())
+                }
+            }
+
+            impl digital::StatefulOutputPin for $GenericPin<mode::Dynamic> {
+                fn is_set_high(&self) -> Result<bool, Self::Error> {
+                    $crate::port::ModeError::check_output($crate::port::DynamicPin::mode(self))?;
+                    Ok(match self {
+                        $(
+                            $GenericPin::$PortEnum(i, _) => unsafe {
+                                (*<$PORTX>::ptr())
+                                    .$reg_port.read().bits() & (1 << *i) != 0
+                            },
+                        )+
+                    })
+                }
+
+                fn is_set_low(&self) -> Result<bool, Self::Error> {
+                    self.is_set_high().map(|b| !b)
+                }
+            }
+
+            impl digital::ToggleableOutputPin for $GenericPin<mode::Dynamic> {
+                type Error = $crate::port::ModeError;
+
+                fn toggle(&mut self) -> Result<(), Self::Error> {
+                    $crate::port::ModeError::check_output($crate::port::DynamicPin::mode(self))?;
+                    match self {
+                        $(
+                            $GenericPin::$PortEnum(i, _) => unsafe {
+                                (*<$PORTX>::ptr())
+                                    .$reg_pin
+                                    .write(|w| w.bits(1 << *i))
+                            },
+                        )+
+                    }
+                    Ok(())
+                }
+            }
+
+            impl digital::InputPin for $GenericPin<mode::Dynamic> {
+                type Error = Void;
+
+                fn is_high(&self) -> Result<bool, Self::Error> {
+                    Ok(match self {
+                        $(
+                            $GenericPin::$PortEnum(i, _) => unsafe {
+                                (*<$PORTX>::ptr())
+                                    .$reg_pin.read().bits() & (1 << *i) != 0
+                            }
+                        )+
+                    })
+                }
+
+                fn is_low(&self) -> Result<bool, Self::Error> {
+                    self.is_high().map(|b| !b)
+                }
+            }
             // -------------------------------------------------------- }}}
         }
         pub use self::generic_pin::$GenericPin;
@@ -559,6 +865,16 @@ macro_rules! impl_port {
                         }
                         $PXi { _mode: marker::PhantomData }
                     }
+
+                    /// Make this pin a dynamic pin whose mode can be changed at runtime.
+                    ///
+                    /// The pin keeps its current configuration, so unlike the other conversions
+                    /// this does not need the `DDR`.  See [`DynamicPin`][1] for details.
+                    ///
+                    /// [1]: ../../../avr_hal_generic/port/trait.DynamicPin.html
+                    pub fn into_dynamic(self) -> $PXi<mode::Dynamic> {
+                        $PXi { _mode: marker::PhantomData }
+                    }
                 }
                 // -------------------------------------------------------- }}}
 
@@ -668,7 +984,215 @@ macro_rules! impl_port {
                 }
 
                 // -------------------------------------------------------- }}}
+
+                // Dynamic mode implementations --------------------------- {{{
+                impl $crate::port::DynamicPin for $PXi<mode::Dynamic> {
+                    fn set_mode(&mut self, mode: $crate::port::DynamicMode) {
+                        // SAFETY: The other pins of the port might be changed from an interrupt,
+                        // so the read-modify-write sequences are wrapped in a critical section.
+                        $crate::avr_device::interrupt::free(|_| unsafe {
+                            let port = &*<$PORTX>::ptr();
+                            match mode {
+                                $crate::port::DynamicMode::Output => {
+                                    port.$reg_ddr.modify(|r, w| w.bits(r.bits() | (1 << $i)));
+                                }
+                                $crate::port::DynamicMode::FloatingInput => {
+                                    port.$reg_ddr.modify(|r, w| w.bits(r.bits() & !(1 << $i)));
+                                    port.$reg_port.modify(|r, w| w.bits(r.bits() & !(1 << $i)));
+                                }
+                                $crate::port::DynamicMode::PullUpInput => {
+                                    port.$reg_ddr.modify(|r, w| w.bits(r.bits() & !(1 << $i)));
+                                    port.$reg_port.modify(|r, w| w.bits(r.bits() | (1 << $i)));
+                                }
+                            }
+                        })
+                    }
+
+                    fn mode(&self) -> $crate::port::DynamicMode {
+                        let port = unsafe { &*<$PORTX>::ptr() };
+                        let ddr = port.$reg_ddr.read().bits() & (1 << $i) != 0;
+                        let pull_up = port.$reg_port.read().bits() & (1 << $i) != 0;
+                        match (ddr, pull_up) {
+                            (true, _) => $crate::port::DynamicMode::Output,
+                            (false, false) => $crate::port::DynamicMode::FloatingInput,
+                            (false, true) => $crate::port::DynamicMode::PullUpInput,
+                        }
+                    }
+                }
+
+                impl digital::OutputPin for $PXi<mode::Dynamic> {
+                    type Error = $crate::port::ModeError;
+
+                    fn set_high(&mut self) -> Result<(), Self::Error> {
+                        $crate::port::ModeError::check_output($crate::port::DynamicPin::mode(self))?;
+                        unsafe {
+                            (*<$PORTX>::ptr()).$reg_port.modify(|r, w| {
+                                w.bits(r.bits() | (1 << $i))
+                            });
+                        }
+                        Ok(())
+                    }
+
+                    fn set_low(&mut self) -> Result<(), Self::Error> {
+                        $crate::port::ModeError::check_output($crate::port::DynamicPin::mode(self))?;
+                        unsafe {
+                            (*<$PORTX>::ptr()).$reg_port.modify(|r, w| {
+                                w.bits(r.bits() & !(1 << $i))
+                            });
+                        }
+                        Ok(())
+                    }
+                }
+
+                impl digital::StatefulOutputPin for $PXi<mode::Dynamic> {
+                    fn is_set_high(&self) -> Result<bool, Self::Error> {
+                        $crate::port::ModeError::check_output($crate::port::DynamicPin::mode(self))?;
+                        Ok(unsafe {
+                            (*<$PORTX>::ptr()).$reg_port.read().bits()
+                        } & (1 << $i) != 0)
+                    }
+
+                    fn is_set_low(&self) -> Result<bool, Self::Error> {
+                        self.is_set_high().map(|b| !b)
+                    }
+                }
+
+                impl digital::ToggleableOutputPin for $PXi<mode::Dynamic> {
+                    type Error = $crate::port::ModeError;
+
+                    fn toggle(&mut self) -> Result<(), Self::Error> {
+                        $crate::port::ModeError::check_output($crate::port::DynamicPin::mode(self))?;
+                        unsafe {
+                            (*<$PORTX>::ptr()).$reg_pin.write(|w| {
+                                w.bits(1 << $i)
+                            });
+                        }
+                        Ok(())
+                    }
+                }
+
+                impl digital::InputPin for $PXi<mode::Dynamic> {
+                    type Error = Void;
+
+                    fn is_high(&self) -> Result<bool, Self::Error> {
+                        Ok(unsafe {
+                            (*<$PORTX>::ptr()).$reg_pin.read().bits()
+                        } & (1 << $i) != 0)
+                    }
+
+                    fn is_low(&self) -> Result<bool, Self::Error> {
+                        Ok(unsafe {
+                            (*<$PORTX>::ptr()).$reg_pin.read().bits()
+                        } & (1 << $i) == 0)
+                    }
+                }
+                // -------------------------------------------------------- }}}
+
+                impl<MODE> $crate::port::GroupPins<$PORTX, MODE> for $PXi<MODE> {
+                    const MASK: u8 = 1 << $i;
+                }
             )+
+
+            // Port group implementation ------------------------------ {{{
+            /// Group of pins of this port which are accessed together.
+            ///
+            /// All pins of the group are read or written in a single register access, so there
+            /// are no glitches between the individual pins.  Bits of values correspond to the
+            /// bits of the port, i.e. bit 2 is the level of `Px2`.  Bits of pins which are not
+            /// part of the group are ignored.
+            ///
+            /// See the [general Digital IO documentation][1] for an example.
+            ///
+            /// [1]: ../../../avr_hal_generic/port/index.html#port-groups
+            pub struct Group<PINS, MODE> {
+                pins: PINS,
+                _mode: marker::PhantomData<MODE>,
+            }
+
+            impl<PINS: $crate::port::GroupPins<$PORTX, MODE>, MODE> Group<PINS, MODE> {
+                /// Group a single pin or a tuple of pins which are all in the same mode.
+                pub fn new(pins: PINS) -> Self {
+                    Group { pins, _mode: marker::PhantomData }
+                }
+
+                /// Bits of the port which belong to this group.
+                pub fn mask(&self) -> u8 {
+                    PINS::MASK
+                }
+
+                /// Read the levels of all pins of the group.
+                pub fn read(&self) -> u8 {
+                    unsafe { (*<$PORTX>::ptr()).$reg_pin.read().bits() & PINS::MASK }
+                }
+
+                /// Dissolve the group and return its pins.
+                pub fn release(self) -> PINS {
+                    self.pins
+                }
+            }
+
+            impl<PINS: $crate::port::GroupPins<$PORTX, mode::Output>> Group<PINS, mode::Output> {
+                /// Set the levels of all pins of the group at once.
+                pub fn write(&mut self, value: u8) {
+                    // SAFETY: The other pins of the port might be changed from an interrupt, so
+                    // the read-modify-write sequence is wrapped in a critical section.
+                    $crate::avr_device::interrupt::free(|_| unsafe {
+                        (*<$PORTX>::ptr()).$reg_port.modify(|r, w| {
+                            w.bits((r.bits() & !PINS::MASK) | (value & PINS::MASK))
+                        });
+                    })
+                }
+
+                /// Set the pins selected by `bits` high, leaving the others unchanged.
+                pub fn set_bits(&mut self, bits: u8) {
+                    // SAFETY: See `write()`.
+                    $crate::avr_device::interrupt::free(|_| unsafe {
+                        (*<$PORTX>::ptr()).$reg_port.modify(|r, w| {
+                            w.bits(r.bits() | (bits & PINS::MASK))
+                        });
+                    })
+                }
+
+                /// Set the pins selected by `bits` low, leaving the others unchanged.
+                pub fn clear_bits(&mut self, bits: u8) {
+                    // SAFETY: See `write()`.
+                    $crate::avr_device::interrupt::free(|_| unsafe {
+                        (*<$PORTX>::ptr()).$reg_port.modify(|r, w| {
+                            w.bits(r.bits() & !(bits & PINS::MASK))
+                        });
+                    })
+                }
+
+                /// Toggle the pins selected by `bits`.
+                pub fn toggle_bits(&mut self, bits: u8) {
+                    // Writing ones to PINx toggles the respective bits in a single access
+                    unsafe {
+                        (*<$PORTX>::ptr()).$reg_pin.write(|w| w.bits(bits & PINS::MASK));
+                    }
+                }
+            }
+
+            impl<MODE> Group<($($PXi<MODE>,)+), MODE> {
+                /// Read the whole port in a single access.
+                ///
+                /// Only available when the group contains all pins of the port.
+                pub fn read_byte(&self) -> u8 {
+                    unsafe { (*<$PORTX>::ptr()).$reg_pin.read().bits() }
+                }
+            }
+
+            impl Group<($($PXi<mode::Output>,)+), mode::Output> {
+                /// Write the whole port in a single access.
+                ///
+                /// Only available when the group contains all pins of the port.  This is faster
+                /// than `write()` because the register does not need to be read first.
+                pub fn write_byte(&mut self, value: u8) {
+                    unsafe {
+                        (*<$PORTX>::ptr()).$reg_port.write(|w| w.bits(value));
+                    }
+                }
+            }
+            // -------------------------------------------------------- }}}
         }
     };
 }
@@ -689,7 +1213,7 @@ macro_rules! impl_board_pins {
         pub struct $Pins:ident {
             $(
                 $(#[$pin_attr:meta])*
-                pub $name:ident: $pinport:ident::$pin:ident::$Pin:ident,
+                pub $name:ident: $pinport:ident::$pin:ident::$Pin:ident = $number:literal,
             )+
         }
     ) => {
@@ -731,5 +1255,108 @@ macro_rules! impl_board_pins {
                 }
             }
         }
+
+        $crate::paste::paste! {
+            use $portpath::Pin as GenericPin;
+
+            impl $Pins {
+                /// Allow taking pins by their number at runtime.
+                ///
+                /// See `NumberedPins` for details.
+                pub fn into_numbered(self) -> [<Numbered $Pins>] {
+                    [<Numbered $Pins>] {
+                        ddr: self.ddr,
+                        $($name: Some(self.$name),)+
+                    }
+                }
+            }
+
+            /// Board pins which can be taken by their number at runtime
+            ///
+            /// This is useful when the pins are only known at runtime, e.g. when they are read
+            /// from a configuration.  Pins are returned as downgraded generic pins in dynamic mode
+            /// (see [`DynamicPin`](avr_hal_generic::port::DynamicPin)), so their mode is chosen
+            /// at runtime as well.  They start out as floating inputs.  Each pin can only be
+            /// taken once, either by its number or from its field.
+            ///
+            /// `by_number()` uses the pin numbers of the Arduino core, which each board assigns
+            /// explicitly.  Digital pins `dN` are number `N`, but the numbers of the analog and
+            /// other pins differ between boards, e.g. `a0` is number 14 on the Uno and 18 on the
+            /// Leonardo.  Analog pins `aN` can also be taken with `take_analog(N)`.
+            ///
+            /// # Example
+            /// ```ignore
+            /// let mut pins = Pins::new(dp.PORTB, dp.PORTC, dp.PORTD).into_numbered();
+            ///
+            /// let mut led = pins.take_digital(13)?;
+            /// led.set_mode(DynamicMode::Output);
+            ///
+            /// let mut button = pins.by_number(config.button_pin)?;
+            /// button.set_mode(DynamicMode::PullUpInput);
+            /// ```
+            pub struct [<Numbered $Pins>] {
+                pub ddr: $DDR,
+                $(
+                    $(#[$pin_attr])*
+                    pub $name: Option<$pinport::$Pin<
+                        $crate::port::mode::Input<$crate::port::mode::Floating>
+                    >>,
+                )+
+            }
+
+            impl [<Numbered $Pins>] {
+                /// Take a pin by its number in the Arduino core.
+                pub fn by_number(&mut self, n: u8) -> Result<
+                    GenericPin<$crate::port::mode::Dynamic>,
+                    $crate::port::PinNumberError,
+                > {
+                    match n {
+                        $(
+                            $number => self.$name
+                                .take()
+                                .map(|pin| pin.into_dynamic().downgrade())
+                                .ok_or($crate::port::PinNumberError::AlreadyTaken),
+                        )+
+                        _ => Err($crate::port::PinNumberError::InvalidNumber),
+                    }
+                }
+
+                /// Take digital pin `dN`.
+                pub fn take_digital(&mut self, n: u8) -> Result<
+                    GenericPin<$crate::port::mode::Dynamic>,
+                    $crate::port::PinNumberError,
+                > {
+                    self.take_named(n, b'd')
+                }
+
+                /// Take analog pin `aN` for use as a digital pin.
+                pub fn take_analog(&mut self, n: u8) -> Result<
+                    GenericPin<$crate::port::mode::Dynamic>,
+                    $crate::port::PinNumberError,
+                > {
+                    self.take_named(n, b'a')
+                }
+
+                /// Take the pin named `<prefix>N` by looking up its number.
+                fn take_named(&mut self, n: u8, prefix: u8) -> Result<
+                    GenericPin<$crate::port::mode::Dynamic>,
+                    $crate::port::PinNumberError,
+                > {
+                    $(
+                        {
+                            const DIGITAL: Option<u8> =
+                                $crate::port::board_pin_number(stringify!($name), b'd');
+                            const ANALOG: Option<u8> =
+                                $crate::port::board_pin_number(stringify!($name), b'a');
+                            let number = if prefix == b'd' { DIGITAL } else { ANALOG };
+                            if number == Some(n) {
+                                return self.by_number($number);
+                            }
+                        }
+                    )+
+                    Err($crate::port::PinNumberError::InvalidNumber)
+                }
+            }
+        }
     };
 }
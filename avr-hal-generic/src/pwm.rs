@@ -16,6 +16,10 @@
 /// | `Prescale64` | 977 Hz | 488 Hz |
 /// | `Prescale256` | 244 Hz | 122 Hz |
 /// | `Prescale1024` | 61.0 Hz | 30.5 Hz |
+///
+/// In the phase correct modes the timer counts up and back down again, halving these frequencies.
+///
+/// For other frequencies, initialize the timer using `with_frequency()` instead.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Prescaler {
     /// No prescaling, the IO clock drives the timer directly.
@@ -30,6 +34,207 @@ pub enum Prescaler {
     Prescale1024,
 }
 
+impl Prescaler {
+    /// Division factor of this prescaler.
+    pub fn divider(self) -> u32 {
+        match self {
+            Prescaler::Direct => 1,
+            Prescaler::Prescale8 => 8,
+            Prescaler::Prescale64 => 64,
+            Prescaler::Prescale256 => 256,
+            Prescaler::Prescale1024 => 1024,
+        }
+    }
+}
+
+/// Waveform generation mode for PWM
+///
+/// | Mode | Frequency | Duty `0` | Duty `TOP` |
+/// | --- | --- | --- | --- |
+/// | `Fast` | `CLK_io / (Prescaler * (TOP + 1))` | 1 tick spike | Always high |
+/// | `PhaseCorrect` | `CLK_io / (2 * Prescaler * TOP)` | Always low | Always high |
+/// | `PhaseFrequencyCorrect` | `CLK_io / (2 * Prescaler * TOP)` | Always low | Always high |
+///
+/// In fast PWM mode, the timer only counts up and the output is switched at the compare match
+/// and at BOTTOM.  The phase correct modes count up and back down again, which produces pulses
+/// centered around BOTTOM.  This is usually what is wanted for motor drivers and audio.
+///
+/// The phase and frequency correct mode only differs from the phase correct mode in when a new
+/// TOP value takes effect, which matters if the frequency is changed while the timer is running.
+/// Timers which do not support it fall back to phase correct PWM.
+///
+/// For inverted outputs (see `set_inverted()`), the duty cycle semantics are swapped: the output
+/// is low for `duty` out of `TOP` ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaveformMode {
+    /// Fast PWM, the timer counts from BOTTOM to TOP.
+    Fast,
+    /// Phase correct PWM, the timer counts from BOTTOM to TOP and back.
+    PhaseCorrect,
+    /// Phase and frequency correct PWM, like `PhaseCorrect` but TOP is only updated at BOTTOM.
+    PhaseFrequencyCorrect,
+}
+
+impl WaveformMode {
+    /// Waveform generation mode bits (`WGMn3:0`) for 16-bit timers.
+    ///
+    /// With `icr_top`, `ICRn` is used as the TOP value, otherwise TOP is fixed to `0xff`.
+    /// Phase and frequency correct PWM always uses `ICRn`.
+    #[doc(hidden)]
+    pub fn wgm_16bit(self, icr_top: bool) -> u8 {
+        match (self, icr_top) {
+            (WaveformMode::Fast, false) => 0b0101,
+            (WaveformMode::Fast, true) => 0b1110,
+            (WaveformMode::PhaseCorrect, false) => 0b0001,
+            (WaveformMode::PhaseCorrect, true) => 0b1010,
+            (WaveformMode::PhaseFrequencyCorrect, _) => 0b1000,
+        }
+    }
+}
+
+/// Find the smallest prescaler and the TOP value for a PWM frequency.
+///
+/// The resulting frequency is `CLK_io / (Prescaler * (TOP + 1))` in fast PWM mode and
+/// `CLK_io / (2 * Prescaler * TOP)` in the phase correct modes.  Frequencies too low to be
+/// reached with the largest prescaler fall back to `max_top`.
+#[doc(hidden)]
+pub fn calculate_top(
+    clock: u32,
+    frequency: u32,
+    max_top: u16,
+    mode: WaveformMode,
+) -> (Prescaler, u16) {
+    let prescalers = [
+        Prescaler::Direct,
+        Prescaler::Prescale8,
+        Prescaler::Prescale64,
+        Prescaler::Prescale256,
+        Prescaler::Prescale1024,
+    ];
+
+    for &prescaler in prescalers.iter() {
+        let ticks = clock / prescaler.divider() / frequency.max(1);
+        // TOP must be at least 1 to get a usable output
+        let top = match mode {
+            WaveformMode::Fast => ticks.max(2) - 1,
+            WaveformMode::PhaseCorrect | WaveformMode::PhaseFrequencyCorrect => (ticks / 2).max(1),
+        };
+        if top <= max_top as u32 {
+            return (prescaler, top as u16);
+        }
+    }
+
+    (Prescaler::Prescale1024, max_top)
+}
+
+/// Timer whose channels can be converted into PWM outputs of `TIMER`
+///
+/// Implemented for each PWM timer and for the timer with a variable TOP returned by its
+/// `with_frequency()`.  Channels whose compare register holds the variable TOP can only be
+/// converted with the plain timer.
+#[doc(hidden)]
+pub trait PwmTimer<TIMER> {}
+
+/// PWM output with its pin and timer type erased
+///
+/// Every PWM pin can be converted into a `PwmOutput` using `.into()`.  This allows storing
+/// outputs of different timers in an array, e.g. to drive a number of LED channels generically.
+/// The duty cycle is always a `u16`; the maximum duty cycle is the same as for the original pin.
+/// Inversion set with `set_inverted()` before the conversion is kept.
+///
+/// # Example
+/// ```ignore
+/// let mut timer0 = Timer0Pwm::new(dp.TC0, pwm::Prescaler::Prescale64);
+/// let mut timer2 = Timer2Pwm::new(dp.TC2, pwm::Prescaler::Prescale64);
+///
+/// let mut leds: [pwm::PwmOutput; 3] = [
+///     portd.pd5.into_output(&mut portd.ddr).into_pwm(&mut timer0).into(),
+///     portd.pd6.into_output(&mut portd.ddr).into_pwm(&mut timer0).into(),
+///     portd.pd3.into_output(&mut portd.ddr).into_pwm(&mut timer2).into(),
+/// ];
+///
+/// for led in leds.iter_mut() {
+///     led.set_duty(led.get_max_duty() / 2);
+///     led.enable();
+/// }
+/// ```
+pub struct PwmOutput {
+    enable: fn(bool),
+    invert: fn(bool),
+    get_duty: fn() -> u16,
+    set_duty: fn(u16),
+    max_duty: u16,
+}
+
+impl PwmOutput {
+    #[doc(hidden)]
+    pub fn new(
+        enable: fn(bool),
+        invert: fn(bool),
+        get_duty: fn() -> u16,
+        set_duty: fn(u16),
+        max_duty: u16,
+    ) -> Self {
+        PwmOutput {
+            enable,
+            invert,
+            get_duty,
+            set_duty,
+            max_duty,
+        }
+    }
+
+    /// Invert the output of this channel.
+    ///
+    /// See the PWM pins' `set_inverted()` for details.
+    pub fn set_inverted(&mut self, inverted: bool) {
+        (self.invert)(inverted)
+    }
+}
+
+/// State of a single PWM channel
+///
+/// The pins are zero-sized, so this is kept in a static for each channel.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelState {
+    pub enabled: bool,
+    pub inverted: bool,
+}
+
+impl ChannelState {
+    pub const fn new() -> Self {
+        ChannelState {
+            enabled: false,
+            inverted: false,
+        }
+    }
+}
+
+impl crate::hal::PwmPin for PwmOutput {
+    type Duty = u16;
+
+    fn enable(&mut self) {
+        (self.enable)(true)
+    }
+
+    fn disable(&mut self) {
+        (self.enable)(false)
+    }
+
+    fn get_duty(&self) -> Self::Duty {
+        (self.get_duty)()
+    }
+
+    fn get_max_duty(&self) -> Self::Duty {
+        self.max_duty
+    }
+
+    fn set_duty(&mut self, duty: Self::Duty) {
+        (self.set_duty)(duty)
+    }
+}
+
 /// Implement traits and types for PWM timers
 #[macro_export]
 macro_rules! impl_pwm {
@@ -39,22 +244,62 @@ macro_rules! impl_pwm {
             type Duty: $Duty:ty,
             timer: $TIMER:ty,
             init: |$init_timer:ident, $prescaler:ident| $init_block:block,
+            waveform: |$wgm_timer:ident, $mode:ident, $variable_top:ident| $waveform_block:block,
+            top: |$top_timer:ident| $top_block:block,
+            set_top: |$set_top_timer:ident, $top:ident| $set_top_block:block,
             pins: {$(
                 $port:ident::$PXi:ident: {
                     ocr: $ocr:ident,
-                    $into_pwm:ident: |$pin_timer:ident| if enable
+                    $into_pwm:ident: |$pin_timer:ident, $inverted:ident| if enable
                         $pin_enable_block:block else $pin_disable_block:block,
+                    $(holds_top: $holds_top:tt,)?
                 },
             )+},
         }
-    ) => {
+    ) => {$crate::paste::paste! {
         $(#[$timer_pwm_attr])*
         pub struct $TimerPwm {
             timer: $TIMER,
         }
 
+        /// PWM timer with a variable TOP, created by `with_frequency()`
+        ///
+        /// If the TOP value is held by the compare register of a channel, that channel cannot be
+        /// used for PWM output.  Its pin does not accept this timer.
+        pub struct [<$TimerPwm VariableTop>] {
+            timer: $TimerPwm,
+        }
+
+        impl [<$TimerPwm VariableTop>] {
+            /// The TOP value the timer counts up to; this is the maximum duty cycle.
+            pub fn top(&self) -> $Duty {
+                self.timer.top()
+            }
+        }
+
+        impl $crate::pwm::PwmTimer<$TimerPwm> for $TimerPwm {}
+        impl $crate::pwm::PwmTimer<$TimerPwm> for [<$TimerPwm VariableTop>] {}
+
         impl $TimerPwm {
             pub fn new(timer: $TIMER, prescaler: $crate::pwm::Prescaler) -> $TimerPwm {
+                Self::with_mode(timer, prescaler, $crate::pwm::WaveformMode::Fast)
+            }
+
+            /// Initialize this timer for PWM with the given waveform generation mode.
+            ///
+            /// # Example
+            /// ```ignore
+            /// let mut timer1 = Timer1Pwm::with_mode(
+            ///     dp.TC1,
+            ///     pwm::Prescaler::Prescale64,
+            ///     pwm::WaveformMode::PhaseCorrect,
+            /// );
+            /// ```
+            pub fn with_mode(
+                timer: $TIMER,
+                prescaler: $crate::pwm::Prescaler,
+                mode: $crate::pwm::WaveformMode,
+            ) -> $TimerPwm {
                 let mut t = $TimerPwm { timer };
 
                 {
@@ -63,40 +308,152 @@ macro_rules! impl_pwm {
                     $init_block
                 }
 
+                t.set_waveform(mode, false);
+
                 t
             }
-        }
 
-        $(
-            impl $port::$PXi<$crate::port::mode::Output> {
-                pub fn $into_pwm(self, pwm_timer: &mut $TimerPwm)
-                    -> $port::$PXi<$crate::port::mode::Pwm<$TimerPwm>>
+            /// Initialize this timer for PWM with the given frequency.
+            ///
+            /// The timer is switched to a mode where the TOP value is taken from a register
+            /// (`ICRn` for 16-bit timers, `OCRnA` for 8-bit timers) instead of being fixed.  This
+            /// allows setting the frequency much more precisely than with a prescaler alone, at the
+            /// cost of a reduced resolution: `get_max_duty()` returns the actual TOP value.
+            ///
+            /// For 8-bit timers, the `A` channel can no longer be used for PWM output as its
+            /// compare register now holds the TOP value.  Its pin only accepts the plain timer.
+            ///
+            /// # Example
+            /// ```ignore
+            /// // 25 kHz for a PC fan, TOP is 639 with a 16 MHz clock
+            /// let mut timer1 = Timer1Pwm::with_frequency::<MHz16>(dp.TC1, 25_000.hz());
+            /// ```
+            pub fn with_frequency<CLOCK: $crate::clock::Clock>(
+                timer: $TIMER,
+                frequency: $crate::timer::Hertz,
+            ) -> [<$TimerPwm VariableTop>] {
+                Self::with_frequency_and_mode::<CLOCK>(
+                    timer,
+                    frequency,
+                    $crate::pwm::WaveformMode::Fast,
+                )
+            }
+
+            /// Initialize this timer for PWM with the given frequency and waveform generation mode.
+            ///
+            /// This works like `with_frequency()`, but the TOP value is calculated for the
+            /// selected mode.  As the phase correct modes count up and down, TOP is about half of
+            /// the value used for fast PWM at the same frequency.
+            ///
+            /// # Example
+            /// ```ignore
+            /// // 20 kHz symmetric pulses for a motor driver, TOP is 400 with a 16 MHz clock
+            /// let mut timer1 = Timer1Pwm::with_frequency_and_mode::<MHz16>(
+            ///     dp.TC1,
+            ///     20_000.hz(),
+            ///     pwm::WaveformMode::PhaseFrequencyCorrect,
+            /// );
+            /// ```
+            pub fn with_frequency_and_mode<CLOCK: $crate::clock::Clock>(
+                timer: $TIMER,
+                frequency: $crate::timer::Hertz,
+                mode: $crate::pwm::WaveformMode,
+            ) -> [<$TimerPwm VariableTop>] {
+                let (prescaler, top) = $crate::pwm::calculate_top(
+                    CLOCK::FREQ,
+                    frequency.0,
+                    <$Duty>::MAX as u16,
+                    mode,
+                );
+                let mut t = Self::with_mode(timer, prescaler, mode);
+
                 {
-                    $port::$PXi { _mode: core::marker::PhantomData }
+                    let $set_top_timer = &mut t.timer;
+                    let $top = top as $Duty;
+                    $set_top_block
                 }
+
+                t.set_waveform(mode, true);
+
+                [<$TimerPwm VariableTop>] { timer: t }
             }
 
-            impl $crate::hal::PwmPin for $port::$PXi<$crate::port::mode::Pwm<$TimerPwm>> {
-                type Duty = $Duty;
+            fn set_waveform(&mut self, mode: $crate::pwm::WaveformMode, variable_top: bool) {
+                let $wgm_timer = &mut self.timer;
+                let $mode = mode;
+                let $variable_top = variable_top;
+                $waveform_block
+            }
 
-                fn enable(&mut self) {
+            /// The TOP value the timer counts up to; this is the maximum duty cycle.
+            pub fn top(&self) -> $Duty {
+                let $top_timer = &self.timer;
+                let top = $top_block;
+                top as $Duty
+            }
+        }
+
+        $(
+            $crate::__pwm_into_pwm!(
+                $($holds_top)?, $TimerPwm, $port::$PXi, $into_pwm
+            );
+
+            impl $port::$PXi<$crate::port::mode::Pwm<$TimerPwm>> {
+                fn state() -> &'static $crate::avr_device::interrupt::Mutex<
+                    core::cell::Cell<$crate::pwm::ChannelState>
+                > {
+                    static STATE: $crate::avr_device::interrupt::Mutex<
+                        core::cell::Cell<$crate::pwm::ChannelState>
+                    > = $crate::avr_device::interrupt::Mutex::new(
+                        core::cell::Cell::new($crate::pwm::ChannelState::new())
+                    );
+                    &STATE
+                }
+
+                fn configure(&mut self, state: $crate::pwm::ChannelState) {
                     // SAFETY: This block will usually result in a read-modify-write sequence which
                     // is not concurrency safe.  Thus, it is wrapped in a critical section which
                     // ensures we will never hit a race-condition here.
-                    $crate::avr_device::interrupt::free(|_| {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        Self::state().borrow(cs).set(state);
+
                         let $pin_timer = unsafe { &*<$TIMER>::ptr() };
-                        $pin_enable_block
+                        let $inverted = state.inverted;
+                        if state.enabled $pin_enable_block else $pin_disable_block
                     })
                 }
 
+                /// Invert the output of this channel.
+                ///
+                /// An inverted output is set on compare match instead of being cleared, so the
+                /// output is low for `duty` out of `get_max_duty()` ticks.  If the output is
+                /// already enabled, the change takes effect immediately.
+                pub fn set_inverted(&mut self, inverted: bool) {
+                    let mut state = $crate::avr_device::interrupt::free(|cs| {
+                        Self::state().borrow(cs).get()
+                    });
+                    state.inverted = inverted;
+                    self.configure(state);
+                }
+            }
+
+            impl $crate::hal::PwmPin for $port::$PXi<$crate::port::mode::Pwm<$TimerPwm>> {
+                type Duty = $Duty;
+
+                fn enable(&mut self) {
+                    let mut state = $crate::avr_device::interrupt::free(|cs| {
+                        Self::state().borrow(cs).get()
+                    });
+                    state.enabled = true;
+                    self.configure(state);
+                }
+
                 fn disable(&mut self) {
-                    // SAFETY: This block will usually result in a read-modify-write sequence which
-                    // is not concurrency safe.  Thus, it is wrapped in a critical section which
-                    // ensures we will never hit a race-condition here.
-                    $crate::avr_device::interrupt::free(|_| {
-                        let $pin_timer = unsafe { &*<$TIMER>::ptr() };
-                        $pin_disable_block
-                    })
+                    let mut state = $crate::avr_device::interrupt::free(|cs| {
+                        Self::state().borrow(cs).get()
+                    });
+                    state.enabled = false;
+                    self.configure(state);
                 }
 
                 fn get_duty(&self) -> Self::Duty {
@@ -104,7 +461,9 @@ macro_rules! impl_pwm {
                 }
 
                 fn get_max_duty(&self) -> Self::Duty {
-                    Self::Duty::MAX
+                    let $top_timer = unsafe { &*<$TIMER>::ptr() };
+                    let top = $top_block;
+                    top as Self::Duty
                 }
 
                 fn set_duty(&mut self, duty: Self::Duty) {
@@ -113,6 +472,73 @@ macro_rules! impl_pwm {
                     unsafe { (&*<$TIMER>::ptr()).$ocr.write(|w| w.bits(duty.into())) };
                 }
             }
+
+            impl From<$port::$PXi<$crate::port::mode::Pwm<$TimerPwm>>> for $crate::pwm::PwmOutput {
+                fn from(pin: $port::$PXi<$crate::port::mode::Pwm<$TimerPwm>>) -> Self {
+                    use $crate::hal::PwmPin;
+                    type Pin = $port::$PXi<$crate::port::mode::Pwm<$TimerPwm>>;
+
+                    // The pin is a zero-sized token, so it can be conjured again in the
+                    // type-erased functions.
+                    $crate::pwm::PwmOutput::new(
+                        |enable| {
+                            let mut pin: Pin = $port::$PXi { _mode: core::marker::PhantomData };
+                            if enable {
+                                pin.enable();
+                            } else {
+                                pin.disable();
+                            }
+                        },
+                        |inverted| {
+                            let mut pin: Pin = $port::$PXi { _mode: core::marker::PhantomData };
+                            pin.set_inverted(inverted);
+                        },
+                        || {
+                            let pin: Pin = $port::$PXi { _mode: core::marker::PhantomData };
+                            pin.get_duty().into()
+                        },
+                        |duty| {
+                            let mut pin: Pin = $port::$PXi { _mode: core::marker::PhantomData };
+                            let max: u16 = pin.get_max_duty().into();
+                            pin.set_duty(duty.min(max) as $Duty);
+                        },
+                        pin.get_max_duty().into(),
+                    )
+                }
+            }
         )+
-    }
+    }};
+}
+
+/// Implement the conversion of a pin into a PWM output.
+///
+/// Pins whose compare register holds the variable TOP (`holds_top: true`) only accept the
+/// plain timer.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __pwm_into_pwm {
+    (true, $TimerPwm:ident, $port:ident::$PXi:ident, $into_pwm:ident) => {
+        impl $port::$PXi<$crate::port::mode::Output> {
+            pub fn $into_pwm(
+                self,
+                pwm_timer: &mut $TimerPwm,
+            ) -> $port::$PXi<$crate::port::mode::Pwm<$TimerPwm>> {
+                $port::$PXi {
+                    _mode: core::marker::PhantomData,
+                }
+            }
+        }
+    };
+    (, $TimerPwm:ident, $port:ident::$PXi:ident, $into_pwm:ident) => {
+        impl $port::$PXi<$crate::port::mode::Output> {
+            pub fn $into_pwm<T: $crate::pwm::PwmTimer<$TimerPwm>>(
+                self,
+                pwm_timer: &mut T,
+            ) -> $port::$PXi<$crate::port::mode::Pwm<$TimerPwm>> {
+                $port::$PXi {
+                    _mode: core::marker::PhantomData,
+                }
+            }
+        }
+    };
 }
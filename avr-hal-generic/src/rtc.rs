@@ -0,0 +1,391 @@
+//! Real-time clock with an asynchronous timer
+//!
+//! Some AVRs can clock `TC2` from a 32.768 kHz watch crystal on the `TOSC1`/`TOSC2` pins,
+//! independently of the system clock.  The timer then keeps running in power-save sleep and its
+//! overflow interrupt, which fires once per second, advances the time and wakes the CPU.  The
+//! current value of the timer is used for the subseconds, with a resolution of 1/256 s.
+//!
+//! The time is kept as seconds since 1970-01-01 00:00:00 (a Unix timestamp) and can be converted
+//! from and to a calendar date and time with [`DateTime`].  There are no time zones or leap
+//! seconds.
+//!
+//! The application needs to forward the overflow interrupt to the RTC:
+//!
+//! ```
+//! let rtc = Rtc::new(dp.TC2);
+//! rtc.set_datetime(&DateTime {
+//!     year: 2021,
+//!     month: 3,
+//!     day: 14,
+//!     hour: 15,
+//!     minute: 9,
+//!     second: 26,
+//! })
+//! .unwrap();
+//! rtc.set_alarm(Some(rtc.timestamp() + 60));
+//! unsafe { avr_device::interrupt::enable() };
+//!
+//! loop {
+//!     // Enter power-save sleep here
+//!     rtc.sync();
+//!     if rtc.take_alarm() {
+//!         // ...
+//!     }
+//! }
+//!
+//! #[avr_device::interrupt(atmega328p)]
+//! fn TIMER2_OVF() {
+//!     Rtc::on_interrupt();
+//! }
+//! ```
+//!
+//! On boards where the system clock crystal is connected to the same pins as `TOSC1`/`TOSC2`,
+//! like the Arduino Uno, the RTC cannot be used.
+use core::cell;
+
+/// Number of subsecond ticks per second
+pub const SUBSECONDS: u32 = 256;
+
+#[derive(Clone, Copy)]
+struct State {
+    seconds: u32,
+    alarm: Option<u32>,
+    alarm_fired: bool,
+}
+
+static STATE: avr_device::interrupt::Mutex<cell::Cell<State>> =
+    avr_device::interrupt::Mutex::new(cell::Cell::new(State {
+        seconds: 0,
+        alarm: None,
+        alarm_fired: false,
+    }));
+
+#[doc(hidden)]
+pub fn reset(cs: &avr_device::interrupt::CriticalSection) {
+    STATE.borrow(cs).set(State {
+        seconds: 0,
+        alarm: None,
+        alarm_fired: false,
+    });
+}
+
+/// Advance the time by one second; returns whether the alarm fired.
+#[doc(hidden)]
+pub fn advance() -> bool {
+    avr_device::interrupt::free(|cs| {
+        let cell = STATE.borrow(cs);
+        let mut state = cell.get();
+
+        state.seconds = state.seconds.wrapping_add(1);
+        let fired = state.alarm.map_or(false, |alarm| state.seconds >= alarm);
+        if fired {
+            state.alarm = None;
+            state.alarm_fired = true;
+        }
+
+        cell.set(state);
+        fired
+    })
+}
+
+#[doc(hidden)]
+pub fn seconds(cs: &avr_device::interrupt::CriticalSection) -> u32 {
+    STATE.borrow(cs).get().seconds
+}
+
+#[doc(hidden)]
+pub fn set_seconds(seconds: u32) {
+    avr_device::interrupt::free(|cs| {
+        let cell = STATE.borrow(cs);
+        let mut state = cell.get();
+        state.seconds = seconds;
+        cell.set(state);
+    })
+}
+
+#[doc(hidden)]
+pub fn set_alarm(alarm: Option<u32>) {
+    avr_device::interrupt::free(|cs| {
+        let cell = STATE.borrow(cs);
+        let mut state = cell.get();
+        state.alarm = alarm;
+        state.alarm_fired = false;
+        cell.set(state);
+    })
+}
+
+#[doc(hidden)]
+pub fn alarm() -> Option<u32> {
+    avr_device::interrupt::free(|cs| STATE.borrow(cs).get().alarm)
+}
+
+#[doc(hidden)]
+pub fn take_alarm() -> bool {
+    avr_device::interrupt::free(|cs| {
+        let cell = STATE.borrow(cs);
+        let mut state = cell.get();
+        let fired = state.alarm_fired;
+        state.alarm_fired = false;
+        cell.set(state);
+        fired
+    })
+}
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+/// Days from 0000-03-01 to 1970-01-01 in the proleptic Gregorian calendar
+const EPOCH_DAYS: u32 = 719_468;
+const DAYS_PER_ERA: u32 = 146_097;
+
+/// Calendar date and time
+///
+/// All fields count from 1, except for the time of day which counts from 0.  The supported range
+/// is from 1970-01-01 00:00:00 to 2106-02-07 06:28:15, which is the range of a `u32` Unix
+/// timestamp.  Conversions of an invalid date such as February 30th, or of a date outside of this
+/// range, fail with [`InvalidDateTime`].
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DateTime {
+    /// Year, e.g. 2021
+    pub year: u16,
+    /// Month from 1 to 12
+    pub month: u8,
+    /// Day of the month from 1 to 31
+    pub day: u8,
+    /// Hour from 0 to 23
+    pub hour: u8,
+    /// Minute from 0 to 59
+    pub minute: u8,
+    /// Second from 0 to 59
+    pub second: u8,
+}
+
+impl DateTime {
+    /// Convert a Unix timestamp to date and time.
+    pub fn from_timestamp(timestamp: u32) -> DateTime {
+        let days = timestamp / SECONDS_PER_DAY;
+        let time = timestamp % SECONDS_PER_DAY;
+
+        // Count from 0000-03-01 so the leap day is at the end of the year
+        let days = days + EPOCH_DAYS;
+        let era = days / DAYS_PER_ERA;
+        let day_of_era = days - era * DAYS_PER_ERA;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month + 2) / 5 + 1;
+        let month = if month < 10 { month + 3 } else { month - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        DateTime {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            hour: (time / 3600) as u8,
+            minute: (time / 60 % 60) as u8,
+            second: (time % 60) as u8,
+        }
+    }
+
+    /// Convert date and time to a Unix timestamp.
+    ///
+    /// Fails if the date or time is invalid or outside of the range of a `u32` timestamp.
+    pub fn timestamp(&self) -> Result<u32, InvalidDateTime> {
+        if !self.is_valid() {
+            return Err(InvalidDateTime);
+        }
+        let days = self.days();
+        if days < 0 {
+            return Err(InvalidDateTime);
+        }
+        let time = self.hour as u32 * 3600 + self.minute as u32 * 60 + self.second as u32;
+        (days as u32)
+            .checked_mul(SECONDS_PER_DAY)
+            .and_then(|seconds| seconds.checked_add(time))
+            .ok_or(InvalidDateTime)
+    }
+
+    /// ISO 8601 day of the week, from 1 (Monday) to 7 (Sunday).
+    ///
+    /// Unlike [`timestamp()`](Self::timestamp), this works for dates outside of the timestamp
+    /// range, but still fails for invalid dates.
+    pub fn weekday(&self) -> Result<u8, InvalidDateTime> {
+        if !self.is_valid() {
+            return Err(InvalidDateTime);
+        }
+        // 1970-01-01 was a Thursday
+        Ok(((self.days() + 3).rem_euclid(7) + 1) as u8)
+    }
+
+    /// Whether all fields are within their range, including the day of the month.
+    pub fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month)
+            && self.day >= 1
+            && self.day <= days_in_month(self.year, self.month)
+            && self.hour < 24
+            && self.minute < 60
+            && self.second < 60
+    }
+
+    /// Days since 1970-01-01, negative before.  The date must be valid.
+    fn days(&self) -> i32 {
+        let month = self.month as i32;
+        let year = self.year as i32 - if month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = if month > 2 { month - 3 } else { month + 9 };
+        let day_of_year = (153 * month + 2) / 5 + self.day as i32 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * DAYS_PER_ERA as i32 + day_of_era - EPOCH_DAYS as i32
+    }
+}
+
+/// Number of days in `month` of `year`.
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Error for a [`DateTime`] which is invalid or cannot be represented as a timestamp
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidDateTime;
+
+/// Implement the real-time clock for an asynchronous timer
+#[macro_export]
+macro_rules! impl_rtc {
+    (
+        $(#[$rtc_attr:meta])*
+        pub struct $Rtc:ident {
+            timer: $TIMER:ty,
+            start: |$start_timer:ident| $start_block:block,
+            stop: |$stop_timer:ident| $stop_block:block,
+            sync: |$sync_timer:ident| $sync_block:block,
+            counter: |$counter_timer:ident| $counter_block:block,
+            is_pending: |$pending_timer:ident| $pending_block:block,
+        }
+    ) => {
+        $(#[$rtc_attr])*
+        pub struct $Rtc {
+            timer: $TIMER,
+        }
+
+        impl $Rtc {
+            /// Switch the timer to the external crystal and start the RTC.
+            ///
+            /// The time starts at zero (1970-01-01 00:00:00).  This blocks until the timer runs
+            /// from the crystal, which can take a while after power-up.  Interrupts must be
+            /// enabled globally for the time to advance.
+            pub fn new(timer: $TIMER) -> $Rtc {
+                $crate::avr_device::interrupt::free(|cs| {
+                    $crate::rtc::reset(cs);
+
+                    let $start_timer = &timer;
+                    $start_block
+                });
+
+                $Rtc { timer }
+            }
+
+            /// Stop the RTC and return the timer peripheral.
+            ///
+            /// The timer is left in asynchronous mode.
+            pub fn release(self) -> $TIMER {
+                let $stop_timer = &self.timer;
+                $stop_block
+                self.timer
+            }
+
+            /// Advance the time and check the alarm.
+            ///
+            /// This function must be called from the timer's overflow interrupt (`TIMER2_OVF`).
+            /// Returns `true` if the alarm fired.
+            pub fn on_interrupt() -> bool {
+                $crate::rtc::advance()
+            }
+
+            /// Wait until the timer has caught up with the system clock.
+            ///
+            /// As the timer runs from its own clock, the CPU must wait for at least one cycle of
+            /// the crystal before entering power-save sleep again after the overflow interrupt,
+            /// and after waking up before the subseconds can be read correctly.  Call this
+            /// function in both places.
+            pub fn sync(&self) {
+                let $sync_timer = &self.timer;
+                $sync_block
+            }
+
+            /// Seconds since 1970-01-01 00:00:00.
+            pub fn timestamp(&self) -> u32 {
+                self.now().0
+            }
+
+            /// Current seconds and subseconds in units of 1/256 s.
+            pub fn now(&self) -> (u32, u8) {
+                $crate::avr_device::interrupt::free(|cs| {
+                    let ticks = {
+                        let $counter_timer = &self.timer;
+                        $counter_block
+                    };
+                    let pending = {
+                        let $pending_timer = &self.timer;
+                        $pending_block
+                    };
+
+                    let mut seconds = $crate::rtc::seconds(cs);
+                    // The timer has already overflowed but the interrupt was not handled yet.  If
+                    // the counter is still at its maximum, it was read before the overflow.
+                    if pending && ticks < u8::MAX {
+                        seconds = seconds.wrapping_add(1);
+                    }
+                    (seconds, ticks)
+                })
+            }
+
+            /// Set the time as seconds since 1970-01-01 00:00:00.
+            ///
+            /// The subseconds are not reset.
+            pub fn set_timestamp(&self, timestamp: u32) {
+                $crate::rtc::set_seconds(timestamp);
+            }
+
+            /// Current date and time.
+            pub fn datetime(&self) -> $crate::rtc::DateTime {
+                $crate::rtc::DateTime::from_timestamp(self.timestamp())
+            }
+
+            /// Set the date and time.
+            ///
+            /// Fails without changing the time if `datetime` is invalid or outside of the
+            /// supported range of `DateTime`.
+            pub fn set_datetime(
+                &self,
+                datetime: &$crate::rtc::DateTime,
+            ) -> Result<(), $crate::rtc::InvalidDateTime> {
+                self.set_timestamp(datetime.timestamp()?);
+                Ok(())
+            }
+
+            /// Set or clear the alarm.
+            ///
+            /// The alarm fires once, in the interrupt of the second given as a timestamp.  A
+            /// timestamp in the past fires with the next interrupt.  Setting the alarm resets a
+            /// previously fired alarm.
+            pub fn set_alarm(&self, timestamp: Option<u32>) {
+                $crate::rtc::set_alarm(timestamp);
+            }
+
+            /// The timestamp of the alarm if it is set and has not fired yet.
+            pub fn alarm(&self) -> Option<u32> {
+                $crate::rtc::alarm()
+            }
+
+            /// Check whether the alarm has fired and reset it.
+            pub fn take_alarm(&self) -> bool {
+                $crate::rtc::take_alarm()
+            }
+        }
+    };
+}
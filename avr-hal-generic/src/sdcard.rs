@@ -0,0 +1,656 @@
+//! SD/MMC card block device over SPI
+//!
+//! Check the documentation of [`SdCard`] for details.
+
+use core::marker;
+use hal::digital::v2::OutputPin;
+use hal::spi::FullDuplex;
+use void::ResultVoidExt;
+
+use crate::spi::{DataOrder, Reconfigure, SerialClockRate};
+
+/// Size of one block in bytes.
+///
+/// All cards are accessed in blocks of this size, regardless of their native block length.
+pub const BLOCK_SIZE: usize = 512;
+
+/// A single data block
+pub type Block = [u8; BLOCK_SIZE];
+
+// Commands --------------------------------------------------------------- {{{
+/// GO_IDLE_STATE - Reset the card into SPI mode
+const CMD0: u8 = 0;
+/// SEND_OP_COND - Start initialization (MMC)
+const CMD1: u8 = 1;
+/// SEND_IF_COND - Check voltage range (SD v2)
+const CMD8: u8 = 8;
+/// SEND_CSD - Read the card specific data register
+const CMD9: u8 = 9;
+/// STOP_TRANSMISSION - End a multi-block read
+const CMD12: u8 = 12;
+/// SEND_STATUS - Read the card status register
+const CMD13: u8 = 13;
+/// SET_BLOCKLEN - Set the block length for byte-addressed cards
+const CMD16: u8 = 16;
+/// READ_SINGLE_BLOCK
+const CMD17: u8 = 17;
+/// READ_MULTIPLE_BLOCK
+const CMD18: u8 = 18;
+/// WRITE_BLOCK
+const CMD24: u8 = 24;
+/// WRITE_MULTIPLE_BLOCK
+const CMD25: u8 = 25;
+/// APP_CMD - The next command is an application specific command
+const CMD55: u8 = 55;
+/// READ_OCR - Read the operation conditions register
+const CMD58: u8 = 58;
+/// CRC_ON_OFF - Enable or disable CRC checking
+const CMD59: u8 = 59;
+/// SET_WR_BLK_ERASE_COUNT - Number of blocks to pre-erase before writing
+const ACMD23: u8 = 23;
+/// SD_SEND_OP_COND - Start initialization (SD)
+const ACMD41: u8 = 41;
+// ------------------------------------------------------------------------ }}}
+
+/// R1: Card is in idle state
+const R1_IDLE_STATE: u8 = 0x01;
+/// R1: An illegal command code was detected
+const R1_ILLEGAL_COMMAND: u8 = 0x04;
+
+/// Start token for single block reads and writes as well as multi-block reads
+const DATA_START_BLOCK: u8 = 0xfe;
+/// Start token for each block of a multi-block write
+const WRITE_MULTIPLE_TOKEN: u8 = 0xfc;
+/// Stop token for multi-block writes
+const STOP_TRAN_TOKEN: u8 = 0xfd;
+
+/// OCR: Card capacity status bit (set for SDHC/SDXC cards)
+const OCR_CCS: u32 = 0x4000_0000;
+
+/// Number of bytes to wait for a command response (N_CR is at most 8)
+const RESPONSE_RETRIES: u8 = 10;
+/// Number of `CMD0` attempts before giving up
+const CMD0_RETRIES: u8 = 10;
+/// Number of `ACMD41`/`CMD1` attempts before giving up
+const INIT_RETRIES: u16 = 10_000;
+/// Number of bytes to wait for a data start token
+const READ_RETRIES: u16 = 50_000;
+/// Number of bytes to wait for the card to leave the busy state
+const BUSY_RETRIES: u32 = 500_000;
+
+/// SD card errors
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Error {
+    /// The card did not answer in time
+    Timeout,
+    /// The card was not initialized yet, call [`SdCard::init()`] first
+    NotInitialized,
+    /// The card does not support the supply voltage or is of an unknown type
+    UnsupportedCard,
+    /// A command was answered with an error (contains the R1 response)
+    Command(u8),
+    /// A read was answered with an error token (contains the token)
+    Read(u8),
+    /// Written data was rejected by the card (contains the data response or status)
+    Write(u8),
+    /// A CRC mismatch was detected
+    Crc,
+    /// The card's CSD register contains invalid values
+    InvalidCsd,
+    /// The block is outside the address range of the card
+    AddressRange,
+}
+
+/// Type of an initialized card
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CardType {
+    /// MultiMediaCard
+    Mmc,
+    /// SD card, version 1.x (byte addressed)
+    SdV1,
+    /// Standard capacity SD card, version 2.0 or later (byte addressed)
+    SdV2,
+    /// High/extended capacity SD card (SDHC/SDXC, block addressed)
+    SdHc,
+}
+
+/// Settings to pass to [`SdCard`].
+///
+/// Easiest way to initialize is with `Settings::default()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    /// SPI clock rate to switch to once the card was initialized
+    pub clock: SerialClockRate,
+    /// Enable CRC checking for commands and data
+    pub crc: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            clock: SerialClockRate::OscfOver2,
+            crc: false,
+        }
+    }
+}
+
+/// SD/MMC card driver
+///
+/// The card is accessed in SPI mode through any SPI peripheral created with
+/// [`impl_spi!`](crate::impl_spi).  During initialization the bus is clocked at no more than
+/// 400 kHz as required by the specification; afterwards it is switched to
+/// [`Settings::clock`] using [`Reconfigure::reconfigure()`].
+///
+/// Addressing is always done in 512 byte blocks, for both byte addressed (SDSC, MMC) and block
+/// addressed (SDHC, SDXC) cards.
+///
+/// # Example
+/// (This example is taken from Arduino Mega 2560)
+/// ```
+/// let (spi, cs) = arduino_mega2560::spi::Spi::new(
+///     dp.SPI,
+///     pins.d52.into_output(&mut pins.ddr),
+///     pins.d51.into_output(&mut pins.ddr),
+///     pins.d50.into_pull_up_input(&mut pins.ddr),
+///     pins.d53.into_output(&mut pins.ddr),
+///     arduino_mega2560::spi::Settings::default(),
+/// );
+///
+/// let mut card = arduino_mega2560::SdCard::new(spi, cs, sdcard::Settings::default());
+/// card.init().unwrap();
+///
+/// let mut block = [0u8; sdcard::BLOCK_SIZE];
+/// card.read_block(0, &mut block).unwrap();
+/// ```
+pub struct SdCard<SPI, CS, CLOCK> {
+    spi: SPI,
+    cs: CS,
+    settings: Settings,
+    card_type: Option<CardType>,
+    _clock: marker::PhantomData<CLOCK>,
+}
+
+impl<SPI, CS, CLOCK> SdCard<SPI, CS, CLOCK>
+where
+    SPI: FullDuplex<u8, Error = void::Void> + Reconfigure,
+    CS: OutputPin<Error = void::Void>,
+    CLOCK: crate::clock::Clock,
+{
+    /// Create a new card driver.
+    ///
+    /// The card is not accessed until [`init()`](SdCard::init) is called.
+    pub fn new(spi: SPI, mut cs: CS, settings: Settings) -> Self {
+        cs.set_high().void_unwrap();
+        SdCard {
+            spi,
+            cs,
+            settings,
+            card_type: None,
+            _clock: marker::PhantomData,
+        }
+    }
+
+    /// Release the SPI peripheral and chip-select pin.
+    pub fn release(self) -> (SPI, CS) {
+        (self.spi, self.cs)
+    }
+
+    /// Type of the card, if it was initialized.
+    pub fn card_type(&self) -> Option<CardType> {
+        self.card_type
+    }
+
+    /// Initialize the card.
+    ///
+    /// This must be called before any other operation and again whenever a card was
+    /// (re-)inserted.  On success, the SPI bus is switched to the configured clock rate.
+    pub fn init(&mut self) -> Result<CardType, Error> {
+        self.card_type = None;
+        self.configure_bus(init_clock_rate::<CLOCK>());
+
+        // Supply at least 74 clock cycles with CS and MOSI high
+        self.cs.set_high().void_unwrap();
+        for _ in 0..10 {
+            self.transfer(0xff);
+        }
+
+        self.select();
+        let res = self.init_card();
+        self.deselect();
+        let card_type = res?;
+
+        self.configure_bus(self.settings.clock);
+        self.card_type = Some(card_type);
+        Ok(card_type)
+    }
+
+    /// Number of blocks available on the card.
+    ///
+    /// Saturates at `u32::MAX` for the largest SDXC cards, whose last block cannot be counted in
+    /// a `u32`.
+    pub fn num_blocks(&mut self) -> Result<u32, Error> {
+        self.check_init()?;
+
+        let mut csd = [0u8; 16];
+        self.select();
+        let res = self.command(CMD9, 0).and_then(|r1| match r1 {
+            0 => self.read_data(&mut csd),
+            r1 => Err(Error::Command(r1)),
+        });
+        self.deselect();
+        res?;
+
+        Ok(match csd[0] >> 6 {
+            // CSD version 1.0
+            0 => {
+                let c_size = ((csd[6] as u32 & 0x03) << 10)
+                    | ((csd[7] as u32) << 2)
+                    | (csd[8] as u32 >> 6);
+                let c_size_mult = ((csd[9] & 0x03) << 1) | (csd[10] >> 7);
+                let read_bl_len = csd[5] & 0x0f;
+                // Only block lengths of 512, 1024 and 2048 bytes are allowed
+                if !(9..=11).contains(&read_bl_len) {
+                    return Err(Error::InvalidCsd);
+                }
+                (c_size + 1) << (c_size_mult + 2 + (read_bl_len - 9))
+            }
+            // CSD version 2.0
+            1 => {
+                let c_size = ((csd[7] as u32 & 0x3f) << 16)
+                    | ((csd[8] as u32) << 8)
+                    | csd[9] as u32;
+                let blocks = (c_size as u64 + 1) << 10;
+                blocks.min(u32::MAX as u64) as u32
+            }
+            _ => return Err(Error::UnsupportedCard),
+        })
+    }
+
+    /// Read a single block.
+    pub fn read_block(&mut self, block: u32, buf: &mut Block) -> Result<(), Error> {
+        self.read_blocks(block, core::slice::from_mut(buf))
+    }
+
+    /// Read consecutive blocks, starting at `start`.
+    ///
+    /// If more than one block is requested, a multi-block read is used.
+    pub fn read_blocks(&mut self, start: u32, bufs: &mut [Block]) -> Result<(), Error> {
+        self.check_init()?;
+        if bufs.is_empty() {
+            return Ok(());
+        }
+
+        let address = self.block_address(start)?;
+        self.select();
+        let res = if bufs.len() == 1 {
+            self.command(CMD17, address).and_then(|r1| match r1 {
+                0 => self.read_data(&mut bufs[0]),
+                r1 => Err(Error::Command(r1)),
+            })
+        } else {
+            self.read_multiple(address, bufs)
+        };
+        self.deselect();
+        res
+    }
+
+    /// Write a single block.
+    pub fn write_block(&mut self, block: u32, buf: &Block) -> Result<(), Error> {
+        self.write_blocks(block, core::slice::from_ref(buf))
+    }
+
+    /// Write consecutive blocks, starting at `start`.
+    ///
+    /// If more than one block is written, a multi-block write is used.  For SD cards, the
+    /// affected blocks are pre-erased first to speed up the operation.
+    pub fn write_blocks(&mut self, start: u32, bufs: &[Block]) -> Result<(), Error> {
+        let card_type = self.check_init()?;
+        if bufs.is_empty() {
+            return Ok(());
+        }
+
+        let address = self.block_address(start)?;
+        self.select();
+        let res = if bufs.len() == 1 {
+            self.command(CMD24, address).and_then(|r1| match r1 {
+                0 => self.write_data(DATA_START_BLOCK, &bufs[0]),
+                r1 => Err(Error::Command(r1)),
+            })
+        } else {
+            self.write_multiple(card_type, address, bufs)
+        };
+        // Errors during programming are only reported in the status register
+        let res = res.and_then(|_| self.check_status());
+        self.deselect();
+        res
+    }
+
+    fn init_card(&mut self) -> Result<CardType, Error> {
+        // Enter SPI mode and idle state
+        let mut retries = 0;
+        while self.command(CMD0, 0) != Ok(R1_IDLE_STATE) {
+            retries += 1;
+            if retries >= CMD0_RETRIES {
+                return Err(Error::Timeout);
+            }
+        }
+
+        // Check for version 2 cards
+        let r1 = self.command(CMD8, 0x1aa)?;
+        let v2 = if r1 & R1_ILLEGAL_COMMAND != 0 {
+            false
+        } else if r1 == R1_IDLE_STATE {
+            let r7 = self.read_u32();
+            // The card must accept 2.7-3.6V and echo back the check pattern
+            if r7 & 0xfff != 0x1aa {
+                return Err(Error::UnsupportedCard);
+            }
+            true
+        } else {
+            return Err(Error::Command(r1));
+        };
+
+        if self.settings.crc {
+            match self.command(CMD59, 1)? {
+                R1_IDLE_STATE => (),
+                r1 => return Err(Error::Command(r1)),
+            }
+        }
+
+        // Start initialization; version 1 cards which don't know ACMD41 are MMCs
+        let mut card_type = if v2 { CardType::SdV2 } else { CardType::SdV1 };
+        let mut retries = 0;
+        loop {
+            let r1 = match card_type {
+                CardType::Mmc => self.command(CMD1, 0)?,
+                _ => self.app_command(ACMD41, if v2 { OCR_CCS } else { 0 })?,
+            };
+            match r1 {
+                0 => break,
+                R1_IDLE_STATE => (),
+                r1 if r1 & R1_ILLEGAL_COMMAND != 0 && card_type == CardType::SdV1 => {
+                    card_type = CardType::Mmc;
+                }
+                r1 => return Err(Error::Command(r1)),
+            }
+
+            retries += 1;
+            if retries >= INIT_RETRIES {
+                return Err(Error::Timeout);
+            }
+        }
+
+        if v2 {
+            match self.command(CMD58, 0)? {
+                0 => (),
+                r1 => return Err(Error::Command(r1)),
+            }
+            if self.read_u32() & OCR_CCS != 0 {
+                card_type = CardType::SdHc;
+            }
+        }
+
+        // Byte addressed cards might use a different block length by default
+        if card_type != CardType::SdHc {
+            match self.command(CMD16, BLOCK_SIZE as u32)? {
+                0 => (),
+                r1 => return Err(Error::Command(r1)),
+            }
+        }
+
+        Ok(card_type)
+    }
+
+    fn read_multiple(&mut self, address: u32, bufs: &mut [Block]) -> Result<(), Error> {
+        match self.command(CMD18, address)? {
+            0 => (),
+            r1 => return Err(Error::Command(r1)),
+        }
+
+        let mut res = Ok(());
+        for buf in bufs.iter_mut() {
+            res = self.read_data(buf);
+            if res.is_err() {
+                break;
+            }
+        }
+
+        // Always terminate the transfer, even if a block failed
+        let stop = self.command(CMD12, 0).and_then(|r1| match r1 {
+            0 => self.wait_ready(),
+            r1 => Err(Error::Command(r1)),
+        });
+        res.and(stop)
+    }
+
+    fn write_multiple(
+        &mut self,
+        card_type: CardType,
+        address: u32,
+        bufs: &[Block],
+    ) -> Result<(), Error> {
+        if card_type != CardType::Mmc {
+            match self.app_command(ACMD23, bufs.len() as u32)? {
+                0 => (),
+                r1 => return Err(Error::Command(r1)),
+            }
+        }
+
+        match self.command(CMD25, address)? {
+            0 => (),
+            r1 => return Err(Error::Command(r1)),
+        }
+
+        let mut res = Ok(());
+        for buf in bufs.iter() {
+            res = self.write_data(WRITE_MULTIPLE_TOKEN, buf);
+            if res.is_err() {
+                break;
+            }
+        }
+
+        // Always terminate the transfer, even if a block failed
+        self.transfer(STOP_TRAN_TOKEN);
+        res.and(self.wait_ready())
+    }
+
+    /// Send a command and return the R1 response.
+    ///
+    /// The card must already be selected.  Additional response bytes can be read afterwards.
+    fn command(&mut self, cmd: u8, arg: u32) -> Result<u8, Error> {
+        // CMD0 is used to reset the card and must not wait for a ready card
+        if cmd != CMD0 {
+            self.wait_ready()?;
+        }
+
+        let frame = [
+            0x40 | cmd,
+            (arg >> 24) as u8,
+            (arg >> 16) as u8,
+            (arg >> 8) as u8,
+            arg as u8,
+        ];
+        for b in frame.iter() {
+            self.transfer(*b);
+        }
+        self.transfer(crc7(&frame));
+
+        // Skip the stuff byte following STOP_TRANSMISSION
+        if cmd == CMD12 {
+            self.transfer(0xff);
+        }
+
+        for _ in 0..RESPONSE_RETRIES {
+            let r1 = self.transfer(0xff);
+            if r1 & 0x80 == 0 {
+                return Ok(r1);
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    fn app_command(&mut self, cmd: u8, arg: u32) -> Result<u8, Error> {
+        self.command(CMD55, 0)?;
+        self.command(cmd, arg)
+    }
+
+    fn check_status(&mut self) -> Result<(), Error> {
+        let r1 = self.command(CMD13, 0)?;
+        let r2 = self.transfer(0xff);
+        match (r1, r2) {
+            (0, 0) => Ok(()),
+            (0, status) => Err(Error::Write(status)),
+            (r1, _) => Err(Error::Command(r1)),
+        }
+    }
+
+    fn read_data(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        let mut token = 0xff;
+        for _ in 0..READ_RETRIES {
+            token = self.transfer(0xff);
+            if token != 0xff {
+                break;
+            }
+        }
+        match token {
+            DATA_START_BLOCK => (),
+            0xff => return Err(Error::Timeout),
+            token => return Err(Error::Read(token)),
+        }
+
+        for b in buf.iter_mut() {
+            *b = self.transfer(0xff);
+        }
+        let crc = (self.transfer(0xff) as u16) << 8 | self.transfer(0xff) as u16;
+
+        if self.settings.crc && crc != crc16(buf) {
+            return Err(Error::Crc);
+        }
+        Ok(())
+    }
+
+    fn write_data(&mut self, token: u8, buf: &[u8]) -> Result<(), Error> {
+        let crc = if self.settings.crc { crc16(buf) } else { 0xffff };
+
+        self.transfer(token);
+        for b in buf.iter() {
+            self.transfer(*b);
+        }
+        self.transfer((crc >> 8) as u8);
+        self.transfer(crc as u8);
+
+        match self.transfer(0xff) & 0x1f {
+            // Data accepted
+            0x05 => (),
+            // Data rejected due to a CRC error
+            0x0b => return Err(Error::Crc),
+            response => return Err(Error::Write(response)),
+        }
+        self.wait_ready()
+    }
+
+    /// Wait until the card releases MISO (stops signalling busy).
+    fn wait_ready(&mut self) -> Result<(), Error> {
+        for _ in 0..BUSY_RETRIES {
+            if self.transfer(0xff) == 0xff {
+                return Ok(());
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    fn read_u32(&mut self) -> u32 {
+        let mut value = 0;
+        for _ in 0..4 {
+            value = value << 8 | self.transfer(0xff) as u32;
+        }
+        value
+    }
+
+    fn check_init(&self) -> Result<CardType, Error> {
+        self.card_type.ok_or(Error::NotInitialized)
+    }
+
+    fn block_address(&self, block: u32) -> Result<u32, Error> {
+        match self.card_type {
+            Some(CardType::SdHc) => Ok(block),
+            _ => block
+                .checked_mul(BLOCK_SIZE as u32)
+                .ok_or(Error::AddressRange),
+        }
+    }
+
+    fn select(&mut self) {
+        self.cs.set_low().void_unwrap();
+    }
+
+    fn deselect(&mut self) {
+        self.cs.set_high().void_unwrap();
+        // The card only releases MISO on the next clock edge
+        self.transfer(0xff);
+    }
+
+    fn configure_bus(&mut self, clock: SerialClockRate) {
+        nb::block!(self.spi.reconfigure(crate::spi::Settings {
+            data_order: DataOrder::MostSignificantFirst,
+            clock,
+            mode: hal::spi::MODE_0,
+        }))
+        .void_unwrap();
+    }
+
+    fn transfer(&mut self, byte: u8) -> u8 {
+        nb::block!(self.spi.send(byte)).void_unwrap();
+        nb::block!(self.spi.read()).void_unwrap()
+    }
+}
+
+/// Fastest SPI clock rate which does not exceed the 400 kHz allowed during initialization.
+fn init_clock_rate<CLOCK: crate::clock::Clock>() -> SerialClockRate {
+    let rates = [
+        (2, SerialClockRate::OscfOver2),
+        (4, SerialClockRate::OscfOver4),
+        (8, SerialClockRate::OscfOver8),
+        (16, SerialClockRate::OscfOver16),
+        (32, SerialClockRate::OscfOver32),
+        (64, SerialClockRate::OscfOver64),
+    ];
+    for (divider, rate) in rates.iter() {
+        if CLOCK::FREQ / divider <= 400_000 {
+            return *rate;
+        }
+    }
+    SerialClockRate::OscfOver128
+}
+
+/// CRC7 of a command frame, already shifted and with the end bit set.
+fn crc7(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for b in data.iter() {
+        let mut d = *b;
+        for _ in 0..8 {
+            crc <<= 1;
+            if (d ^ crc) & 0x80 != 0 {
+                crc ^= 0x09;
+            }
+            d <<= 1;
+        }
+    }
+    (crc << 1) | 1
+}
+
+/// CRC16 (CCITT) of a data block.
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for b in data.iter() {
+        crc ^= (*b as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
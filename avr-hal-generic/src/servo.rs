@@ -0,0 +1,571 @@
+//! RC servo control
+//!
+//! RC servos expect a pulse every 20 ms whose width sets the position, usually between about 1 ms
+//! and 2 ms.  Two drivers are available:
+//!
+//! - `ServoN` uses a 16-bit timer in fast PWM mode with `ICRn` as TOP for a 50 Hz period.  Each
+//!   compare output of the timer drives one servo in hardware, without any CPU load.
+//! - `MultiServoN` drives up to [`MAX_SERVOS`] servos on arbitrary output pins, like Arduino's
+//!   `Servo` library.  The pulses are generated one after another from the `TIMERn_COMPA`
+//!   interrupt, which the application needs to forward to the driver.  Its channels borrow the
+//!   driver, and dropping a channel frees it for another pin.
+//!
+//! Both drivers hand out [`Servo`] channels whose position can be set in microseconds or degrees.
+//! The pulse width range of each channel is configurable with a [`ServoConfig`].
+//!
+//! # Example
+//! ```
+//! let mut servo1 = Servo1::<MHz16>::new(dp.TC1);
+//! let mut servo = pins.d9.into_output(&mut pins.ddr).into_servo(&mut servo1, Default::default());
+//!
+//! servo.set_degrees(90);
+//! servo.set_pulse(1200.us());
+//! ```
+//!
+//! With the software-multiplexed driver:
+//! ```
+//! let servos = MultiServo1::<MHz16>::new(dp.TC1);
+//! let mut servo = servos
+//!     .attach(pins.d2.into_output(&mut pins.ddr).downgrade(), Default::default())
+//!     .unwrap();
+//! unsafe { avr_device::interrupt::enable() };
+//!
+//! servo.set_degrees(45);
+//!
+//! // Free the channel and get the pin back
+//! let pin = servos.detach(servo);
+//!
+//! #[avr_device::interrupt(atmega328p)]
+//! fn TIMER1_COMPA() {
+//!     MultiServo1::<MHz16>::on_compare();
+//! }
+//! ```
+
+use crate::hal::digital::v2::OutputPin;
+use crate::timer::Microseconds;
+
+/// Maximum number of servos driven by one `MultiServoN` driver.
+pub const MAX_SERVOS: usize = 12;
+
+/// Timer prescaler used by the servo drivers.
+#[doc(hidden)]
+pub const PRESCALER: u32 = 8;
+
+/// Period of the servo signal in milliseconds.
+#[doc(hidden)]
+pub const PERIOD_MS: u32 = 20;
+
+/// Pulse width range of a servo
+///
+/// The default range of 544 µs to 2400 µs is the same as in Arduino's `Servo` library.  Most
+/// servos can be driven a bit beyond their nominal 1 ms to 2 ms range, but the exact limits vary
+/// and the servo might stall when driven too far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServoConfig {
+    /// Pulse width for 0°.
+    pub min_pulse: Microseconds,
+    /// Pulse width for 180°.
+    pub max_pulse: Microseconds,
+}
+
+impl Default for ServoConfig {
+    fn default() -> Self {
+        Self {
+            min_pulse: Microseconds(544),
+            max_pulse: Microseconds(2400),
+        }
+    }
+}
+
+impl ServoConfig {
+    fn clamp(&self, pulse: Microseconds) -> Microseconds {
+        Microseconds(pulse.0.max(self.min_pulse.0).min(self.max_pulse.0))
+    }
+
+    fn center(&self) -> Microseconds {
+        Microseconds((self.min_pulse.0 + self.max_pulse.0) / 2)
+    }
+}
+
+/// Low-level output of a servo channel.
+#[doc(hidden)]
+pub trait ServoOutput {
+    /// Timer ticks per millisecond.
+    fn ticks_per_ms(&self) -> u32;
+    fn set_ticks(&mut self, ticks: u16);
+    fn ticks(&self) -> u16;
+    fn set_enabled(&mut self, enabled: bool);
+}
+
+/// A single servo channel
+///
+/// Channels are created by the servo drivers, see the [module documentation](self).  A new
+/// channel starts out enabled, with the servo centered.
+pub struct Servo<OUT> {
+    output: OUT,
+    config: ServoConfig,
+}
+
+impl<OUT: ServoOutput> Servo<OUT> {
+    #[doc(hidden)]
+    pub fn new(output: OUT, config: ServoConfig) -> Self {
+        let mut servo = Servo { output, config };
+        servo.set_pulse(config.center());
+        servo.enable();
+        servo
+    }
+
+    /// The pulse width range of this channel.
+    pub fn config(&self) -> ServoConfig {
+        self.config
+    }
+
+    /// Change the pulse width range.  The current pulse width is clamped to the new range.
+    pub fn set_config(&mut self, config: ServoConfig) {
+        self.config = config;
+        self.set_pulse(self.pulse());
+    }
+
+    /// Set the pulse width.  It is clamped to the range of the [`ServoConfig`].
+    pub fn set_pulse(&mut self, pulse: Microseconds) {
+        let pulse = self.config.clamp(pulse);
+        let ticks = pulse.0 * self.output.ticks_per_ms() / 1000;
+        self.output.set_ticks(ticks as u16);
+    }
+
+    /// The current pulse width.
+    ///
+    /// Due to the limited resolution of the timer, this can slightly differ from the value passed
+    /// to `set_pulse()`.
+    pub fn pulse(&self) -> Microseconds {
+        Microseconds(self.output.ticks() as u32 * 1000 / self.output.ticks_per_ms())
+    }
+
+    /// Move the servo to a position between 0° and 180°.
+    pub fn set_degrees(&mut self, degrees: u16) {
+        let degrees = degrees.min(180) as u32;
+        let range = self
+            .config
+            .max_pulse
+            .0
+            .saturating_sub(self.config.min_pulse.0);
+        self.set_pulse(Microseconds(
+            self.config.min_pulse.0 + range * degrees / 180,
+        ));
+    }
+
+    /// The current position in degrees.
+    pub fn degrees(&self) -> u16 {
+        let range = self
+            .config
+            .max_pulse
+            .0
+            .saturating_sub(self.config.min_pulse.0);
+        if range == 0 {
+            return 0;
+        }
+        let offset = self.pulse().0.saturating_sub(self.config.min_pulse.0);
+        ((offset * 180 + range / 2) / range).min(180) as u16
+    }
+
+    /// Start sending pulses to the servo.
+    pub fn enable(&mut self) {
+        self.output.set_enabled(true);
+    }
+
+    /// Stop sending pulses.  Most servos no longer hold their position without a signal.
+    pub fn disable(&mut self) {
+        self.output.set_enabled(false);
+    }
+
+    #[doc(hidden)]
+    pub fn into_output(self) -> OUT {
+        self.output
+    }
+}
+
+/// Shared state between the compare interrupt and the `MultiServoN` driver.
+#[doc(hidden)]
+pub struct MultiServoState<PIN> {
+    pins: [Option<PIN>; MAX_SERVOS],
+    ticks: [u16; MAX_SERVOS],
+    enabled: [bool; MAX_SERVOS],
+    /// Channel whose pulse is currently being output, `MAX_SERVOS` between frames.
+    current: usize,
+    /// Length of a frame in timer ticks.
+    frame: u16,
+}
+
+impl<PIN: OutputPin> MultiServoState<PIN> {
+    pub fn new(frame: u16) -> Self {
+        MultiServoState {
+            pins: Default::default(),
+            ticks: Default::default(),
+            enabled: Default::default(),
+            current: MAX_SERVOS,
+            frame,
+        }
+    }
+
+    /// Add a pin, returning its channel index or the pin if all channels are taken.
+    pub fn attach(&mut self, mut pin: PIN) -> Result<usize, PIN> {
+        match self.pins.iter().position(|p| p.is_none()) {
+            Some(index) => {
+                pin.set_low().ok();
+                self.pins[index] = Some(pin);
+                self.enabled[index] = false;
+                Ok(index)
+            }
+            None => Err(pin),
+        }
+    }
+
+    /// Remove the pin of channel `index`, setting it low.
+    pub fn detach(&mut self, index: usize) -> Option<PIN> {
+        self.enabled[index] = false;
+        let mut pin = self.pins[index].take()?;
+        pin.set_low().ok();
+        Some(pin)
+    }
+
+    pub fn set_ticks(&mut self, index: usize, ticks: u16) {
+        self.ticks[index] = ticks;
+    }
+
+    pub fn ticks(&self, index: usize) -> u16 {
+        self.ticks[index]
+    }
+
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        self.enabled[index] = enabled;
+    }
+
+    /// Set all pins low, ending the pulse which might be in progress.
+    pub fn stop(&mut self) {
+        for pin in self.pins.iter_mut().flatten() {
+            pin.set_low().ok();
+        }
+        self.current = MAX_SERVOS;
+    }
+
+    /// End the current pulse and start the next one.
+    ///
+    /// `now` is the current counter value.  Returns whether the counter must be reset to zero for
+    /// the start of a new frame and the counter value for the next compare match.
+    pub fn next(&mut self, now: u16) -> (bool, u16) {
+        let (restart, now, first) = if self.current == MAX_SERVOS {
+            (true, 0, 0)
+        } else {
+            if let Some(pin) = self.pins[self.current].as_mut() {
+                pin.set_low().ok();
+            }
+            (false, now, self.current + 1)
+        };
+
+        for index in first..MAX_SERVOS {
+            if let (Some(pin), true) = (self.pins[index].as_mut(), self.enabled[index]) {
+                pin.set_high().ok();
+                self.current = index;
+                return (restart, now.saturating_add(self.ticks[index]));
+            }
+        }
+
+        // All pulses are done, wait for the start of the next frame.  If the pulses took longer
+        // than a frame, the next frame starts right away.
+        self.current = MAX_SERVOS;
+        (restart, self.frame.max(now.saturating_add(16)))
+    }
+}
+
+/// Implement the hardware servo driver for a 16-bit timer
+#[macro_export]
+macro_rules! impl_servo {
+    (
+        $(#[$servo_attr:meta])*
+        pub struct $Servo:ident {
+            peripheral: $TIMER:ty,
+            register_suffix: $n:expr,
+            pins: {$(
+                $port:ident::$PXi:ident: {
+                    channel: $ch:ident,
+                    method: $into_servo:ident,
+                },
+            )+},
+        }
+    ) => {
+        $crate::paste::paste! {
+            $(#[$servo_attr])*
+            pub struct $Servo<CLOCK> {
+                timer: $TIMER,
+                _clock: core::marker::PhantomData<CLOCK>,
+            }
+
+            impl<CLOCK: $crate::clock::Clock> $Servo<CLOCK> {
+                /// Take ownership of the timer and start it with a 50 Hz period.
+                pub fn new(timer: $TIMER) -> $Servo<CLOCK> {
+                    let top = CLOCK::FREQ / $crate::servo::PRESCALER / 1000
+                        * $crate::servo::PERIOD_MS - 1;
+
+                    // Fast PWM with ICRn as TOP (mode 14), prescaler 8
+                    timer.[<tccr $n b>].reset();
+                    timer.[<tccr $n a>].write(|w| unsafe { w.bits(0b0000_0010) });
+                    timer.[<tcnt $n>].reset();
+                    timer.[<icr $n>].write(|w| unsafe { w.bits(top as u16) });
+                    timer.[<tccr $n b>].write(|w| unsafe { w.bits(0b0001_1010) });
+
+                    $Servo {
+                        timer,
+                        _clock: core::marker::PhantomData,
+                    }
+                }
+
+                /// Stop the timer and return the peripheral.
+                pub fn release(self) -> $TIMER {
+                    self.timer.[<tccr $n b>].reset();
+                    self.timer.[<tccr $n a>].reset();
+                    self.timer
+                }
+            }
+
+            $(
+                impl $port::$PXi<$crate::port::mode::Output> {
+                    /// Drive a servo from this pin.
+                    pub fn $into_servo<CLOCK: $crate::clock::Clock>(
+                        self,
+                        servo: &mut $Servo<CLOCK>,
+                        config: $crate::servo::ServoConfig,
+                    ) -> $crate::servo::Servo<
+                        $port::$PXi<$crate::port::mode::Pwm<$Servo<CLOCK>>>
+                    > {
+                        $crate::servo::Servo::new(
+                            $port::$PXi { _mode: core::marker::PhantomData },
+                            config,
+                        )
+                    }
+                }
+
+                impl<CLOCK: $crate::clock::Clock> $crate::servo::ServoOutput
+                    for $port::$PXi<$crate::port::mode::Pwm<$Servo<CLOCK>>>
+                {
+                    fn ticks_per_ms(&self) -> u32 {
+                        CLOCK::FREQ / $crate::servo::PRESCALER / 1000
+                    }
+
+                    fn set_ticks(&mut self, ticks: u16) {
+                        // SAFETY: This register is exclusively used here so there are no
+                        // concurrency issues.
+                        let timer = unsafe { &*<$TIMER>::ptr() };
+                        timer.[<ocr $n $ch>].write(|w| unsafe { w.bits(ticks) });
+                    }
+
+                    fn ticks(&self) -> u16 {
+                        unsafe { &*<$TIMER>::ptr() }.[<ocr $n $ch>].read().bits()
+                    }
+
+                    fn set_enabled(&mut self, enabled: bool) {
+                        // SAFETY: The read-modify-write sequence is wrapped in a critical section
+                        // as other channels share the register.
+                        $crate::avr_device::interrupt::free(|_| {
+                            let timer = unsafe { &*<$TIMER>::ptr() };
+                            timer.[<tccr $n a>].modify(|_, w| if enabled {
+                                w.[<com $n $ch>]().match_clear()
+                            } else {
+                                w.[<com $n $ch>]().disconnected()
+                            });
+                        })
+                    }
+                }
+            )+
+        }
+    };
+}
+
+/// Implement the software-multiplexed servo driver for a 16-bit timer
+#[macro_export]
+macro_rules! impl_multi_servo {
+    (
+        $(#[$servo_attr:meta])*
+        pub struct $MultiServo:ident {
+            peripheral: $TIMER:ty,
+            register_suffix: $n:expr,
+            pin: $Pin:ty,
+        }
+    ) => {
+        $crate::paste::paste! {
+            $(#[$servo_attr])*
+            pub struct $MultiServo<CLOCK> {
+                timer: $TIMER,
+                _clock: core::marker::PhantomData<CLOCK>,
+            }
+
+            /// A servo channel of the software-multiplexed servo driver
+            ///
+            /// The channel borrows the driver, so it cannot outlive it.  Dropping the channel
+            /// frees it and drops its pin; use `detach()` of the driver to get the pin back
+            /// instead.
+            pub struct [<$MultiServo Channel>]<'a, CLOCK: $crate::clock::Clock> {
+                index: usize,
+                _driver: core::marker::PhantomData<&'a $MultiServo<CLOCK>>,
+            }
+
+            impl<CLOCK: $crate::clock::Clock> $MultiServo<CLOCK> {
+                fn state() -> &'static $crate::avr_device::interrupt::Mutex<
+                    core::cell::RefCell<Option<$crate::servo::MultiServoState<$Pin>>>
+                > {
+                    static STATE: $crate::avr_device::interrupt::Mutex<
+                        core::cell::RefCell<Option<$crate::servo::MultiServoState<$Pin>>>
+                    > = $crate::avr_device::interrupt::Mutex::new(core::cell::RefCell::new(None));
+                    &STATE
+                }
+
+                /// Take ownership of the timer and start generating pulses.
+                ///
+                /// Interrupts must be enabled globally and the `TIMERn_COMPA` interrupt must call
+                /// [`on_compare()`](#method.on_compare).
+                pub fn new(timer: $TIMER) -> $MultiServo<CLOCK> {
+                    let frame = CLOCK::FREQ / $crate::servo::PRESCALER / 1000
+                        * $crate::servo::PERIOD_MS;
+
+                    $crate::avr_device::interrupt::free(|cs| {
+                        Self::state().borrow(cs).replace(Some(
+                            $crate::servo::MultiServoState::new(frame as u16)
+                        ));
+                    });
+
+                    // Normal mode, prescaler 8
+                    timer.[<tccr $n b>].reset();
+                    timer.[<tccr $n a>].reset();
+                    timer.[<tcnt $n>].reset();
+                    timer.[<ocr $n a>].write(|w| unsafe { w.bits(frame as u16) });
+                    timer.[<tifr $n>].write(|w| w.[<ocf $n a>]().set_bit());
+                    timer.[<timsk $n>].modify(|_, w| w.[<ocie $n a>]().set_bit());
+                    timer.[<tccr $n b>].write(|w| unsafe { w.bits(0b0000_0010) });
+
+                    $MultiServo {
+                        timer,
+                        _clock: core::marker::PhantomData,
+                    }
+                }
+
+                /// Stop the timer and return the peripheral.  All pins are set low and dropped.
+                ///
+                /// All channels must have been dropped or detached before.
+                pub fn release(self) -> $TIMER {
+                    self.timer.[<tccr $n b>].reset();
+                    self.timer.[<timsk $n>].modify(|_, w| w.[<ocie $n a>]().clear_bit());
+                    $crate::avr_device::interrupt::free(|cs| {
+                        if let Some(mut state) = Self::state().borrow(cs).replace(None) {
+                            state.stop();
+                        }
+                    });
+                    self.timer
+                }
+
+                /// Drive a servo from this pin.
+                ///
+                /// If all channels are taken, the pin is returned as the error.
+                pub fn attach(
+                    &self,
+                    pin: $Pin,
+                    config: $crate::servo::ServoConfig,
+                ) -> Result<$crate::servo::Servo<[<$MultiServo Channel>]<'_, CLOCK>>, $Pin> {
+                    let index = $crate::avr_device::interrupt::free(|cs| {
+                        Self::state()
+                            .borrow(cs)
+                            .borrow_mut()
+                            .as_mut()
+                            .expect("servo state missing")
+                            .attach(pin)
+                    })?;
+
+                    Ok($crate::servo::Servo::new(
+                        [<$MultiServo Channel>] {
+                            index,
+                            _driver: core::marker::PhantomData,
+                        },
+                        config,
+                    ))
+                }
+
+                /// Stop driving a servo and return its pin, which is set low.
+                ///
+                /// The channel is free for another pin afterwards.
+                pub fn detach(
+                    &self,
+                    servo: $crate::servo::Servo<[<$MultiServo Channel>]<'_, CLOCK>>,
+                ) -> $Pin {
+                    let channel = servo.into_output();
+                    let index = channel.index;
+                    channel
+                        .with_state(|state| state.detach(index))
+                        .expect("servo channel is attached")
+                }
+
+                /// End the current pulse and start the next one.
+                ///
+                /// This function must be called from the `TIMERn_COMPA` interrupt.
+                pub fn on_compare() {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        // SAFETY: Once the driver is set up, the counter and the compare register
+                        // are only accessed here.
+                        let timer = unsafe { &*<$TIMER>::ptr() };
+
+                        if let Some(state) = Self::state().borrow(cs).borrow_mut().as_mut() {
+                            let (restart, compare) = state.next(timer.[<tcnt $n>].read().bits());
+                            if restart {
+                                timer.[<tcnt $n>].reset();
+                            }
+                            timer.[<ocr $n a>].write(|w| unsafe { w.bits(compare) });
+                        }
+                    })
+                }
+            }
+
+            impl<CLOCK: $crate::clock::Clock> [<$MultiServo Channel>]<'_, CLOCK> {
+                fn with_state<R>(
+                    &self,
+                    f: impl FnOnce(&mut $crate::servo::MultiServoState<$Pin>) -> R,
+                ) -> R {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        f($MultiServo::<CLOCK>::state()
+                            .borrow(cs)
+                            .borrow_mut()
+                            .as_mut()
+                            .expect("servo state missing"))
+                    })
+                }
+            }
+
+            impl<CLOCK: $crate::clock::Clock> Drop for [<$MultiServo Channel>]<'_, CLOCK> {
+                fn drop(&mut self) {
+                    // The pin was already taken if the channel is dropped by `detach()`
+                    let index = self.index;
+                    self.with_state(|state| state.detach(index));
+                }
+            }
+
+            impl<CLOCK: $crate::clock::Clock> $crate::servo::ServoOutput
+                for [<$MultiServo Channel>]<'_, CLOCK>
+            {
+                fn ticks_per_ms(&self) -> u32 {
+                    CLOCK::FREQ / $crate::servo::PRESCALER / 1000
+                }
+
+                fn set_ticks(&mut self, ticks: u16) {
+                    let index = self.index;
+                    self.with_state(|state| state.set_ticks(index, ticks))
+                }
+
+                fn ticks(&self) -> u16 {
+                    let index = self.index;
+                    self.with_state(|state| state.ticks(index))
+                }
+
+                fn set_enabled(&mut self, enabled: bool) {
+                    let index = self.index;
+                    self.with_state(|state| state.set_enabled(index, enabled))
+                }
+            }
+        }
+    };
+}
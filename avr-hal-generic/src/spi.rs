@@ -64,6 +64,14 @@ impl Default for Settings {
     }
 }
 
+/// Runtime reconfiguration of an SPI peripheral
+///
+/// Implemented by all SPI types created with [`impl_spi!`] so drivers (e.g. the
+/// [`SdCard`](crate::sdcard::SdCard) driver) can switch bus speed or mode on their own.
+pub trait Reconfigure {
+    /// Wait for any in-flight transfer to complete and apply new settings.
+    fn reconfigure(&mut self, settings: Settings) -> nb::Result<(), void::Void>;
+}
 
 /// Implement traits for a SPI interface
 #[macro_export]
@@ -308,6 +316,12 @@ macro_rules! impl_spi {
             }
         }
 
+        impl<MisoInputMode: $crate::port::mode::InputMode> $crate::spi::Reconfigure for $Spi<MisoInputMode> {
+            fn reconfigure(&mut self, settings: Settings) -> $crate::nb::Result<(), $crate::void::Void> {
+                $Spi::reconfigure(self, settings)
+            }
+        }
+
         /// FullDuplex trait implementation, allowing this struct to be provided to
         /// drivers that require it for operation.  Only 8-bit word size is supported
         /// for now.
@@ -0,0 +1,236 @@
+//! System time service providing `millis()` and `micros()`
+//!
+//! The time service claims one timer (usually `TC0`) and lets it generate a compare-match
+//! interrupt every few hundred microseconds.  The interrupt advances a global time counter; the
+//! current value of the timer is added on top so the time has a resolution of a few microseconds.
+//!
+//! The application needs to forward the timer interrupt to the time service:
+//!
+//! ```
+//! let systime = SysTime::<MHz16>::new(dp.TC0);
+//! unsafe { avr_device::interrupt::enable() };
+//!
+//! let start = systime.millis();
+//! // ...
+//! let elapsed = systime.millis().wrapping_sub(start);
+//!
+//! #[avr_device::interrupt(atmega328p)]
+//! fn TIMER0_COMPA() {
+//!     SysTime::<MHz16>::on_interrupt();
+//! }
+//! ```
+//!
+//! Both counters wrap around: `millis()` after about 49 days and `micros()` after about 71
+//! minutes.  Compute time differences with `wrapping_sub()` to handle this correctly.
+//!
+//! With the `embedded-time` feature enabled, the time service also implements the
+//! `embedded_time::Clock` trait with microsecond resolution.
+
+use core::cell;
+
+/// Prescaler the time service runs its timer with.
+#[doc(hidden)]
+pub const PRESCALER: u32 = 64;
+
+/// Number of timer ticks per interrupt for a clock frequency.
+///
+/// The number is chosen such that one period is an exact number of microseconds, so the time does
+/// not drift.  Returns 0 if there is no such number.
+#[doc(hidden)]
+pub const fn ticks_per_period(freq: u32) -> u32 {
+    let mut ticks = 256;
+    while ticks > 0 && (ticks as u64 * PRESCALER as u64 * 1_000_000) % freq as u64 != 0 {
+        ticks -= 1;
+    }
+    ticks
+}
+
+#[derive(Clone, Copy)]
+struct State {
+    micros: u32,
+    millis: u32,
+    /// Microseconds which were not yet accounted for in `millis`
+    fraction: u32,
+}
+
+static STATE: avr_device::interrupt::Mutex<cell::Cell<State>> =
+    avr_device::interrupt::Mutex::new(cell::Cell::new(State {
+        micros: 0,
+        millis: 0,
+        fraction: 0,
+    }));
+
+#[doc(hidden)]
+pub fn reset(cs: &avr_device::interrupt::CriticalSection) {
+    STATE.borrow(cs).set(State {
+        micros: 0,
+        millis: 0,
+        fraction: 0,
+    });
+}
+
+#[doc(hidden)]
+pub fn advance(period_us: u32) {
+    avr_device::interrupt::free(|cs| {
+        let cell = STATE.borrow(cs);
+        let mut state = cell.get();
+
+        state.micros = state.micros.wrapping_add(period_us);
+        state.fraction += period_us;
+        state.millis = state.millis.wrapping_add(state.fraction / 1000);
+        state.fraction %= 1000;
+
+        cell.set(state);
+    })
+}
+
+/// Returns the current `(millis, micros)` given the microseconds elapsed since the last interrupt.
+#[doc(hidden)]
+pub fn now(cs: &avr_device::interrupt::CriticalSection, elapsed_us: u32) -> (u32, u32) {
+    let state = STATE.borrow(cs).get();
+    (
+        state
+            .millis
+            .wrapping_add((state.fraction + elapsed_us) / 1000),
+        state.micros.wrapping_add(elapsed_us),
+    )
+}
+
+/// Implement the system time service for a timer
+#[macro_export]
+macro_rules! impl_systime {
+    (
+        $(#[$systime_attr:meta])*
+        pub struct $SysTime:ident {
+            timer: $TIMER:ty,
+            start: |$start_timer:ident, $top:ident| $start_block:block,
+            stop: |$stop_timer:ident| $stop_block:block,
+            counter: |$counter_timer:ident| $counter_block:block,
+            is_pending: |$pending_timer:ident| $pending_block:block,
+        }
+    ) => {
+        $(#[$systime_attr])*
+        pub struct $SysTime<CLOCK> {
+            timer: $TIMER,
+            _clock: core::marker::PhantomData<CLOCK>,
+        }
+
+        impl<CLOCK: $crate::clock::Clock> $SysTime<CLOCK> {
+            const TICKS: u32 = {
+                let ticks = $crate::systime::ticks_per_period(CLOCK::FREQ);
+                let period_us =
+                    ticks as u64 * $crate::systime::PRESCALER as u64 * 1_000_000
+                        / CLOCK::FREQ as u64;
+                // Fails to compile when evaluated for a clock which does not allow a period of an
+                // exact number of microseconds, or for which `now()` would overflow
+                let _ = [()][(ticks == 0 || ticks as u64 * period_us > u32::MAX as u64) as usize];
+                ticks
+            };
+            const PERIOD_US: u32 = (Self::TICKS as u64 * $crate::systime::PRESCALER as u64
+                * 1_000_000
+                / CLOCK::FREQ as u64) as u32;
+
+            /// Start the time service on this timer.
+            ///
+            /// The time starts at zero.  Interrupts must be enabled globally for the time to
+            /// advance.  Fails to compile for a clock which does not allow an interrupt period of
+            /// an exact number of microseconds.
+            pub fn new(timer: $TIMER) -> $SysTime<CLOCK> {
+                $crate::avr_device::interrupt::free(|cs| {
+                    $crate::systime::reset(cs);
+
+                    let $start_timer = &timer;
+                    let $top = (Self::TICKS - 1) as u8;
+                    $start_block
+                });
+
+                $SysTime {
+                    timer,
+                    _clock: core::marker::PhantomData,
+                }
+            }
+
+            /// Stop the time service and return the timer peripheral.
+            pub fn release(self) -> $TIMER {
+                let $stop_timer = &self.timer;
+                $stop_block
+                self.timer
+            }
+
+            /// Advance the time.
+            ///
+            /// This function must be called from the timer's compare-match interrupt
+            /// (`TIMER0_COMPA` for `TC0`).
+            pub fn on_interrupt() {
+                $crate::systime::advance(Self::PERIOD_US);
+            }
+
+            /// Milliseconds since the time service was started.
+            ///
+            /// Wraps around after about 49 days.
+            pub fn millis(&self) -> u32 {
+                self.now().0
+            }
+
+            /// Microseconds since the time service was started.
+            ///
+            /// Wraps around after about 71 minutes.  The resolution depends on the clock speed,
+            /// at 16 MHz it is 4 µs.
+            pub fn micros(&self) -> u32 {
+                self.now().1
+            }
+
+            fn now(&self) -> (u32, u32) {
+                $crate::avr_device::interrupt::free(|cs| {
+                    let ticks = {
+                        let $counter_timer = &self.timer;
+                        $counter_block
+                    } as u32;
+                    let pending = {
+                        let $pending_timer = &self.timer;
+                        $pending_block
+                    };
+
+                    let mut elapsed = ticks * Self::PERIOD_US / Self::TICKS;
+                    // The timer has already wrapped around but the interrupt was not handled yet.
+                    // If the counter is still at TOP, it was read before the wrap-around.
+                    if pending && ticks < Self::TICKS - 1 {
+                        elapsed += Self::PERIOD_US;
+                    }
+
+                    $crate::systime::now(cs, elapsed)
+                })
+            }
+        }
+
+        $crate::__systime_embedded_time!($SysTime);
+    };
+}
+
+#[cfg(feature = "embedded-time")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __systime_embedded_time {
+    ($SysTime:ident) => {
+        impl<CLOCK: $crate::clock::Clock> $crate::embedded_time::Clock for $SysTime<CLOCK> {
+            type T = u32;
+
+            const SCALING_FACTOR: $crate::embedded_time::fraction::Fraction =
+                $crate::embedded_time::fraction::Fraction::new(1, 1_000_000);
+
+            fn try_now(
+                &self,
+            ) -> Result<$crate::embedded_time::Instant<Self>, $crate::embedded_time::clock::Error>
+            {
+                Ok($crate::embedded_time::Instant::new(self.micros()))
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "embedded-time"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __systime_embedded_time {
+    ($SysTime:ident) => {};
+}
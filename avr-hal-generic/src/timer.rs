@@ -0,0 +1,333 @@
+//! Timer/Counter abstraction
+//!
+//! The timer/counter peripherals can be used as count-down timers implementing
+//! `embedded_hal::timer::CountDown` and `embedded_hal::timer::Periodic`.  The timer runs in CTC
+//! (clear timer on compare match) mode: Prescaler and TOP value are calculated from the requested
+//! duration and the clock speed.  Once started, the timer restarts automatically, so each call to
+//! `wait()` completes after another period has passed.
+//!
+//! If the requested duration is longer than what the timer can count, the longest possible
+//! period is used instead.  The actual period can be checked with the `period()` method.
+//!
+//! # Example
+//! ```
+//! let mut timer = Timer1::<MHz16>::new(dp.TC1);
+//!
+//! timer.start(500.ms());
+//! loop {
+//!     nb::block!(timer.wait()).void_unwrap();
+//!     led.toggle().void_unwrap();
+//! }
+//! ```
+//!
+//! Instead of polling, the timer can also trigger the compare-match interrupt (`TIMERn_COMPA`)
+//! after each period when `listen(Event::Timeout)` is called.
+
+/// Duration in microseconds
+///
+/// This is the time unit used by the [`CountDown`](crate::hal::timer::CountDown) implementations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Microseconds(pub u32);
+
+/// Duration in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Milliseconds(pub u32);
+
+/// Frequency in hertz
+///
+/// Used as a timer duration, this means one period of the frequency.  A frequency of zero is
+/// converted to the longest possible duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hertz(pub u32);
+
+impl From<Milliseconds> for Microseconds {
+    fn from(ms: Milliseconds) -> Self {
+        Microseconds(ms.0.saturating_mul(1000))
+    }
+}
+
+impl From<Hertz> for Microseconds {
+    fn from(hz: Hertz) -> Self {
+        match hz.0 {
+            0 => Microseconds(u32::MAX),
+            hz => Microseconds(1_000_000 / hz),
+        }
+    }
+}
+
+/// Extension trait for creating durations and frequencies from plain numbers
+///
+/// This allows writing
+/// ```
+/// timer.start(250.ms());
+/// ```
+pub trait TimeExt {
+    /// Duration of this many microseconds.
+    fn us(self) -> Microseconds;
+    /// Duration of this many milliseconds.
+    fn ms(self) -> Milliseconds;
+    /// Frequency of this many hertz.
+    fn hz(self) -> Hertz;
+}
+
+impl TimeExt for u32 {
+    fn us(self) -> Microseconds {
+        Microseconds(self)
+    }
+
+    fn ms(self) -> Milliseconds {
+        Milliseconds(self)
+    }
+
+    fn hz(self) -> Hertz {
+        Hertz(self)
+    }
+}
+
+/// Events/Interrupts for timers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The timer period has elapsed.
+    ///
+    /// Corresponds to the `TIMERn_COMPA` interrupt.  Please refer to the datasheet for your MCU
+    /// for details.
+    Timeout,
+
+    /// The counter overflowed.
+    ///
+    /// In CTC mode this only happens when the TOP value is the maximum counter value.  Corresponds
+    /// to the `TIMERn_OVF` interrupt.
+    Overflow,
+}
+
+/// Find the smallest prescaler for which `cycles` fit into the counter.
+///
+/// `prescalers` is a list of division factors and their clock-select bits, sorted by the division
+/// factor.  Returns the chosen prescaler and the TOP value.  If the duration is too long, the
+/// largest prescaler and `max` are returned.
+#[doc(hidden)]
+pub fn calculate_period(cycles: u32, prescalers: &[(u16, u8)], max: u16) -> ((u16, u8), u16) {
+    for &prescaler in prescalers.iter() {
+        let ticks = cycles / prescaler.0 as u32;
+        if ticks <= max as u32 + 1 {
+            return (prescaler, ticks.saturating_sub(1) as u16);
+        }
+    }
+    (prescalers[prescalers.len() - 1], max)
+}
+
+/// Implement a count-down timer for a timer/counter peripheral
+#[macro_export]
+macro_rules! impl_timer {
+    (
+        $(#[$timer_attr:meta])*
+        pub struct $Timer:ident {
+            timer: $TIMER:ty,
+            counter: $Counter:ty,
+            prescalers: { $($div:expr => $cs:expr,)+ },
+            start: |$start_timer:ident, $cs_bits:ident, $top:ident| $start_block:block,
+            stop: |$stop_timer:ident| $stop_block:block,
+            is_elapsed: |$elapsed_timer:ident| $elapsed_block:block,
+            clear: |$clear_timer:ident| $clear_block:block,
+            interrupt: |$int_timer:ident, $event:ident, $state:ident| $int_block:block,
+        }
+    ) => {
+        $(#[$timer_attr])*
+        pub struct $Timer<CLOCK> {
+            timer: $TIMER,
+            period: $crate::timer::Microseconds,
+            _clock: core::marker::PhantomData<CLOCK>,
+        }
+
+        impl<CLOCK: $crate::clock::Clock> $Timer<CLOCK> {
+            /// Take ownership of the timer peripheral.  The timer is stopped until
+            /// [`start()`](#method.start) is called.
+            pub fn new(timer: $TIMER) -> $Timer<CLOCK> {
+                let mut t = $Timer {
+                    timer,
+                    period: $crate::timer::Microseconds(0),
+                    _clock: core::marker::PhantomData,
+                };
+                t.stop();
+                t
+            }
+
+            /// Stop the timer and return the peripheral.
+            pub fn release(mut self) -> $TIMER {
+                self.stop();
+                self.timer
+            }
+
+            /// Stop the timer.  It can be restarted with [`start()`](#method.start).
+            pub fn stop(&mut self) {
+                let $stop_timer = &mut self.timer;
+                $stop_block
+            }
+
+            /// The actual period the timer is running with.
+            ///
+            /// Due to the limited resolution of the timer, this can differ from the duration passed
+            /// to [`start()`](#method.start).
+            pub fn period(&self) -> $crate::timer::Microseconds {
+                self.period
+            }
+
+            /// Start listening for an interrupt event.
+            pub fn listen(&mut self, event: $crate::timer::Event) {
+                self.interrupt(event, true);
+            }
+
+            /// Stop listening for an interrupt event.
+            pub fn unlisten(&mut self, event: $crate::timer::Event) {
+                self.interrupt(event, false);
+            }
+
+            fn interrupt(&mut self, event: $crate::timer::Event, state: bool) {
+                let $int_timer = &mut self.timer;
+                let $event = event;
+                let $state = state;
+                $int_block
+            }
+        }
+
+        impl<CLOCK: $crate::clock::Clock> $crate::hal::timer::CountDown for $Timer<CLOCK> {
+            type Time = $crate::timer::Microseconds;
+
+            fn start<T>(&mut self, count: T)
+            where
+                T: Into<Self::Time>,
+            {
+                let cycles = count.into().0 as u64 * CLOCK::FREQ as u64 / 1_000_000;
+                let cycles = cycles.min(u32::MAX as u64) as u32;
+                let ((div, cs), top) = $crate::timer::calculate_period(
+                    cycles,
+                    &[$(($div, $cs),)+],
+                    <$Counter>::MAX as u16,
+                );
+
+                let period = (top as u64 + 1) * div as u64 * 1_000_000 / CLOCK::FREQ as u64;
+                self.period = $crate::timer::Microseconds(period.min(u32::MAX as u64) as u32);
+
+                let $start_timer = &mut self.timer;
+                let $cs_bits = cs;
+                let $top = top as $Counter;
+                $start_block
+            }
+
+            fn wait(&mut self) -> $crate::nb::Result<(), $crate::void::Void> {
+                let elapsed = {
+                    let $elapsed_timer = &mut self.timer;
+                    $elapsed_block
+                };
+                if elapsed {
+                    let $clear_timer = &mut self.timer;
+                    $clear_block
+                    Ok(())
+                } else {
+                    Err($crate::nb::Error::WouldBlock)
+                }
+            }
+        }
+
+        impl<CLOCK: $crate::clock::Clock> $crate::hal::timer::Periodic for $Timer<CLOCK> {}
+    };
+}
+
+/// Implement a count-down timer for the common timer/counter layout of the ATmega chips
+///
+/// The registers are named after the timer number (`TCCRnA`, `TCCRnB`, `TCNTn`, `OCRnA`,
+/// `TIMSKn`, `TIFRn`).  8-bit timers select CTC mode in `TCCRnA`, 16-bit timers in `TCCRnB`.
+#[macro_export]
+macro_rules! impl_timer_traditional {
+    (
+        $(#[$timer_attr:meta])*
+        pub struct $Timer:ident {
+            peripheral: $TIMER:ty,
+            register_suffix: $n:expr,
+            counter: u8,
+            prescalers: { $($div:expr => $cs:expr,)+ },
+        }
+    ) => {
+        $crate::impl_timer_traditional! {
+            @impl
+            $(#[$timer_attr])*
+            pub struct $Timer {
+                peripheral: $TIMER,
+                register_suffix: $n,
+                counter: u8,
+                // WGMn1 in TCCRnA
+                ctc: (0b0000_0010, 0b0000_0000),
+                prescalers: { $($div => $cs,)+ },
+            }
+        }
+    };
+    (
+        $(#[$timer_attr:meta])*
+        pub struct $Timer:ident {
+            peripheral: $TIMER:ty,
+            register_suffix: $n:expr,
+            counter: u16,
+            prescalers: { $($div:expr => $cs:expr,)+ },
+        }
+    ) => {
+        $crate::impl_timer_traditional! {
+            @impl
+            $(#[$timer_attr])*
+            pub struct $Timer {
+                peripheral: $TIMER,
+                register_suffix: $n,
+                counter: u16,
+                // WGMn2 in TCCRnB
+                ctc: (0b0000_0000, 0b0000_1000),
+                prescalers: { $($div => $cs,)+ },
+            }
+        }
+    };
+    (
+        @impl
+        $(#[$timer_attr:meta])*
+        pub struct $Timer:ident {
+            peripheral: $TIMER:ty,
+            register_suffix: $n:expr,
+            counter: $Counter:ty,
+            ctc: ($tccra:expr, $tccrb:expr),
+            prescalers: { $($div:expr => $cs:expr,)+ },
+        }
+    ) => {
+        $crate::paste::paste! {
+            $crate::impl_timer! {
+                $(#[$timer_attr])*
+                pub struct $Timer {
+                    timer: $TIMER,
+                    counter: $Counter,
+                    prescalers: { $($div => $cs,)+ },
+                    start: |tim, cs, top| {
+                        tim.[<tccr $n b>].reset();
+                        tim.[<tccr $n a>].write(|w| unsafe { w.bits($tccra) });
+                        tim.[<tcnt $n>].reset();
+                        tim.[<ocr $n a>].write(|w| unsafe { w.bits(top) });
+                        tim.[<tifr $n>].write(|w| w.[<ocf $n a>]().set_bit().[<tov $n>]().set_bit());
+                        tim.[<tccr $n b>].write(|w| unsafe { w.bits($tccrb | cs) });
+                    },
+                    stop: |tim| {
+                        tim.[<tccr $n b>].reset();
+                    },
+                    is_elapsed: |tim| {
+                        tim.[<tifr $n>].read().[<ocf $n a>]().bit_is_set()
+                    },
+                    clear: |tim| {
+                        tim.[<tifr $n>].write(|w| w.[<ocf $n a>]().set_bit());
+                    },
+                    interrupt: |tim, event, state| {
+                        match event {
+                            $crate::timer::Event::Timeout =>
+                                tim.[<timsk $n>].modify(|_, w| w.[<ocie $n a>]().bit(state)),
+                            $crate::timer::Event::Overflow =>
+                                tim.[<timsk $n>].modify(|_, w| w.[<toie $n>]().bit(state)),
+                        }
+                    },
+                }
+            }
+        }
+    };
+}
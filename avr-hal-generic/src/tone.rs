@@ -0,0 +1,434 @@
+//! Tone and square-wave generation
+//!
+//! A timer in CTC (clear timer on compare match) mode toggles an output each time it reaches its
+//! TOP value, which produces a square wave with 50% duty cycle.  Prescaler and TOP value are
+//! calculated from the requested frequency and the clock speed.  This is useful for buzzers or to
+//! provide a clock signal to other chips.
+//!
+//! Two drivers are available for each timer:
+//!
+//! - `ToneN` toggles the `OCnA` pin of the timer in hardware.  This does not need any CPU time,
+//!   unless the tone should stop after a certain duration.
+//! - `SoftToneN` toggles any output pin from the `TIMERn_COMPA` interrupt.  This is limited to
+//!   lower frequencies as each edge costs an interrupt.
+//!
+//! For tones with a duration and for `SoftToneN`, the application needs to forward the
+//! `TIMERn_COMPA` interrupt to the driver:
+//!
+//! ```
+//! let mut tone = Tone2::<MHz16>::new(dp.TC2, portb.pb3.into_output(&mut portb.ddr));
+//! unsafe { avr_device::interrupt::enable() };
+//!
+//! tone.play_for(440.hz(), 500.ms());
+//!
+//! #[avr_device::interrupt(atmega328p)]
+//! fn TIMER2_COMPA() {
+//!     Tone2::<MHz16>::on_compare();
+//! }
+//! ```
+//!
+//! If the requested frequency is too low for the timer, the lowest possible frequency is used
+//! instead.  The actual frequency can be checked with the `frequency()` method.
+
+use crate::hal::digital::v2::{OutputPin, ToggleableOutputPin};
+use crate::timer::{Hertz, Milliseconds};
+
+/// Find prescaler and TOP value for a square wave.
+///
+/// Returns the chosen prescaler, the TOP value and the actual frequency.
+#[doc(hidden)]
+pub fn calculate_tone(
+    clock: u32,
+    frequency: Hertz,
+    prescalers: &[(u16, u8)],
+    max: u16,
+) -> ((u16, u8), u16, Hertz) {
+    // The output is toggled twice per period
+    let cycles = clock / 2 / frequency.0.max(1);
+    let ((div, cs), top) = crate::timer::calculate_period(cycles, prescalers, max);
+    let actual = clock / 2 / div as u32 / (top as u32 + 1);
+    ((div, cs), top, Hertz(actual))
+}
+
+/// Number of output toggles for a tone of the given duration.
+#[doc(hidden)]
+pub fn toggles(frequency: Hertz, duration: Milliseconds) -> u32 {
+    let per_second = frequency.0.saturating_mul(2);
+    let millis = duration.0 % 1000;
+    let fraction = match per_second.checked_mul(millis) {
+        Some(toggles) => toggles / 1000,
+        None => per_second / 1000 * millis,
+    };
+    per_second
+        .saturating_mul(duration.0 / 1000)
+        .saturating_add(fraction)
+        .max(1)
+}
+
+/// Shared state between the compare interrupt and the tone drivers.
+#[doc(hidden)]
+pub struct ToneState<PIN> {
+    pin: Option<PIN>,
+    /// Remaining compare matches until the tone stops, `None` for a continuous tone.
+    remaining: Option<u32>,
+}
+
+impl<PIN> ToneState<PIN> {
+    pub const fn new() -> Self {
+        ToneState {
+            pin: None,
+            remaining: None,
+        }
+    }
+}
+
+impl<PIN: OutputPin + ToggleableOutputPin> ToneState<PIN> {
+    pub fn set_pin(&mut self, pin: Option<PIN>) -> Option<PIN> {
+        core::mem::replace(&mut self.pin, pin)
+    }
+
+    pub fn start(&mut self, toggles: Option<u32>) {
+        self.remaining = toggles;
+        if let Some(pin) = self.pin.as_mut() {
+            pin.set_low().ok();
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.remaining = None;
+        if let Some(pin) = self.pin.as_mut() {
+            pin.set_low().ok();
+        }
+    }
+
+    /// Handle a compare match.  Returns `false` once the tone is finished.
+    pub fn on_compare(&mut self) -> bool {
+        if let Some(pin) = self.pin.as_mut() {
+            pin.toggle().ok();
+        }
+        match self.remaining.as_mut() {
+            Some(remaining) => {
+                *remaining = remaining.saturating_sub(1);
+                *remaining != 0
+            }
+            None => true,
+        }
+    }
+}
+
+/// Implement tone generation for the common timer/counter layout of the ATmega chips
+///
+/// The registers are named after the timer number (`TCCRnA`, `TCCRnB`, `TCNTn`, `OCRnA`,
+/// `TIMSKn`, `TIFRn`).  8-bit timers select CTC mode in `TCCRnA`, 16-bit timers in `TCCRnB`.
+#[macro_export]
+macro_rules! impl_tone_traditional {
+    (
+        $(#[$tone_attr:meta])*
+        pub struct $Tone:ident {
+            peripheral: $TIMER:ty,
+            register_suffix: $n:expr,
+            counter: u8,
+            prescalers: { $($div:expr => $cs:expr,)+ },
+            pin: $pinmod:ident::$OC:ident,
+            soft_pin: $Pin:ty,
+        }
+    ) => {
+        $crate::impl_tone_traditional! {
+            @impl
+            $(#[$tone_attr])*
+            pub struct $Tone {
+                peripheral: $TIMER,
+                register_suffix: $n,
+                counter: u8,
+                // WGMn1 in TCCRnA
+                ctc: (0b0000_0010, 0b0000_0000),
+                prescalers: { $($div => $cs,)+ },
+                pin: $pinmod::$OC,
+                soft_pin: $Pin,
+            }
+        }
+    };
+    (
+        $(#[$tone_attr:meta])*
+        pub struct $Tone:ident {
+            peripheral: $TIMER:ty,
+            register_suffix: $n:expr,
+            counter: u16,
+            prescalers: { $($div:expr => $cs:expr,)+ },
+            pin: $pinmod:ident::$OC:ident,
+            soft_pin: $Pin:ty,
+        }
+    ) => {
+        $crate::impl_tone_traditional! {
+            @impl
+            $(#[$tone_attr])*
+            pub struct $Tone {
+                peripheral: $TIMER,
+                register_suffix: $n,
+                counter: u16,
+                // WGMn2 in TCCRnB
+                ctc: (0b0000_0000, 0b0000_1000),
+                prescalers: { $($div => $cs,)+ },
+                pin: $pinmod::$OC,
+                soft_pin: $Pin,
+            }
+        }
+    };
+    (
+        @impl
+        $(#[$tone_attr:meta])*
+        pub struct $Tone:ident {
+            peripheral: $TIMER:ty,
+            register_suffix: $n:expr,
+            counter: $Counter:ty,
+            ctc: ($tccra:expr, $tccrb:expr),
+            prescalers: { $($div:expr => $cs:expr,)+ },
+            pin: $pinmod:ident::$OC:ident,
+            soft_pin: $Pin:ty,
+        }
+    ) => {
+        $crate::paste::paste! {
+            $(#[$tone_attr])*
+            pub struct $Tone<CLOCK> {
+                timer: $TIMER,
+                pin: $pinmod::$OC<$crate::port::mode::Output>,
+                frequency: $crate::timer::Hertz,
+                _clock: core::marker::PhantomData<CLOCK>,
+            }
+
+            /// Tone output on any pin, toggled from the compare-match interrupt
+            pub struct [<Soft $Tone>]<CLOCK> {
+                timer: $TIMER,
+                frequency: $crate::timer::Hertz,
+                _clock: core::marker::PhantomData<CLOCK>,
+            }
+
+            impl<CLOCK: $crate::clock::Clock> $Tone<CLOCK> {
+                fn state() -> &'static $crate::avr_device::interrupt::Mutex<
+                    core::cell::RefCell<$crate::tone::ToneState<$Pin>>
+                > {
+                    static STATE: $crate::avr_device::interrupt::Mutex<
+                        core::cell::RefCell<$crate::tone::ToneState<$Pin>>
+                    > = $crate::avr_device::interrupt::Mutex::new(
+                        core::cell::RefCell::new($crate::tone::ToneState::new())
+                    );
+                    &STATE
+                }
+
+                /// Take ownership of the timer and its `OCnA` pin.  No tone is played until
+                /// [`play()`](#method.play) is called.
+                pub fn new(
+                    timer: $TIMER,
+                    pin: $pinmod::$OC<$crate::port::mode::Output>,
+                ) -> $Tone<CLOCK> {
+                    let mut t = $Tone {
+                        timer,
+                        pin,
+                        frequency: $crate::timer::Hertz(0),
+                        _clock: core::marker::PhantomData,
+                    };
+                    t.stop();
+                    t
+                }
+
+                /// Stop the tone and return the peripheral and the pin.
+                pub fn release(mut self) -> ($TIMER, $pinmod::$OC<$crate::port::mode::Output>) {
+                    self.stop();
+                    (self.timer, self.pin)
+                }
+
+                /// Play a tone with the given frequency until [`stop()`](#method.stop) is called.
+                pub fn play(&mut self, frequency: $crate::timer::Hertz) {
+                    self.start(frequency, None);
+                }
+
+                /// Play a tone with the given frequency for the given duration.
+                ///
+                /// This function does not block.  The `TIMERn_COMPA` interrupt must call
+                /// [`on_compare()`](#method.on_compare) to stop the tone after the duration has
+                /// passed.
+                pub fn play_for(
+                    &mut self,
+                    frequency: $crate::timer::Hertz,
+                    duration: $crate::timer::Milliseconds,
+                ) {
+                    self.start(frequency, Some(duration));
+                }
+
+                fn start(
+                    &mut self,
+                    frequency: $crate::timer::Hertz,
+                    duration: Option<$crate::timer::Milliseconds>,
+                ) {
+                    let ((_, cs_bits), top, actual) = $crate::tone::calculate_tone(
+                        CLOCK::FREQ,
+                        frequency,
+                        &[$(($div, $cs),)+],
+                        <$Counter>::MAX as u16,
+                    );
+                    self.frequency = actual;
+
+                    $crate::avr_device::interrupt::free(|cs| {
+                        Self::state().borrow(cs).borrow_mut().start(
+                            duration.map(|d| $crate::tone::toggles(actual, d)),
+                        );
+                    });
+
+                    // Toggle OCnA on compare match
+                    let tim = &self.timer;
+                    tim.[<tccr $n b>].reset();
+                    tim.[<tccr $n a>].write(|w| unsafe { w.bits($tccra | 0b0100_0000) });
+                    tim.[<tcnt $n>].reset();
+                    tim.[<ocr $n a>].write(|w| unsafe { w.bits(top as $Counter) });
+                    tim.[<tifr $n>].write(|w| w.[<ocf $n a>]().set_bit());
+                    tim.[<timsk $n>].modify(|_, w| w.[<ocie $n a>]().bit(duration.is_some()));
+                    tim.[<tccr $n b>].write(|w| unsafe { w.bits($tccrb | cs_bits) });
+                }
+
+                /// Stop the tone.  The pin is driven low.
+                pub fn stop(&mut self) {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        Self::stop_timer(&self.timer);
+                        Self::state().borrow(cs).borrow_mut().stop();
+                    });
+                    // Once the timer is disconnected, the pin outputs its PORT value again
+                    $crate::hal::digital::v2::OutputPin::set_low(&mut self.pin).ok();
+                }
+
+                fn stop_timer(tim: &$TIMER) {
+                    tim.[<tccr $n b>].reset();
+                    tim.[<tccr $n a>].reset();
+                    tim.[<timsk $n>].modify(|_, w| w.[<ocie $n a>]().clear_bit());
+                }
+
+                /// Whether a tone is currently playing.
+                pub fn is_playing(&self) -> bool {
+                    self.timer.[<tccr $n b>].read().bits() & 0b0000_0111 != 0
+                }
+
+                /// The actual frequency of the last tone.
+                ///
+                /// Due to the limited resolution of the timer, this can differ from the frequency
+                /// passed to [`play()`](#method.play).
+                pub fn frequency(&self) -> $crate::timer::Hertz {
+                    self.frequency
+                }
+
+                /// Count an edge of the output and stop the tone once its duration has passed.
+                ///
+                /// This function must be called from the `TIMERn_COMPA` interrupt.  The type
+                /// parameter does not matter here.
+                pub fn on_compare() {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        let mut state = Self::state().borrow(cs).borrow_mut();
+                        if !state.on_compare() {
+                            // SAFETY: Once the tone is playing, the timer is only stopped here.
+                            Self::stop_timer(unsafe { &*<$TIMER>::ptr() });
+                            state.stop();
+                        }
+                    })
+                }
+            }
+
+            impl<CLOCK: $crate::clock::Clock> [<Soft $Tone>]<CLOCK> {
+                /// Take ownership of the timer and the pin.  No tone is played until
+                /// [`play()`](#method.play) is called.
+                pub fn new(timer: $TIMER, pin: $Pin) -> [<Soft $Tone>]<CLOCK> {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        $Tone::<CLOCK>::state().borrow(cs).borrow_mut().set_pin(Some(pin));
+                    });
+                    let mut t = [<Soft $Tone>] {
+                        timer,
+                        frequency: $crate::timer::Hertz(0),
+                        _clock: core::marker::PhantomData,
+                    };
+                    t.stop();
+                    t
+                }
+
+                /// Stop the tone and return the peripheral and the pin.
+                pub fn release(mut self) -> ($TIMER, $Pin) {
+                    self.stop();
+                    let pin = $crate::avr_device::interrupt::free(|cs| {
+                        $Tone::<CLOCK>::state().borrow(cs).borrow_mut().set_pin(None)
+                    });
+                    (self.timer, pin.expect("tone pin missing"))
+                }
+
+                /// Play a tone with the given frequency until [`stop()`](#method.stop) is called.
+                ///
+                /// The `TIMERn_COMPA` interrupt must call [`on_compare()`](#method.on_compare).
+                pub fn play(&mut self, frequency: $crate::timer::Hertz) {
+                    self.start(frequency, None);
+                }
+
+                /// Play a tone with the given frequency for the given duration.
+                ///
+                /// This function does not block.  The `TIMERn_COMPA` interrupt must call
+                /// [`on_compare()`](#method.on_compare).
+                pub fn play_for(
+                    &mut self,
+                    frequency: $crate::timer::Hertz,
+                    duration: $crate::timer::Milliseconds,
+                ) {
+                    self.start(frequency, Some(duration));
+                }
+
+                fn start(
+                    &mut self,
+                    frequency: $crate::timer::Hertz,
+                    duration: Option<$crate::timer::Milliseconds>,
+                ) {
+                    let ((_, cs_bits), top, actual) = $crate::tone::calculate_tone(
+                        CLOCK::FREQ,
+                        frequency,
+                        &[$(($div, $cs),)+],
+                        <$Counter>::MAX as u16,
+                    );
+                    self.frequency = actual;
+
+                    $crate::avr_device::interrupt::free(|cs| {
+                        $Tone::<CLOCK>::state().borrow(cs).borrow_mut().start(
+                            duration.map(|d| $crate::tone::toggles(actual, d)),
+                        );
+                    });
+
+                    let tim = &self.timer;
+                    tim.[<tccr $n b>].reset();
+                    tim.[<tccr $n a>].write(|w| unsafe { w.bits($tccra) });
+                    tim.[<tcnt $n>].reset();
+                    tim.[<ocr $n a>].write(|w| unsafe { w.bits(top as $Counter) });
+                    tim.[<tifr $n>].write(|w| w.[<ocf $n a>]().set_bit());
+                    tim.[<timsk $n>].modify(|_, w| w.[<ocie $n a>]().set_bit());
+                    tim.[<tccr $n b>].write(|w| unsafe { w.bits($tccrb | cs_bits) });
+                }
+
+                /// Stop the tone.  The pin is driven low.
+                pub fn stop(&mut self) {
+                    $crate::avr_device::interrupt::free(|cs| {
+                        $Tone::<CLOCK>::stop_timer(&self.timer);
+                        $Tone::<CLOCK>::state().borrow(cs).borrow_mut().stop();
+                    });
+                }
+
+                /// Whether a tone is currently playing.
+                pub fn is_playing(&self) -> bool {
+                    self.timer.[<tccr $n b>].read().bits() & 0b0000_0111 != 0
+                }
+
+                /// The actual frequency of the last tone.
+                pub fn frequency(&self) -> $crate::timer::Hertz {
+                    self.frequency
+                }
+
+                /// Toggle the pin and stop the tone once its duration has passed.
+                ///
+                /// This function must be called from the `TIMERn_COMPA` interrupt.  The type
+                /// parameter does not matter here.
+                pub fn on_compare() {
+                    $Tone::<CLOCK>::on_compare()
+                }
+            }
+        }
+    };
+}
@@ -13,13 +13,18 @@ mod pins;
 pub use crate::pac::Peripherals;
 pub use crate::pins::*;
 pub use crate::hal::adc;
+pub use crate::hal::capture;
 pub mod prelude {
     pub use crate::hal::prelude::*;
     pub use crate::hal::usart::BaudrateArduinoExt as _;
 }
 pub use crate::hal::pwm;
+pub use crate::hal::servo;
 pub use crate::hal::spi;
+pub use crate::hal::timer;
+pub use crate::hal::tone;
 
 pub type Delay = crate::hal::delay::Delay<hal::clock::MHz16>;
+pub type SysTime = crate::hal::systime::SysTime<hal::clock::MHz16>;
 pub type Serial<IMODE> = crate::hal::usart::Usart0<hal::clock::MHz16, IMODE>;
 pub type I2cMaster<M> = crate::hal::i2c::I2cMaster<hal::clock::MHz16, M>;
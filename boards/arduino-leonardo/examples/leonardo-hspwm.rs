@@ -0,0 +1,52 @@
+//! Drive an H-bridge from two complementary PWM channels of `TC4`.
+//!
+//! Channel `B` drives one half-bridge on `D10`/`D9` and channel `D` the other one on `D6`/`D12`.
+//! The PWM frequency is about 31 kHz from the 64 MHz PLL clock, with 125 ns of dead time between
+//! the high- and low-side switches.  The motor is slowly ramped between full speed in both
+//! directions.
+#![no_std]
+#![no_main]
+
+use arduino_leonardo::hspwm;
+use arduino_leonardo::prelude::*;
+use panic_halt as _;
+
+#[arduino_leonardo::entry]
+fn main() -> ! {
+    let dp = arduino_leonardo::Peripherals::take().unwrap();
+
+    let mut pins = arduino_leonardo::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD, dp.PORTE, dp.PORTF);
+
+    let mut timer4 = hspwm::Timer4::new(
+        dp.TC4,
+        &dp.PLL,
+        hspwm::Config {
+            mode: hspwm::WaveformMode::PhaseFrequencyCorrect,
+            dead_time: hspwm::DeadTime::symmetric(8),
+            ..hspwm::Config::default()
+        },
+    );
+
+    let mut left = timer4.complementary_b(
+        pins.d10.into_output(&mut pins.ddr),
+        pins.d9.into_output(&mut pins.ddr),
+    );
+    let mut right = timer4.complementary_d(
+        pins.d6.into_output(&mut pins.ddr),
+        pins.d12.into_output(&mut pins.ddr),
+    );
+
+    let max = left.get_max_duty();
+    left.set_duty(max / 2);
+    right.set_duty(max / 2);
+    left.enable();
+    right.enable();
+
+    loop {
+        for duty in (0..=max).step_by(8).chain((0..=max).rev().step_by(8)) {
+            left.set_duty(duty);
+            right.set_duty(max - duty);
+            arduino_leonardo::delay_ms(10);
+        }
+    }
+}
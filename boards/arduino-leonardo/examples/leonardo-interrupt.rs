@@ -34,13 +34,12 @@ fn main() -> ! {
     // stop because they are never handled.
     dp.USB_DEVICE.usbcon.reset();
 
-    // Initialize INT6
-    // There is not yet a hal implementation, which is why we need to do this
-    // manually
-    let ei = dp.EXINT;
-    // TODO: Patch EXINT so we at least don't need manual values here
-    ei.eicrb.write(|w| w.isc6().bits(0x02));
-    ei.eimsk.write(|w| w.int().bits(0x40));
+    // Initialize INT6 (on pin D7) to trigger on a falling edge
+    let mut exint = dp.EXINT;
+    let mut int6 = pins
+        .d7
+        .into_ext_interrupt(&mut exint, arduino_leonardo::exint::Sense::Falling);
+    int6.enable();
 
     // Enable interrupts
     unsafe {
@@ -84,6 +84,29 @@ pub fn delay_us(us: u16) {
     Delay::new().delay_us(us)
 }
 
+/// System time service providing `millis()` and `micros()` using `TC0`
+///
+/// The `TIMER0_COMPA` interrupt must be forwarded to the time service.
+///
+/// # Example
+/// For a full example, see [`examples/uno-millis.rs`][ex-millis].  In short:
+/// ```no_run
+/// let dp = arduino_leonardo::Peripherals::take().unwrap();
+///
+/// let systime = arduino_leonardo::SysTime::new(dp.TC0);
+/// unsafe { avr_device::interrupt::enable() };
+///
+/// let now = systime.millis();
+///
+/// #[avr_device::interrupt(atmega32u4)]
+/// fn TIMER0_COMPA() {
+///     arduino_leonardo::SysTime::on_interrupt();
+/// }
+/// ```
+///
+/// [ex-millis]: https://github.com/Rahix/avr-hal/blob/master/boards/arduino-uno/examples/uno-millis.rs
+pub type SysTime = hal::systime::SysTime<hal::clock::MHz16>;
+
 /// Support for the Serial Peripheral Interface
 ///
 /// # Example
@@ -140,6 +163,88 @@ pub mod adc {
     pub use atmega32u4_hal::adc::*;
 }
 
+/// Input capture for measuring periods and pulse widths
+///
+/// The capture inputs are `D4` (`TC1`) and `D13` (`TC3`).
+///
+/// # Example
+/// ```
+/// let mut capture = arduino_leonardo::capture::InputCapture1::new(
+///     dp.TC1,
+///     pins.d4,
+///     arduino_leonardo::capture::CaptureSettings::default(),
+/// );
+/// unsafe { avr_device::interrupt::enable() };
+///
+/// let frequency = nb::block!(capture.frequency()).void_unwrap();
+///
+/// #[avr_device::interrupt(atmega32u4)]
+/// fn TIMER1_CAPT() {
+///     arduino_leonardo::capture::InputCapture1::<Floating>::on_capture();
+/// }
+///
+/// #[avr_device::interrupt(atmega32u4)]
+/// fn TIMER1_OVF() {
+///     arduino_leonardo::capture::InputCapture1::<Floating>::on_overflow();
+/// }
+/// ```
+pub mod capture {
+    pub use atmega32u4_hal::capture::*;
+
+    /// Input capture on `TC1`
+    pub type InputCapture1<IMODE> =
+        atmega32u4_hal::capture::InputCapture1<atmega32u4_hal::clock::MHz16, IMODE>;
+
+    /// Input capture on `TC3`
+    pub type InputCapture3<IMODE> =
+        atmega32u4_hal::capture::InputCapture3<atmega32u4_hal::clock::MHz16, IMODE>;
+}
+
+/// Analog comparator
+///
+/// The positive comparator input `AIN0` is on pin `D7`.  The negative input is one of the analog
+/// pins, selected by handing the ADC multiplexer to the comparator.
+///
+/// # Example
+/// ```
+/// let mut ac = dp.AC;
+/// let ain0 = pins.d7.into_comparator_input(&mut ac);
+///
+/// let mut adc = arduino_leonardo::adc::Adc::new(dp.ADC, Default::default());
+/// let mut a0 = pins.a0.into_analog_input(&mut adc);
+/// let negative = adc.into_comparator_input(&mut a0);
+///
+/// let comparator = arduino_leonardo::comparator::AnalogComparator::new(ac, ain0, negative);
+/// ```
+pub mod comparator {
+    pub use atmega32u4_hal::comparator::*;
+}
+
+/// External and pin-change interrupts
+///
+/// | Interrupt | Pin |
+/// | --- | --- |
+/// | `INT0` | `D3` |
+/// | `INT1` | `D2` |
+/// | `INT2` | `D0` |
+/// | `INT3` | `D1` |
+/// | `INT6` | `D7` |
+///
+/// The only pin-change bank, `PinChange0`, contains `D8` - `D11`, the SPI pins and `led_rx`.
+///
+/// # Example
+/// For a full example, see [`examples/leonardo-interrupt.rs`][ex-interrupt].  In short:
+/// ```
+/// let mut exint = dp.EXINT;
+/// let mut int6 = pins.d7.into_ext_interrupt(&mut exint, arduino_leonardo::exint::Sense::Falling);
+/// int6.enable();
+/// ```
+///
+/// [ex-interrupt]: https://github.com/Rahix/avr-hal/blob/master/boards/arduino-leonardo/examples/leonardo-interrupt.rs
+pub mod exint {
+    pub use atmega32u4_hal::exint::*;
+}
+
 /// Support for PWM pins
 ///
 /// The 4 timers of ATmega32U4 can be used for PWM on certain pins.
@@ -177,6 +282,157 @@ pub mod pwm {
     pub use atmega32u4_hal::pwm::*;
 }
 
+/// High-speed PWM with `TC4`
+///
+/// `TC4` can run from the PLL at up to 64 MHz with 10-bit resolution and drive pairs of
+/// complementary outputs with dead time, e.g. for the half-bridges of a motor driver.
+///
+/// # Example
+/// For a full example, see [`examples/leonardo-hspwm.rs`][ex-hspwm].  In short:
+/// ```
+/// let mut timer4 = arduino_leonardo::hspwm::Timer4::new(
+///     dp.TC4,
+///     &dp.PLL,
+///     arduino_leonardo::hspwm::Config::default(),
+/// );
+///
+/// let mut half_bridge = timer4.complementary_b(
+///     pins.d10.into_output(&mut pins.ddr),
+///     pins.d9.into_output(&mut pins.ddr),
+/// );
+///
+/// half_bridge.set_duty(512);
+/// half_bridge.enable();
+/// ```
+///
+/// [ex-hspwm]: https://github.com/Rahix/avr-hal/blob/master/boards/arduino-leonardo/examples/leonardo-hspwm.rs
+///
+/// | Channel | `OC4x` | `!OC4x` |
+/// | --- | --- | --- |
+/// | `A` | `pins.d13` | `pins.d5` |
+/// | `B` | `pins.d10` | `pins.d9` |
+/// | `D` | `pins.d6` | `pins.d12` |
+pub mod hspwm {
+    pub use atmega32u4_hal::hspwm::*;
+
+    /// `TC4` in high-speed PWM mode
+    pub type Timer4 = atmega32u4_hal::hspwm::Timer4<atmega32u4_hal::clock::MHz16>;
+}
+
+/// Servo control
+///
+/// `ServoN` drives servos in hardware from the compare outputs of a 16-bit timer.  These are `D9`,
+/// `D10` and `D11` on `TC1`, `D5` on `TC3`.  `MultiServo1` drives up to 12 servos on any output
+/// pins from the `TIMER1_COMPA` interrupt.
+///
+/// # Example
+/// ```
+/// let mut servo1 = arduino_leonardo::servo::Servo1::new(dp.TC1);
+/// let mut servo = pins
+///     .d9
+///     .into_output(&mut pins.ddr)
+///     .into_servo(&mut servo1, arduino_leonardo::servo::ServoConfig::default());
+///
+/// servo.set_degrees(90);
+/// ```
+///
+/// With the software-multiplexed driver:
+/// ```
+/// let servos = arduino_leonardo::servo::MultiServo1::new(dp.TC1);
+/// let mut servo = servos
+///     .attach(
+///         pins.d2.into_output(&mut pins.ddr).downgrade(),
+///         arduino_leonardo::servo::ServoConfig::default(),
+///     )
+///     .unwrap();
+/// unsafe { avr_device::interrupt::enable() };
+///
+/// servo.set_pulse(1500.us());
+///
+/// #[avr_device::interrupt(atmega32u4)]
+/// fn TIMER1_COMPA() {
+///     arduino_leonardo::servo::MultiServo1::on_compare();
+/// }
+/// ```
+pub mod servo {
+    pub use atmega32u4_hal::servo::*;
+
+    /// Hardware servo driver on `TC1` (pins `D9`, `D10`, `D11`)
+    pub type Servo1 = atmega32u4_hal::servo::Servo1<atmega32u4_hal::clock::MHz16>;
+
+    /// Hardware servo driver on `TC3` (pin `D5`)
+    pub type Servo3 = atmega32u4_hal::servo::Servo3<atmega32u4_hal::clock::MHz16>;
+
+    /// Software-multiplexed servo driver on `TC1`
+    pub type MultiServo1 = atmega32u4_hal::servo::MultiServo1<atmega32u4_hal::clock::MHz16>;
+
+    /// Software-multiplexed servo driver on `TC3`
+    pub type MultiServo3 = atmega32u4_hal::servo::MultiServo3<atmega32u4_hal::clock::MHz16>;
+}
+
+/// Tone and square-wave generation
+///
+/// `ToneN` toggles the `OCnA` pin of a timer in hardware.  `SoftToneN` toggles any output pin from
+/// the `TIMERn_COMPA` interrupt.
+///
+/// # Example
+/// ```
+/// let mut tone3 = arduino_leonardo::tone::Tone3::new(dp.TC3, pins.d5.into_output(&mut pins.ddr));
+///
+/// tone3.play(440.hz());
+/// ```
+pub mod tone {
+    pub use atmega32u4_hal::tone::*;
+
+    /// Tone output on `TC0` (pin `D11`)
+    pub type Tone0 = atmega32u4_hal::tone::Tone0<atmega32u4_hal::clock::MHz16>;
+
+    /// Software tone output on `TC0`
+    pub type SoftTone0 = atmega32u4_hal::tone::SoftTone0<atmega32u4_hal::clock::MHz16>;
+
+    /// Tone output on `TC1` (pin `D9`)
+    pub type Tone1 = atmega32u4_hal::tone::Tone1<atmega32u4_hal::clock::MHz16>;
+
+    /// Software tone output on `TC1`
+    pub type SoftTone1 = atmega32u4_hal::tone::SoftTone1<atmega32u4_hal::clock::MHz16>;
+
+    /// Tone output on `TC3` (pin `D5`)
+    pub type Tone3 = atmega32u4_hal::tone::Tone3<atmega32u4_hal::clock::MHz16>;
+
+    /// Software tone output on `TC3`
+    pub type SoftTone3 = atmega32u4_hal::tone::SoftTone3<atmega32u4_hal::clock::MHz16>;
+}
+
+/// Count-down timers
+///
+/// The timers implement `embedded_hal::timer::CountDown` and `embedded_hal::timer::Periodic`.
+/// Prescaler and TOP value are calculated automatically from the requested duration.
+///
+/// # Example
+/// ```
+/// let mut timer1 = arduino_leonardo::timer::Timer1::new(dp.TC1);
+///
+/// timer1.start(500.ms());
+/// loop {
+///     nb::block!(timer1.wait()).void_unwrap();
+///     led.toggle().void_unwrap();
+/// }
+/// ```
+///
+/// A timer which is also used for PWM cannot be used as a count-down timer at the same time.
+pub mod timer {
+    pub use atmega32u4_hal::timer::*;
+
+    /// `TC0` as a count-down timer
+    pub type Timer0 = atmega32u4_hal::timer::Timer0<atmega32u4_hal::clock::MHz16>;
+
+    /// `TC1` as a count-down timer
+    pub type Timer1 = atmega32u4_hal::timer::Timer1<atmega32u4_hal::clock::MHz16>;
+
+    /// `TC3` as a count-down timer
+    pub type Timer3 = atmega32u4_hal::timer::Timer3<atmega32u4_hal::clock::MHz16>;
+}
+
 /// Serial (UART) interface on pins `D0` (RX) and `D1` (TX)
 ///
 /// # Example
@@ -19,114 +19,114 @@ avr_hal_generic::impl_board_pins! {
         ///
         /// * `RX` (UART)
         /// * `INT2`: External Interrupt
-        pub d0: portd::pd2::PD2,
+        pub d0: portd::pd2::PD2 = 0,
         /// `D1` / `TX`
         ///
         /// * `TX` (UART)
         /// * `INT3`: External Interrupt
-        pub d1: portd::pd3::PD3,
+        pub d1: portd::pd3::PD3 = 1,
         /// `D2` / `SDA`
         ///
         /// * `SDA`: i2c/twi data
         /// * `INT1`: External Interrupt
-        pub d2: portd::pd1::PD1,
+        pub d2: portd::pd1::PD1 = 2,
         /// `D3` / `SCL`
         ///
         /// * **PWM**: [atmega32u4_hal::timer::Timer0Pwm]
         /// * `SCL`: i2c/twi clock
         /// * `INT0`: External Interrupt
         /// * `OC0B`: Output Compare Channel `B` for Timer/Counter0
-        pub d3: portd::pd0::PD0,
+        pub d3: portd::pd0::PD0 = 3,
         /// `D4`
-        pub d4: portd::pd4::PD4,
+        pub d4: portd::pd4::PD4 = 4,
         /// `D5`
         ///
         /// * **PWM**: [atmega32u4_hal::timer::Timer3Pwm]
         /// * `OC3A`: Output Compare Channel `A` for Timer/Counter3
         /// * `#OC4A`: Inverted Output Compare Channel `A` for Timer/Counter4 (Not implemented)
-        pub d5: portc::pc6::PC6,
+        pub d5: portc::pc6::PC6 = 5,
         /// `D6`
         ///
         /// * **PWM**: [atmega32u4_hal::timer::Timer4Pwm]
         /// * `OC4D`: Output Compare Channel `D` for Timer/Counter4
-        pub d6: portd::pd7::PD7,
+        pub d6: portd::pd7::PD7 = 6,
         /// `D7`
         ///
         /// * `INT6`: External Interrupt
-        pub d7: porte::pe6::PE6,
+        pub d7: porte::pe6::PE6 = 7,
         /// `D8`
-        pub d8: portb::pb4::PB4,
+        pub d8: portb::pb4::PB4 = 8,
         /// `D9`
         ///
         /// * **PWM**: [atmega32u4_hal::timer::Timer1Pwm]
         /// * `OC1A`: Output Compare Channel `A` for Timer/Counter1
         /// * `#OC4B`: Inverted Output Compare Channel `B` for Timer/Counter4 (Not implemented)
-        pub d9: portb::pb5::PB5,
+        pub d9: portb::pb5::PB5 = 9,
         /// `D10`
         ///
         /// * **PWM**: [atmega32u4_hal::timer::Timer1Pwm]
         /// * `OC1B`: Output Compare Channel `B` for Timer/Counter1
         /// * `OC4B`: Output Compare Channel `B` for Timer/Counter4 (Not implemented)
-        pub d10: portb::pb6::PB6,
+        pub d10: portb::pb6::PB6 = 10,
         /// `D11`
         ///
         /// * **PWM**: [atmega32u4_hal::timer::Timer0Pwm]
         /// * `OC0A`: Output Compare Channel `B` for Timer/Counter0
         /// * `OC1C`: Output Compare Channel `C` for Timer/Counter1
-        pub d11: portb::pb7::PB7,
+        pub d11: portb::pb7::PB7 = 11,
         /// `D12`
         ///
         /// * `#OC4D`: Inverted Output Compare Channel `D` for Timer/Counter4 (Not implemented)
-        pub d12: portd::pd6::PD6,
+        pub d12: portd::pd6::PD6 = 12,
         /// `D13` / `LED_BUILTIN`
         ///
         /// * Onboard LED
         /// * **PWM**: [atmega32u4_hal::timer::Timer4Pwm]
         /// * `OC4A`: Output Compare Channel `A` for Timer/Counter4
-        pub d13: portc::pc7::PC7,
+        pub d13: portc::pc7::PC7 = 13,
         /// `RX`
         ///
         /// Led for indicating inbound data.  Also the CS pin.
-        pub led_rx: portb::pb0::PB0,
+        pub led_rx: portb::pb0::PB0 = 17,
         /// `TX`
         ///
         /// Led for indicating outbound data
-        pub led_tx: portd::pd5::PD5,
+        pub led_tx: portd::pd5::PD5 = 30,
         /// `SCLK`
         ///
         /// ICSP SCLK pin
-        pub sck: portb::pb1::PB1,
+        pub sck: portb::pb1::PB1 = 15,
         /// `MOSI`
         ///
         /// ICSP MOSI pin
-        pub mosi: portb::pb2::PB2,
+        pub mosi: portb::pb2::PB2 = 16,
         /// `MISO`
         ///
         /// ICSP MISO pin
-        pub miso: portb::pb3::PB3,
+        pub miso: portb::pb3::PB3 = 14,
         /// `A0`
         ///
         /// * `ADC7` channel
-        pub a0: portf::pf7::PF7,
+        pub a0: portf::pf7::PF7 = 18,
         /// `A1`
         ///
         /// * `ADC6` channel
-        pub a1: portf::pf6::PF6,
+        pub a1: portf::pf6::PF6 = 19,
         /// `A2`
         ///
         /// * `ADC5` channel
-        pub a2: portf::pf5::PF5,
+        pub a2: portf::pf5::PF5 = 20,
         /// `A3`
         ///
         /// * `ADC4` channel
-        pub a3: portf::pf4::PF4,
+        pub a3: portf::pf4::PF4 = 21,
         /// `A4`
         ///
         /// * `ADC1` channel
-        pub a4: portf::pf1::PF1,
+        pub a4: portf::pf1::PF1 = 22,
         /// `A5`
         ///
         /// * `ADC0` channel
-        pub a5: portf::pf0::PF0,
+        pub a5: portf::pf0::PF0 = 23,
     }
 }
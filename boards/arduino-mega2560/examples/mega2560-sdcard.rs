@@ -0,0 +1,83 @@
+//! This example initializes an SD card connected to the SPI pins and dumps
+//! some information about it.  The card's chip-select must be connected to
+//! pin `D53`.
+//!
+//! Once this program is written to the board, the serial output can be
+//! accessed with
+//!
+//! ```
+//! sudo screen /dev/ttyACM0 57600
+//! ```
+
+#![no_std]
+#![no_main]
+
+extern crate panic_halt;
+use arduino_mega2560::prelude::*;
+use arduino_mega2560::sdcard;
+use arduino_mega2560::spi::{Settings, Spi};
+
+#[arduino_mega2560::entry]
+fn main() -> ! {
+    let dp = arduino_mega2560::Peripherals::take().unwrap();
+    let mut pins = arduino_mega2560::Pins::new(
+        dp.PORTA,
+        dp.PORTB,
+        dp.PORTC,
+        dp.PORTD,
+        dp.PORTE,
+        dp.PORTF,
+        dp.PORTG,
+        dp.PORTH,
+        dp.PORTJ,
+        dp.PORTK,
+        dp.PORTL,
+    );
+    // set up serial interface for text output
+    let mut serial = arduino_mega2560::Serial::new(
+        dp.USART0,
+        pins.d0,
+        pins.d1.into_output(&mut pins.ddr),
+        57600.into_baudrate(),
+    );
+
+    // Create SPI interface.  The card driver reconfigures it as needed.
+    let (spi, cs) = Spi::new(
+        dp.SPI,
+        pins.d52.into_output(&mut pins.ddr),
+        pins.d51.into_output(&mut pins.ddr),
+        pins.d50.into_pull_up_input(&mut pins.ddr),
+        pins.d53.into_output(&mut pins.ddr),
+        Settings::default(),
+    );
+
+    let mut card = arduino_mega2560::SdCard::new(spi, cs, sdcard::Settings::default());
+
+    match card.init() {
+        Ok(card_type) => ufmt::uwriteln!(&mut serial, "Card type: {:?}\r", card_type).void_unwrap(),
+        Err(e) => {
+            ufmt::uwriteln!(&mut serial, "Initialization failed: {:?}\r", e).void_unwrap();
+            loop {}
+        }
+    }
+
+    match card.num_blocks() {
+        Ok(blocks) => ufmt::uwriteln!(&mut serial, "Size: {} MiB\r", blocks / 2048).void_unwrap(),
+        Err(e) => ufmt::uwriteln!(&mut serial, "Reading CSD failed: {:?}\r", e).void_unwrap(),
+    }
+
+    // Block 0 usually contains the MBR which ends in a 0x55 0xAA signature
+    let mut block = [0u8; sdcard::BLOCK_SIZE];
+    match card.read_block(0, &mut block) {
+        Ok(()) => ufmt::uwriteln!(
+            &mut serial,
+            "Boot signature: {} {}\r",
+            block[510],
+            block[511]
+        )
+        .void_unwrap(),
+        Err(e) => ufmt::uwriteln!(&mut serial, "Reading block 0 failed: {:?}\r", e).void_unwrap(),
+    }
+
+    loop {}
+}
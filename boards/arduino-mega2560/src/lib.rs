@@ -20,8 +20,12 @@ pub mod prelude {
 
 pub use crate::hal::spi;
 pub use crate::hal::adc;
+pub use crate::hal::comparator;
+pub use crate::hal::exint;
+pub use crate::hal::rtc;
 
 pub type Delay = crate::hal::delay::Delay<hal::clock::MHz16>;
+pub type SysTime = crate::hal::systime::SysTime<hal::clock::MHz16>;
 pub type Serial<IMODE> = crate::usart::Usart0<IMODE>;
 
 pub mod usart {
@@ -33,6 +37,59 @@ pub mod usart {
     pub type Usart3<IMODE> = crate::hal::usart::Usart3<crate::hal::clock::MHz16, IMODE>;
 }
 
+pub mod capture {
+    pub use crate::hal::capture::*;
+
+    pub type InputCapture1<IMODE> =
+        crate::hal::capture::InputCapture1<crate::hal::clock::MHz16, IMODE>;
+    pub type InputCapture3<IMODE> =
+        crate::hal::capture::InputCapture3<crate::hal::clock::MHz16, IMODE>;
+    pub type InputCapture4<IMODE> =
+        crate::hal::capture::InputCapture4<crate::hal::clock::MHz16, IMODE>;
+    pub type InputCapture5<IMODE> =
+        crate::hal::capture::InputCapture5<crate::hal::clock::MHz16, IMODE>;
+}
+
+pub mod timer {
+    pub use crate::hal::timer::*;
+
+    pub type Timer0 = crate::hal::timer::Timer0<crate::hal::clock::MHz16>;
+    pub type Timer1 = crate::hal::timer::Timer1<crate::hal::clock::MHz16>;
+    pub type Timer2 = crate::hal::timer::Timer2<crate::hal::clock::MHz16>;
+    pub type Timer3 = crate::hal::timer::Timer3<crate::hal::clock::MHz16>;
+    pub type Timer4 = crate::hal::timer::Timer4<crate::hal::clock::MHz16>;
+    pub type Timer5 = crate::hal::timer::Timer5<crate::hal::clock::MHz16>;
+}
+
+/// SD/MMC card on the SPI bus (pins `D50`-`D53`)
+///
+/// # Example
+/// For a full example, see [`examples/mega2560-sdcard.rs`][ex-sdcard].  In short:
+/// ```no_run
+/// let (spi, cs) = arduino_mega2560::spi::Spi::new(
+///     dp.SPI,
+///     pins.d52.into_output(&mut pins.ddr),
+///     pins.d51.into_output(&mut pins.ddr),
+///     pins.d50.into_pull_up_input(&mut pins.ddr),
+///     pins.d53.into_output(&mut pins.ddr),
+///     arduino_mega2560::spi::Settings::default(),
+/// );
+///
+/// let mut card = arduino_mega2560::SdCard::new(spi, cs, arduino_mega2560::sdcard::Settings::default());
+/// card.init().unwrap();
+/// ```
+///
+/// [ex-sdcard]: https://github.com/Rahix/avr-hal/blob/master/boards/arduino-mega2560/examples/mega2560-sdcard.rs
+pub type SdCard = avr_hal_generic::sdcard::SdCard<
+    spi::Spi<crate::hal::port::mode::PullUp>,
+    spi::ChipSelectPin,
+    hal::clock::MHz16,
+>;
+
+pub mod sdcard {
+    pub use avr_hal_generic::sdcard::*;
+}
+
 pub type I2cMaster<M> = crate::hal::i2c::I2cMaster<hal::clock::MHz16, M>;
 #[doc(hidden)]
 #[deprecated = "Please use `I2cMaster` instead of `I2c`"]
@@ -84,3 +141,33 @@ pub type I2c<M> = I2cMaster<M>;
 pub mod pwm {
     pub use crate::hal::pwm::*;
 }
+
+pub mod servo {
+    pub use crate::hal::servo::*;
+
+    pub type Servo1 = crate::hal::servo::Servo1<crate::hal::clock::MHz16>;
+    pub type Servo3 = crate::hal::servo::Servo3<crate::hal::clock::MHz16>;
+    pub type Servo4 = crate::hal::servo::Servo4<crate::hal::clock::MHz16>;
+    pub type Servo5 = crate::hal::servo::Servo5<crate::hal::clock::MHz16>;
+    pub type MultiServo1 = crate::hal::servo::MultiServo1<crate::hal::clock::MHz16>;
+    pub type MultiServo3 = crate::hal::servo::MultiServo3<crate::hal::clock::MHz16>;
+    pub type MultiServo4 = crate::hal::servo::MultiServo4<crate::hal::clock::MHz16>;
+    pub type MultiServo5 = crate::hal::servo::MultiServo5<crate::hal::clock::MHz16>;
+}
+
+pub mod tone {
+    pub use crate::hal::tone::*;
+
+    pub type Tone0 = crate::hal::tone::Tone0<crate::hal::clock::MHz16>;
+    pub type Tone1 = crate::hal::tone::Tone1<crate::hal::clock::MHz16>;
+    pub type Tone2 = crate::hal::tone::Tone2<crate::hal::clock::MHz16>;
+    pub type Tone3 = crate::hal::tone::Tone3<crate::hal::clock::MHz16>;
+    pub type Tone4 = crate::hal::tone::Tone4<crate::hal::clock::MHz16>;
+    pub type Tone5 = crate::hal::tone::Tone5<crate::hal::clock::MHz16>;
+    pub type SoftTone0 = crate::hal::tone::SoftTone0<crate::hal::clock::MHz16>;
+    pub type SoftTone1 = crate::hal::tone::SoftTone1<crate::hal::clock::MHz16>;
+    pub type SoftTone2 = crate::hal::tone::SoftTone2<crate::hal::clock::MHz16>;
+    pub type SoftTone3 = crate::hal::tone::SoftTone3<crate::hal::clock::MHz16>;
+    pub type SoftTone4 = crate::hal::tone::SoftTone4<crate::hal::clock::MHz16>;
+    pub type SoftTone5 = crate::hal::tone::SoftTone5<crate::hal::clock::MHz16>;
+}
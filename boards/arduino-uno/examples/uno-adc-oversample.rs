@@ -0,0 +1,57 @@
+//! Read `A0` with increased resolution and filtering.
+//!
+//! Prints the plain 10-bit reading, a 13-bit reading obtained by oversampling and decimation,
+//! and a median of 9 conversions taken in ADC noise reduction sleep mode.
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::adc;
+use arduino_uno::prelude::*;
+use panic_halt as _;
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    let mut pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    let mut serial = arduino_uno::Serial::new(
+        dp.USART0,
+        pins.d0,
+        pins.d1.into_output(&mut pins.ddr),
+        57600.into_baudrate(),
+    );
+
+    let mut adc = adc::Adc::new(dp.ADC, Default::default());
+    let mut a0 = pins.a0.into_analog_input(&mut adc);
+
+    let mut decimate = adc::Oversample::new(adc::Filter::Decimate { bits: 13 }).unwrap();
+    let mut median =
+        adc::Oversample::with_buffer(adc::Filter::Median { samples: 9 }, [0; 9]).unwrap();
+
+    // The ADC interrupt wakes the CPU from noise reduction sleep
+    unsafe { avr_device::interrupt::enable() };
+
+    loop {
+        let raw: u16 = nb::block!(adc.read(&mut a0)).void_unwrap();
+        let decimated = nb::block!(decimate.read(&mut adc, &mut a0)).void_unwrap();
+        let filtered = median.read_noise_reduced(&mut adc, &mut a0);
+
+        ufmt::uwriteln!(
+            &mut serial,
+            "10 bit: {}, 13 bit: {}, median: {}\r",
+            raw,
+            decimated,
+            filtered
+        )
+        .void_unwrap();
+
+        arduino_uno::delay_ms(500);
+    }
+}
+
+#[avr_device::interrupt(atmega328p)]
+fn ADC() {
+    // Nothing to do here, the conversion result is read after waking up
+}
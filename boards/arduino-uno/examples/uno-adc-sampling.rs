@@ -0,0 +1,105 @@
+//! Sample `A0` at a fixed rate of 8 kHz, defined by timer 0.
+//!
+//! The ADC is auto-triggered by the timer 0 compare match A event and the results are collected
+//! into a ring buffer from the `ADC` interrupt.  The main loop prints the minimum and maximum
+//! value of every 8000 samples (i.e. once per second).
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::adc;
+use arduino_uno::prelude::*;
+use core::cell;
+use panic_halt as _;
+
+static mut SAMPLES: [u16; 64] = [0; 64];
+
+static BUFFER: avr_device::interrupt::Mutex<cell::RefCell<Option<adc::SampleBuffer<'static>>>> =
+    avr_device::interrupt::Mutex::new(cell::RefCell::new(None));
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    let mut pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    let mut serial = arduino_uno::Serial::new(
+        dp.USART0,
+        pins.d0,
+        pins.d1.into_output(&mut pins.ddr),
+        57600.into_baudrate(),
+    );
+
+    avr_device::interrupt::free(|cs| {
+        BUFFER
+            .borrow(cs)
+            .replace(Some(adc::SampleBuffer::new(unsafe { &mut SAMPLES })));
+    });
+
+    // Timer 0 in CTC mode: 16 MHz / 8 / 250 = 8 kHz
+    let tc0 = dp.TC0;
+    tc0.tccr0a.write(|w| w.wgm0().ctc());
+    tc0.ocr0a.write(|w| unsafe { w.bits(249) });
+    tc0.tccr0b.write(|w| w.cs0().prescale_8());
+
+    // A conversion takes 13 ADC clock cycles; with a 250 kHz ADC clock this allows up to
+    // ~19 kHz sample rate.
+    let mut adc = adc::Adc::new(
+        dp.ADC,
+        adc::AdcSettings {
+            clock_divider: adc::ClockRateDivision::Factor64,
+            trigger_source: adc::TriggerSource::Timer0CompareA,
+            ..Default::default()
+        },
+    );
+    let mut a0 = pins.a0.into_analog_input(&mut adc);
+
+    adc.listen();
+    adc.start_continuous(&mut a0);
+
+    // Enable interrupts globally
+    unsafe { avr_device::interrupt::enable() };
+
+    let (mut count, mut min, mut max) = (0u16, u16::MAX, 0u16);
+    loop {
+        let (sample, overrun) = avr_device::interrupt::free(|cs| {
+            let mut buffer = BUFFER.borrow(cs).borrow_mut();
+            let buffer = buffer.as_mut().unwrap();
+            (buffer.pop(), buffer.take_overrun())
+        });
+
+        if overrun {
+            ufmt::uwriteln!(&mut serial, "Samples were dropped!\r").void_unwrap();
+        }
+
+        if let Some(sample) = sample {
+            min = min.min(sample);
+            max = max.max(sample);
+            count += 1;
+
+            if count == 8000 {
+                ufmt::uwriteln!(&mut serial, "min: {}, max: {}\r", min, max).void_unwrap();
+                count = 0;
+                min = u16::MAX;
+                max = 0;
+            }
+        }
+    }
+}
+
+#[avr_device::interrupt(atmega328p)]
+fn ADC() {
+    // The compare match flag is not cleared by hardware as the timer interrupt is not used.  It
+    // must be cleared manually so the next compare match triggers a new conversion.
+    unsafe {
+        (*arduino_uno::pac::TC0::ptr())
+            .tifr0
+            .write(|w| w.ocf0a().set_bit());
+    }
+
+    avr_device::interrupt::free(|cs| {
+        if let Some(buffer) = BUFFER.borrow(cs).borrow_mut().as_mut() {
+            buffer.push(unsafe { adc::Adc::interrupt_sample() });
+        }
+    })
+}
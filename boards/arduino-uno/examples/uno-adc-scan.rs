@@ -0,0 +1,50 @@
+#![no_std]
+#![no_main]
+
+use arduino_uno::adc;
+use arduino_uno::prelude::*;
+use panic_halt as _;
+
+// This example converts a list of channels in one go using a scan.  On most POSIX operating
+// systems (like GNU/Linux or OSX), you can interface with the program by running (assuming the
+// device appears as ttyACM0)
+//
+// $ sudo screen /dev/ttyACM0 9600
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    let mut pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    let mut serial =
+        arduino_uno::Serial::new(dp.USART0, pins.d0, pins.d1.into_output(&mut pins.ddr), 9600.into_baudrate());
+
+    let mut adc = adc::Adc::new(dp.ADC, Default::default());
+
+    let a0 = pins.a0.into_analog_input(&mut adc);
+    let a1 = pins.a1.into_analog_input(&mut adc);
+    let a2 = pins.a2.into_analog_input(&mut adc);
+
+    // The bandgap channel needs time to settle after being selected, so the scan automatically
+    // discards its first conversion.
+    let channels = [
+        adc::Adc::channel_id(&a0),
+        adc::Adc::channel_id(&a1),
+        adc::Adc::channel_id(&a2),
+        adc::Adc::channel_id(&adc::channel::Vbg),
+    ];
+
+    loop {
+        let mut results = [0u16; 4];
+        {
+            let mut scan = adc::Scan::new(&channels, &mut results);
+            nb::block!(adc.scan(&mut scan)).void_unwrap();
+        }
+
+        let [v0, v1, v2, vbg] = results;
+        ufmt::uwriteln!(&mut serial, "A0: {} A1: {} A2: {} Vbg: {}\r", v0, v1, v2, vbg).void_unwrap();
+
+        arduino_uno::delay_ms(1000);
+    }
+}
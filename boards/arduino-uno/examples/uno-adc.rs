@@ -30,6 +30,13 @@ fn main() -> ! {
     ufmt::uwriteln!(&mut serial, "Vbandgap: {}\r", vbg).void_unwrap();
     ufmt::uwriteln!(&mut serial, "GND: {}\r", gnd).void_unwrap();
 
+    let mut delay = arduino_uno::Delay::new();
+    let vcc = adc.read_vcc_millivolts(&mut delay, adc::BANDGAP_MILLIVOLTS);
+    let temperature = adc.read_temperature(&mut delay, &adc::TEMPERATURE_TYPICAL);
+
+    ufmt::uwriteln!(&mut serial, "Vcc: {} mV\r", vcc).void_unwrap();
+    ufmt::uwriteln!(&mut serial, "Temperature: {} C\r", temperature).void_unwrap();
+
     let mut a0 = pins.a0.into_analog_input(&mut adc);
     let mut a1 = pins.a1.into_analog_input(&mut adc);
     let mut a2 = pins.a2.into_analog_input(&mut adc);
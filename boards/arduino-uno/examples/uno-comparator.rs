@@ -0,0 +1,58 @@
+//! Compare the voltage on `D7` against the internal bandgap reference and `D6` against `A0`.
+//!
+//! For the first five seconds, the on-board LED shows whether `D7` is above the bandgap voltage
+//! (~1.1V).  Afterwards, a message is printed whenever `D6` rises above `A0`.
+#![no_std]
+#![no_main]
+
+use arduino_uno::adc;
+use arduino_uno::comparator;
+use arduino_uno::prelude::*;
+use panic_halt as _;
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    let mut pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    let mut serial = arduino_uno::Serial::new(
+        dp.USART0,
+        pins.d0,
+        pins.d1.into_output(&mut pins.ddr),
+        57600.into_baudrate(),
+    );
+
+    let mut led = pins.d13.into_output(&mut pins.ddr);
+
+    let mut ac = dp.AC;
+    let ain0 = pins.d6.into_comparator_input(&mut ac);
+    let ain1 = pins.d7.into_comparator_input(&mut ac);
+
+    // First, compare D7 against the bandgap reference for a few seconds
+    let comparator = comparator::AnalogComparator::new(ac, comparator::Bandgap, ain1);
+    for _ in 0..50 {
+        // The bandgap is the positive input here, so the output is low when D7 is above it
+        if comparator.output() {
+            led.set_low().void_unwrap();
+        } else {
+            led.set_high().void_unwrap();
+        }
+        arduino_uno::delay_ms(100);
+    }
+    let (ac, _, _ain1) = comparator.release();
+
+    // Now compare against A0 using the ADC multiplexer
+    let mut adc = adc::Adc::new(dp.ADC, Default::default());
+    let mut a0 = pins.a0.into_analog_input(&mut adc);
+    let negative = adc.into_comparator_input(&mut a0);
+
+    let mut comparator = comparator::AnalogComparator::new(ac, ain0, negative);
+    comparator.set_edge(comparator::Edge::Rising);
+
+    loop {
+        if comparator.check_edge() {
+            ufmt::uwriteln!(&mut serial, "D6 rose above A0\r").void_unwrap();
+        }
+    }
+}
@@ -1,9 +1,27 @@
+//! Measure distances with an HC-SR04 ultrasonic sensor using input capture.
+//!
+//! Connect the sensor's `TRIG` to `D2` and its `ECHO` to `D8`, the input capture pin of timer 1.
+//! The timer records the time of both edges of the echo pulse, so its width is measured exactly
+//! even while the CPU is busy with something else.
 #![no_std]
 #![no_main]
+#![feature(abi_avr_interrupt)]
 
+use arduino_uno::capture::{CaptureSettings, Edge, InputCapture1, Prescaler};
+use arduino_uno::hal::port::mode::Floating;
 use arduino_uno::prelude::*;
 use panic_halt as _;
 
+#[avr_device::interrupt(atmega328p)]
+fn TIMER1_CAPT() {
+    InputCapture1::<Floating>::on_capture();
+}
+
+#[avr_device::interrupt(atmega328p)]
+fn TIMER1_OVF() {
+    InputCapture1::<Floating>::on_overflow();
+}
+
 #[arduino_uno::entry]
 fn main() -> ! {
     let dp = arduino_uno::Peripherals::take().unwrap();
@@ -19,59 +37,54 @@ fn main() -> ! {
         57600.into_baudrate(),
     );
 
-    let timer1 = dp.TC1;
-    // Starting and initializing the timer with prescaling 64.
-    // it gives one clock count every 4 µs.
-    // since the clock register size is 16 bits, the timer is full every
-    // 1/(16e6/64)*2^16 ≈ 260 ms
-    timer1.tccr1b.write(|w| w.cs1().prescale_64());
-
     // Sensor used in this example: https://www.electroschematics.com/hc-sr04-datasheet/
     let mut trig = pins.d2.into_output(&mut pins.ddr);
-    // Pins are floating input by default.
-    let echo = pins.d3;
 
-    'outer: loop {
-        // the timer is reinitialized with value 0.
-        timer1.tcnt1.write(|w| unsafe { w.bits(0) });
+    // With prescaling 64, the timer counts once every 4 µs.  Capturing both edges gives the
+    // start and the end of the echo pulse.
+    let mut echo = InputCapture1::new(
+        dp.TC1,
+        pins.d8,
+        CaptureSettings {
+            edge: Edge::Both,
+            noise_canceler: true,
+            prescaler: Prescaler::Prescale64,
+        },
+    );
+
+    unsafe { avr_device::interrupt::enable() };
+
+    loop {
+        // Drop edges left over from the last measurement
+        while echo.read().is_ok() {}
 
         // the trigger must be set to high under 10 µs as per the HC-SR04 datasheet
         trig.set_high().void_unwrap();
         delay.delay_us(10u16);
         trig.set_low().void_unwrap();
 
-        while echo.is_low().void_unwrap() {
-            // exiting the loop if the timer has reached 200 ms.
-            // 0.2s/4µs = 50000
-            if timer1.tcnt1.read().bits() >= 50000 {
-                // jump to the beginning of the outer loop if no obstacle is detected
-                ufmt::uwriteln!(
-                    &mut serial,
-                    "Nothing was detected and jump to outer loop.\r"
-                )
-                .void_unwrap();
-                continue 'outer;
+        // Give up if there was no echo within 200 ms (50000 timer counts)
+        let start = echo.now();
+        let width = loop {
+            match echo.pulse_width(Edge::Rising) {
+                Ok(width) => break Some(width),
+                Err(nb::Error::WouldBlock) if echo.now().wrapping_sub(start) < 50000 => (),
+                Err(_) => break None,
             }
-        }
-        // Restarting the timer
-        timer1.tcnt1.write(|w| unsafe { w.bits(0) });
+        };
 
-        // Wait for the echo to get low again
-        while echo.is_high().void_unwrap() {}
-
-        // 1 count == 4 us, so the value is multiplied by 4.
-        // 1/58 ≈ (34000 ms/2)* 1µs
-        let value = (timer1.tcnt1.read().bits() * 4) / 58;
+        match width {
+            // 1/58 ≈ (34000 ms/2)* 1µs
+            Some(width) => ufmt::uwriteln!(
+                &mut serial,
+                "Hello, we are {} cms away from target!\r",
+                width.0 / 58
+            )
+            .void_unwrap(),
+            None => ufmt::uwriteln!(&mut serial, "Nothing was detected.\r").void_unwrap(),
+        }
 
         // Await 100 ms before sending the next trig
-        // 0.1s/4µs = 50000
-        while timer1.tcnt1.read().bits() < 25000 {}
-
-        ufmt::uwriteln!(
-            &mut serial,
-            "Hello, we are {} cms away from target!\r",
-            value
-        )
-        .void_unwrap();
+        arduino_uno::delay_ms(100);
     }
 }
@@ -0,0 +1,82 @@
+//! Count button presses on `D4` and `D5` using the pin-change interrupt of port D.
+//!
+//! Connect push-buttons between the pins and GND.  The internal pull-ups are enabled, so a press
+//! pulls the pin low.  The counters are printed whenever they change.  Buttons bounce, so a single
+//! press might be counted more than once.
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::exint;
+use arduino_uno::hal::port::{mode, portd};
+use arduino_uno::prelude::*;
+use core::cell;
+use panic_halt as _;
+
+struct Buttons {
+    bank: exint::PinChange2,
+    a: portd::PD4<mode::Input<mode::PullUp>>,
+    b: portd::PD5<mode::Input<mode::PullUp>>,
+}
+
+static BUTTONS: avr_device::interrupt::Mutex<cell::RefCell<Option<Buttons>>> =
+    avr_device::interrupt::Mutex::new(cell::RefCell::new(None));
+static PRESSES: avr_device::interrupt::Mutex<cell::Cell<(u16, u16)>> =
+    avr_device::interrupt::Mutex::new(cell::Cell::new((0, 0)));
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    let mut pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    let mut serial = arduino_uno::Serial::new(
+        dp.USART0,
+        pins.d0,
+        pins.d1.into_output(&mut pins.ddr),
+        57600.into_baudrate(),
+    );
+
+    let a = pins.d4.into_pull_up_input(&mut pins.ddr);
+    let b = pins.d5.into_pull_up_input(&mut pins.ddr);
+
+    let (_exint, banks) = exint::split(dp.EXINT);
+    let mut bank = exint::PinChange2::new(banks.pin_change2);
+    bank.watch(&a);
+    bank.watch(&b);
+    bank.enable();
+
+    avr_device::interrupt::free(|cs| {
+        *BUTTONS.borrow(cs).borrow_mut() = Some(Buttons { bank, a, b });
+    });
+    unsafe { avr_device::interrupt::enable() };
+
+    let mut last = (0, 0);
+    loop {
+        let presses = avr_device::interrupt::free(|cs| PRESSES.borrow(cs).get());
+        if presses != last {
+            ufmt::uwriteln!(&mut serial, "D4: {} D5: {}\r", presses.0, presses.1).void_unwrap();
+            last = presses;
+        }
+    }
+}
+
+#[avr_device::interrupt(atmega328p)]
+fn PCINT2() {
+    avr_device::interrupt::free(|cs| {
+        if let Some(buttons) = &mut *BUTTONS.borrow(cs).borrow_mut() {
+            let changes = buttons.bank.changes();
+            let (mut a, mut b) = PRESSES.borrow(cs).get();
+
+            // Only count the falling edges
+            if changes.contains(&buttons.a) && buttons.a.is_low().void_unwrap() {
+                a = a.wrapping_add(1);
+            }
+            if changes.contains(&buttons.b) && buttons.b.is_low().void_unwrap() {
+                b = b.wrapping_add(1);
+            }
+
+            PRESSES.borrow(cs).set((a, b));
+        }
+    });
+}
@@ -0,0 +1,34 @@
+//! Drive a 4-pin PC fan with 25 kHz PWM on `D9`.
+//!
+//! The fan speed is read from `A0` and the duty cycle is scaled to the TOP value of the timer,
+//! which is 639 for 25 kHz at 16 MHz.
+#![no_std]
+#![no_main]
+
+use arduino_uno::hal::clock::MHz16;
+use arduino_uno::prelude::*;
+use arduino_uno::pwm;
+use panic_halt as _;
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    let mut pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    let mut adc = arduino_uno::adc::Adc::new(dp.ADC, Default::default());
+    let mut a0 = pins.a0.into_analog_input(&mut adc);
+
+    let mut timer1 = pwm::Timer1Pwm::with_frequency::<MHz16>(dp.TC1, 25_000.hz());
+
+    let mut fan = pins.d9.into_output(&mut pins.ddr).into_pwm(&mut timer1);
+    fan.enable();
+
+    loop {
+        let speed: u16 = nb::block!(adc.read(&mut a0)).void_unwrap();
+        let duty = (speed as u32 * fan.get_max_duty() as u32 / 1023) as u16;
+        fan.set_duty(duty);
+
+        arduino_uno::delay_ms(100);
+    }
+}
@@ -0,0 +1,44 @@
+//! Drive a half-bridge motor driver with 20 kHz phase and frequency correct PWM.
+//!
+//! `D9` drives the high side and `D10` drives the low side.  `D10` is inverted so both outputs
+//! are complementary and, as the pulses are centered, switch at the same points in time.  The
+//! motor speed is read from `A0`.
+#![no_std]
+#![no_main]
+
+use arduino_uno::hal::clock::MHz16;
+use arduino_uno::prelude::*;
+use arduino_uno::pwm;
+use panic_halt as _;
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    let mut pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    let mut adc = arduino_uno::adc::Adc::new(dp.ADC, Default::default());
+    let mut a0 = pins.a0.into_analog_input(&mut adc);
+
+    let mut timer1 = pwm::Timer1Pwm::with_frequency_and_mode::<MHz16>(
+        dp.TC1,
+        20_000.hz(),
+        pwm::WaveformMode::PhaseFrequencyCorrect,
+    );
+
+    let mut high = pins.d9.into_output(&mut pins.ddr).into_pwm(&mut timer1);
+    let mut low = pins.d10.into_output(&mut pins.ddr).into_pwm(&mut timer1);
+    low.set_inverted(true);
+
+    high.enable();
+    low.enable();
+
+    loop {
+        let speed: u16 = nb::block!(adc.read(&mut a0)).void_unwrap();
+        let duty = (speed as u32 * high.get_max_duty() as u32 / 1023) as u16;
+        high.set_duty(duty);
+        low.set_duty(duty);
+
+        arduino_uno::delay_ms(100);
+    }
+}
@@ -0,0 +1,50 @@
+//! Sweep two servos on `D9` and `D2` in opposite directions.
+//!
+//! Both servos are driven by the software-multiplexed driver on `TC1`, so any output pin can be
+//! used.  The second servo is configured with a narrower pulse width range.
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::prelude::*;
+use arduino_uno::servo;
+use panic_halt as _;
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    let mut pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    let servos = servo::MultiServo1::new(dp.TC1);
+    let mut first = servos
+        .attach(
+            pins.d9.into_output(&mut pins.ddr).downgrade(),
+            servo::ServoConfig::default(),
+        )
+        .unwrap_or_else(|_| panic!());
+    let mut second = servos
+        .attach(
+            pins.d2.into_output(&mut pins.ddr).downgrade(),
+            servo::ServoConfig {
+                min_pulse: 1000.us(),
+                max_pulse: 2000.us(),
+            },
+        )
+        .unwrap_or_else(|_| panic!());
+
+    unsafe { avr_device::interrupt::enable() };
+
+    loop {
+        for degrees in (0..=180).chain((0..180).rev()) {
+            first.set_degrees(degrees);
+            second.set_degrees(180 - degrees);
+            arduino_uno::delay_ms(15);
+        }
+    }
+}
+
+#[avr_device::interrupt(atmega328p)]
+fn TIMER1_COMPA() {
+    servo::MultiServo1::on_compare();
+}
@@ -0,0 +1,38 @@
+//! Blink the onboard LED using `TC1` as a periodic count-down timer.
+//!
+//! Unlike `delay_ms()`, the timer keeps running while the loop does other work, so the blink
+//! interval does not drift with the time spent printing.
+#![no_std]
+#![no_main]
+
+use arduino_uno::prelude::*;
+use panic_halt as _;
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    let mut pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    let mut serial = arduino_uno::Serial::new(
+        dp.USART0,
+        pins.d0,
+        pins.d1.into_output(&mut pins.ddr),
+        57600.into_baudrate(),
+    );
+
+    // Digital pin 13 is also connected to an onboard LED marked "L"
+    let mut led = pins.d13.into_output(&mut pins.ddr);
+
+    let mut timer = arduino_uno::timer::Timer1::new(dp.TC1);
+    timer.start(500.ms());
+
+    let mut count: u32 = 0;
+    loop {
+        nb::block!(timer.wait()).void_unwrap();
+        led.toggle().void_unwrap();
+
+        count += 1;
+        ufmt::uwriteln!(&mut serial, "Tick {}\r", count).void_unwrap();
+    }
+}
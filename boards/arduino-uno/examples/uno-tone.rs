@@ -0,0 +1,55 @@
+//! Play a short melody on a buzzer connected to `D11` and blink the LED with a software tone.
+//!
+//! The melody is generated in hardware by `TC2`.  The LED on `D13` is not a compare output pin, so
+//! it is toggled by the `TIMER1_COMPA` interrupt instead.
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::prelude::*;
+use arduino_uno::tone;
+use panic_halt as _;
+
+const MELODY: [(u32, u32); 8] = [
+    (262, 250),
+    (294, 250),
+    (330, 250),
+    (349, 250),
+    (392, 500),
+    (392, 500),
+    (440, 250),
+    (392, 1000),
+];
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    let mut pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    let mut buzzer = tone::Tone2::new(dp.TC2, pins.d11.into_output(&mut pins.ddr));
+    let mut blink = tone::SoftTone1::new(dp.TC1, pins.d13.into_output(&mut pins.ddr).downgrade());
+
+    unsafe { avr_device::interrupt::enable() };
+
+    blink.play(2.hz());
+
+    loop {
+        for &(frequency, duration) in MELODY.iter() {
+            buzzer.play_for(frequency.hz(), duration.ms());
+            while buzzer.is_playing() {}
+            arduino_uno::delay_ms(50);
+        }
+        arduino_uno::delay_ms(1000);
+    }
+}
+
+#[avr_device::interrupt(atmega328p)]
+fn TIMER2_COMPA() {
+    tone::Tone2::on_compare();
+}
+
+#[avr_device::interrupt(atmega328p)]
+fn TIMER1_COMPA() {
+    tone::SoftTone1::on_compare();
+}
@@ -97,6 +97,29 @@ pub fn delay_us(us: u16) {
     Delay::new().delay_us(us)
 }
 
+/// System time service providing `millis()` and `micros()` using `TC0`
+///
+/// The `TIMER0_COMPA` interrupt must be forwarded to the time service.
+///
+/// # Example
+/// For a full example, see [`examples/uno-millis.rs`][ex-millis].  In short:
+/// ```no_run
+/// let dp = arduino_uno::Peripherals::take().unwrap();
+///
+/// let systime = arduino_uno::SysTime::new(dp.TC0);
+/// unsafe { avr_device::interrupt::enable() };
+///
+/// let now = systime.millis();
+///
+/// #[avr_device::interrupt(atmega328p)]
+/// fn TIMER0_COMPA() {
+///     arduino_uno::SysTime::on_interrupt();
+/// }
+/// ```
+///
+/// [ex-millis]: https://github.com/Rahix/avr-hal/blob/master/boards/arduino-uno/examples/uno-millis.rs
+pub type SysTime = hal::systime::SysTime<hal::clock::MHz16>;
+
 /// Support for the Serial Peripheral Interface
 ///
 /// # Example
@@ -144,6 +167,88 @@ pub mod adc {
     pub use atmega328p_hal::adc::*;
 }
 
+/// Input capture for measuring periods and pulse widths
+///
+/// The capture input of `TC1` is `D8`.
+///
+/// # Example
+/// ```
+/// let mut capture = arduino_uno::capture::InputCapture1::new(
+///     dp.TC1,
+///     pins.d8,
+///     arduino_uno::capture::CaptureSettings::default(),
+/// );
+/// unsafe { avr_device::interrupt::enable() };
+///
+/// let frequency = nb::block!(capture.frequency()).void_unwrap();
+///
+/// #[avr_device::interrupt(atmega328p)]
+/// fn TIMER1_CAPT() {
+///     arduino_uno::capture::InputCapture1::<Floating>::on_capture();
+/// }
+///
+/// #[avr_device::interrupt(atmega328p)]
+/// fn TIMER1_OVF() {
+///     arduino_uno::capture::InputCapture1::<Floating>::on_overflow();
+/// }
+/// ```
+pub mod capture {
+    pub use atmega328p_hal::capture::*;
+
+    /// Input capture on `TC1`
+    pub type InputCapture1<IMODE> =
+        atmega328p_hal::capture::InputCapture1<atmega328p_hal::clock::MHz16, IMODE>;
+}
+
+/// Analog comparator
+///
+/// The comparator inputs `AIN0` and `AIN1` are on pins `D6` and `D7`.  Instead of `AIN1`, one of
+/// the analog pins can be used as negative input by handing the ADC multiplexer to the comparator.
+///
+/// # Example
+/// ```
+/// let mut ac = dp.AC;
+/// let ain0 = pins.d6.into_comparator_input(&mut ac);
+/// let ain1 = pins.d7.into_comparator_input(&mut ac);
+///
+/// let comparator = arduino_uno::comparator::AnalogComparator::new(ac, ain0, ain1);
+///
+/// if comparator.output() {
+///     // D6 is higher than D7
+/// }
+/// ```
+pub mod comparator {
+    pub use atmega328p_hal::comparator::*;
+}
+
+/// External and pin-change interrupts
+///
+/// `INT0` is on pin `D2` and `INT1` is on pin `D3`.  Additionally, every pin can trigger the
+/// pin-change interrupt of its bank:
+///
+/// | Bank | Pins |
+/// | --- | --- |
+/// | `PinChange0` | `D8` - `D13` |
+/// | `PinChange1` | `A0` - `A5` |
+/// | `PinChange2` | `D0` - `D7` |
+///
+/// # Example
+/// For a full pin-change example, see [`examples/uno-pin-change.rs`][ex-pin-change].  In short:
+/// ```
+/// let (mut exint, banks) = arduino_uno::exint::split(dp.EXINT);
+/// let mut int0 = pins.d2.into_ext_interrupt(&mut exint, arduino_uno::exint::Sense::Falling);
+/// int0.enable();
+///
+/// let mut bank = arduino_uno::exint::PinChange2::new(banks.pin_change2);
+/// bank.watch(&button);
+/// bank.enable();
+/// ```
+///
+/// [ex-pin-change]: https://github.com/Rahix/avr-hal/blob/master/boards/arduino-uno/examples/uno-pin-change.rs
+pub mod exint {
+    pub use atmega328p_hal::exint::*;
+}
+
 /// Support for PWM pins
 ///
 /// The 3 timers of ATmega328P can be used for PWM on certain pins.
@@ -176,11 +281,148 @@ pub mod adc {
 /// | `pins.d10` | `.into_pwm(&mut timer1)` |
 /// | `pins.d11` | `.into_pwm(&mut timer2)` |
 ///
+/// For frequencies which cannot be reached using one of the prescalers, a timer can instead be
+/// initialized with `with_frequency()`.  The maximum duty cycle then depends on the frequency and
+/// for `timer0` and `timer2` only the pins `d5` and `d3` can be used.  See
+/// [`examples/uno-pwm-frequency.rs`][ex-pwm-frequency]:
+///
+/// ```
+/// let mut timer1 = arduino_uno::pwm::Timer1Pwm::with_frequency::<arduino_uno::hal::clock::MHz16>(
+///     dp.TC1,
+///     25_000.hz(),
+/// );
+/// ```
+///
+/// Timers use fast PWM by default.  For symmetric pulses, e.g. for motor drivers, a phase correct
+/// mode can be selected with `with_mode()` or `with_frequency_and_mode()`.  Each output can also
+/// be inverted with `set_inverted()`.  See [`examples/uno-pwm-phase-correct.rs`][ex-pwm-phase]:
+///
+/// ```
+/// let mut timer1 = arduino_uno::pwm::Timer1Pwm::with_mode(
+///     dp.TC1,
+///     arduino_uno::pwm::Prescaler::Prescale64,
+///     arduino_uno::pwm::WaveformMode::PhaseCorrect,
+/// );
+///
+/// let mut pin = pins.d10.into_output(&mut pins.ddr).into_pwm(&mut timer1);
+/// pin.set_inverted(true);
+/// ```
+///
 /// [ex-pwm]: https://github.com/Rahix/avr-hal/blob/master/boards/arduino-uno/examples/uno-pwm.rs
+/// [ex-pwm-frequency]: https://github.com/Rahix/avr-hal/blob/master/boards/arduino-uno/examples/uno-pwm-frequency.rs
+/// [ex-pwm-phase]: https://github.com/Rahix/avr-hal/blob/master/boards/arduino-uno/examples/uno-pwm-phase-correct.rs
 pub mod pwm {
     pub use atmega328p_hal::pwm::*;
 }
 
+/// Servo control
+///
+/// `ServoN` drives servos in hardware from the compare outputs of a 16-bit timer.  These are `D9`
+/// and `D10` on `TC1`.  `MultiServo1` drives up to 12 servos on any output pins from the
+/// `TIMER1_COMPA` interrupt.
+///
+/// # Example
+/// ```
+/// let mut servo1 = arduino_uno::servo::Servo1::new(dp.TC1);
+/// let mut servo = pins
+///     .d9
+///     .into_output(&mut pins.ddr)
+///     .into_servo(&mut servo1, arduino_uno::servo::ServoConfig::default());
+///
+/// servo.set_degrees(90);
+/// ```
+///
+/// With the software-multiplexed driver:
+/// ```
+/// let servos = arduino_uno::servo::MultiServo1::new(dp.TC1);
+/// let mut servo = servos
+///     .attach(
+///         pins.d2.into_output(&mut pins.ddr).downgrade(),
+///         arduino_uno::servo::ServoConfig::default(),
+///     )
+///     .unwrap();
+/// unsafe { avr_device::interrupt::enable() };
+///
+/// servo.set_pulse(1500.us());
+///
+/// #[avr_device::interrupt(atmega328p)]
+/// fn TIMER1_COMPA() {
+///     arduino_uno::servo::MultiServo1::on_compare();
+/// }
+/// ```
+pub mod servo {
+    pub use atmega328p_hal::servo::*;
+
+    /// Hardware servo driver on `TC1` (pins `D9`, `D10`)
+    pub type Servo1 = atmega328p_hal::servo::Servo1<atmega328p_hal::clock::MHz16>;
+
+    /// Software-multiplexed servo driver on `TC1`
+    pub type MultiServo1 = atmega328p_hal::servo::MultiServo1<atmega328p_hal::clock::MHz16>;
+}
+
+/// Tone and square-wave generation
+///
+/// `ToneN` toggles the `OCnA` pin of a timer in hardware.  `SoftToneN` toggles any output pin from
+/// the `TIMERn_COMPA` interrupt.
+///
+/// # Example
+/// ```
+/// let mut tone2 = arduino_uno::tone::Tone2::new(dp.TC2, pins.d11.into_output(&mut pins.ddr));
+///
+/// tone2.play(440.hz());
+/// ```
+pub mod tone {
+    pub use atmega328p_hal::tone::*;
+
+    /// Tone output on `TC0` (pin `D6`)
+    pub type Tone0 = atmega328p_hal::tone::Tone0<atmega328p_hal::clock::MHz16>;
+
+    /// Software tone output on `TC0`
+    pub type SoftTone0 = atmega328p_hal::tone::SoftTone0<atmega328p_hal::clock::MHz16>;
+
+    /// Tone output on `TC1` (pin `D9`)
+    pub type Tone1 = atmega328p_hal::tone::Tone1<atmega328p_hal::clock::MHz16>;
+
+    /// Software tone output on `TC1`
+    pub type SoftTone1 = atmega328p_hal::tone::SoftTone1<atmega328p_hal::clock::MHz16>;
+
+    /// Tone output on `TC2` (pin `D11`)
+    pub type Tone2 = atmega328p_hal::tone::Tone2<atmega328p_hal::clock::MHz16>;
+
+    /// Software tone output on `TC2`
+    pub type SoftTone2 = atmega328p_hal::tone::SoftTone2<atmega328p_hal::clock::MHz16>;
+}
+
+/// Count-down timers
+///
+/// The timers implement `embedded_hal::timer::CountDown` and `embedded_hal::timer::Periodic`.
+/// Prescaler and TOP value are calculated automatically from the requested duration.
+///
+/// # Example
+/// ```
+/// let mut timer1 = arduino_uno::timer::Timer1::new(dp.TC1);
+///
+/// timer1.start(500.ms());
+/// loop {
+///     nb::block!(timer1.wait()).void_unwrap();
+///     led.toggle().void_unwrap();
+/// }
+/// ```
+///
+/// A timer which is also used for PWM cannot be used as a count-down timer at the same time.
+pub mod timer {
+    pub use atmega328p_hal::timer::*;
+
+    /// `TC0` as a count-down timer
+    pub type Timer0 = atmega328p_hal::timer::Timer0<atmega328p_hal::clock::MHz16>;
+
+    /// `TC1` as a count-down timer
+    pub type Timer1 = atmega328p_hal::timer::Timer1<atmega328p_hal::clock::MHz16>;
+
+    /// `TC2` as a count-down timer
+    pub type Timer2 = atmega328p_hal::timer::Timer2<atmega328p_hal::clock::MHz16>;
+}
+
 /// Serial (UART) interface on pins `D0` (RX) and `D1` (TX)
 ///
 /// # Example
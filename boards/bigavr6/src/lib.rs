@@ -9,12 +9,17 @@ pub use crate::hal::pac;
 pub use crate::hal::entry;
 
 pub use crate::pac::Peripherals;
+pub use crate::hal::adc;
+pub use crate::hal::capture;
+pub use crate::hal::timer;
+pub use crate::hal::tone;
 pub mod prelude {
     pub use crate::hal::prelude::*;
     pub use crate::hal::usart::BaudrateExt as _;
 }
 
 pub type Delay = crate::hal::delay::Delay<hal::clock::MHz16>;
+pub type SysTime = crate::hal::systime::SysTime<hal::clock::MHz16>;
 pub type Serial<IMODE> = crate::hal::usart::Usart0<hal::clock::MHz16, IMODE>;
 pub type I2cMaster<M> = crate::hal::i2c::I2cMaster<hal::clock::MHz16, M>;
 #[doc(hidden)]
@@ -82,6 +82,29 @@ pub fn delay_us(us: u16) {
     Delay::new().delay_us(us)
 }
 
+/// System time service providing `millis()` and `micros()` using `TC0`
+///
+/// The `TIMER0_COMPA` interrupt must be forwarded to the time service.
+///
+/// # Example
+/// For a full example, see [`examples/uno-millis.rs`][ex-millis].  In short:
+/// ```no_run
+/// let dp = sparkfun_pro_micro::Peripherals::take().unwrap();
+///
+/// let systime = sparkfun_pro_micro::SysTime::new(dp.TC0);
+/// unsafe { avr_device::interrupt::enable() };
+///
+/// let now = systime.millis();
+///
+/// #[avr_device::interrupt(atmega32u4)]
+/// fn TIMER0_COMPA() {
+///     sparkfun_pro_micro::SysTime::on_interrupt();
+/// }
+/// ```
+///
+/// [ex-millis]: https://github.com/Rahix/avr-hal/blob/master/boards/arduino-uno/examples/uno-millis.rs
+pub type SysTime = hal::systime::SysTime<hal::clock::MHz16>;
+
 /// Support for the Serial Peripheral Interface
 ///
 /// # Example
@@ -139,6 +162,39 @@ pub mod adc {
     pub use atmega32u4_hal::adc::*;
 }
 
+/// Input capture for measuring periods and pulse widths
+///
+/// The capture input of `TC1` is `D4`.
+///
+/// # Example
+/// ```
+/// let mut capture = sparkfun_pro_micro::capture::InputCapture1::new(
+///     dp.TC1,
+///     pins.d4,
+///     sparkfun_pro_micro::capture::CaptureSettings::default(),
+/// );
+/// unsafe { avr_device::interrupt::enable() };
+///
+/// let frequency = nb::block!(capture.frequency()).void_unwrap();
+///
+/// #[avr_device::interrupt(atmega32u4)]
+/// fn TIMER1_CAPT() {
+///     sparkfun_pro_micro::capture::InputCapture1::<Floating>::on_capture();
+/// }
+///
+/// #[avr_device::interrupt(atmega32u4)]
+/// fn TIMER1_OVF() {
+///     sparkfun_pro_micro::capture::InputCapture1::<Floating>::on_overflow();
+/// }
+/// ```
+pub mod capture {
+    pub use atmega32u4_hal::capture::*;
+
+    /// Input capture on `TC1`
+    pub type InputCapture1<IMODE> =
+        atmega32u4_hal::capture::InputCapture1<atmega32u4_hal::clock::MHz16, IMODE>;
+}
+
 /// Support for PWM pins
 ///
 /// The 4 timers of ATmega32U4 can be used for PWM on certain pins.
@@ -173,6 +229,146 @@ pub mod pwm {
     pub use atmega32u4_hal::pwm::*;
 }
 
+/// High-speed PWM with `TC4`
+///
+/// `TC4` can run from the PLL at up to 64 MHz with 10-bit resolution and drive pairs of
+/// complementary outputs with dead time, e.g. for the half-bridges of a motor driver.
+///
+/// # Example
+/// ```
+/// let mut timer4 = sparkfun_pro_micro::hspwm::Timer4::new(
+///     dp.TC4,
+///     &dp.PLL,
+///     sparkfun_pro_micro::hspwm::Config::default(),
+/// );
+///
+/// let mut half_bridge = timer4.complementary_b(
+///     pins.d10.into_output(&mut pins.ddr),
+///     pins.d9.into_output(&mut pins.ddr),
+/// );
+///
+/// half_bridge.set_duty(512);
+/// half_bridge.enable();
+/// ```
+///
+/// | Channel | `OC4x` | `!OC4x` |
+/// | --- | --- | --- |
+/// | `B` | `pins.d10` | `pins.d9` |
+pub mod hspwm {
+    pub use atmega32u4_hal::hspwm::*;
+
+    /// `TC4` in high-speed PWM mode
+    pub type Timer4 = atmega32u4_hal::hspwm::Timer4<atmega32u4_hal::clock::MHz16>;
+}
+
+/// Servo control
+///
+/// `ServoN` drives servos in hardware from the compare outputs of a 16-bit timer.  These are `D9`
+/// and `D10` on `TC1`, `D5` on `TC3`.  `MultiServo1` drives up to 12 servos on any output pins from
+/// the `TIMER1_COMPA` interrupt.
+///
+/// # Example
+/// ```
+/// let mut servo1 = sparkfun_pro_micro::servo::Servo1::new(dp.TC1);
+/// let mut servo = pins
+///     .d9
+///     .into_output(&mut pins.ddr)
+///     .into_servo(&mut servo1, sparkfun_pro_micro::servo::ServoConfig::default());
+///
+/// servo.set_degrees(90);
+/// ```
+///
+/// With the software-multiplexed driver:
+/// ```
+/// let servos = sparkfun_pro_micro::servo::MultiServo1::new(dp.TC1);
+/// let mut servo = servos
+///     .attach(
+///         pins.d2.into_output(&mut pins.ddr).downgrade(),
+///         sparkfun_pro_micro::servo::ServoConfig::default(),
+///     )
+///     .unwrap();
+/// unsafe { avr_device::interrupt::enable() };
+///
+/// servo.set_pulse(1500.us());
+///
+/// #[avr_device::interrupt(atmega32u4)]
+/// fn TIMER1_COMPA() {
+///     sparkfun_pro_micro::servo::MultiServo1::on_compare();
+/// }
+/// ```
+pub mod servo {
+    pub use atmega32u4_hal::servo::*;
+
+    /// Hardware servo driver on `TC1` (pins `D9`, `D10`)
+    pub type Servo1 = atmega32u4_hal::servo::Servo1<atmega32u4_hal::clock::MHz16>;
+
+    /// Hardware servo driver on `TC3` (pin `D5`)
+    pub type Servo3 = atmega32u4_hal::servo::Servo3<atmega32u4_hal::clock::MHz16>;
+
+    /// Software-multiplexed servo driver on `TC1`
+    pub type MultiServo1 = atmega32u4_hal::servo::MultiServo1<atmega32u4_hal::clock::MHz16>;
+
+    /// Software-multiplexed servo driver on `TC3`
+    pub type MultiServo3 = atmega32u4_hal::servo::MultiServo3<atmega32u4_hal::clock::MHz16>;
+}
+
+/// Tone and square-wave generation
+///
+/// `ToneN` toggles the `OCnA` pin of a timer in hardware.  `SoftToneN` toggles any output pin from
+/// the `TIMERn_COMPA` interrupt.
+///
+/// # Example
+/// ```
+/// let mut tone3 = sparkfun_pro_micro::tone::Tone3::new(dp.TC3, pins.d5.into_output(&mut pins.ddr));
+///
+/// tone3.play(440.hz());
+/// ```
+pub mod tone {
+    pub use atmega32u4_hal::tone::*;
+
+    /// Tone output on `TC1` (pin `D9`)
+    pub type Tone1 = atmega32u4_hal::tone::Tone1<atmega32u4_hal::clock::MHz16>;
+
+    /// Software tone output on `TC1`
+    pub type SoftTone1 = atmega32u4_hal::tone::SoftTone1<atmega32u4_hal::clock::MHz16>;
+
+    /// Tone output on `TC3` (pin `D5`)
+    pub type Tone3 = atmega32u4_hal::tone::Tone3<atmega32u4_hal::clock::MHz16>;
+
+    /// Software tone output on `TC3`
+    pub type SoftTone3 = atmega32u4_hal::tone::SoftTone3<atmega32u4_hal::clock::MHz16>;
+}
+
+/// Count-down timers
+///
+/// The timers implement `embedded_hal::timer::CountDown` and `embedded_hal::timer::Periodic`.
+/// Prescaler and TOP value are calculated automatically from the requested duration.
+///
+/// # Example
+/// ```
+/// let mut timer1 = sparkfun_pro_micro::timer::Timer1::new(dp.TC1);
+///
+/// timer1.start(500.ms());
+/// loop {
+///     nb::block!(timer1.wait()).void_unwrap();
+///     led.toggle().void_unwrap();
+/// }
+/// ```
+///
+/// A timer which is also used for PWM cannot be used as a count-down timer at the same time.
+pub mod timer {
+    pub use atmega32u4_hal::timer::*;
+
+    /// `TC0` as a count-down timer
+    pub type Timer0 = atmega32u4_hal::timer::Timer0<atmega32u4_hal::clock::MHz16>;
+
+    /// `TC1` as a count-down timer
+    pub type Timer1 = atmega32u4_hal::timer::Timer1<atmega32u4_hal::clock::MHz16>;
+
+    /// `TC3` as a count-down timer
+    pub type Timer3 = atmega32u4_hal::timer::Timer3<atmega32u4_hal::clock::MHz16>;
+}
+
 /// Serial (UART) interface on pins `D0` (RX) and `D1` (TX)
 ///
 /// # Example
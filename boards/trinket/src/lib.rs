@@ -10,8 +10,11 @@ pub use crate::hal::entry;
 
 pub use crate::pac::Peripherals;
 pub use crate::hal::prelude;
+pub use crate::hal::adc;
+pub use crate::hal::timer;
 
 pub type Delay = crate::hal::delay::Delay<hal::clock::MHz8>;
+pub type SysTime = crate::hal::systime::SysTime<hal::clock::MHz8>;
 
 pub use crate::pins::*;
 mod pins {
@@ -29,15 +32,15 @@ mod pins {
         /// Reexport of the pins with names as on the Trinket board
         pub struct Pins {
             /// `#0`: `PB0`, `DI`(SPI), `SDA`(I2C)
-            pub d0: portb::pb0::PB0,
+            pub d0: portb::pb0::PB0 = 0,
             /// `#1`: `PB1`, `DO`(SPI), Builtin LED
-            pub d1: portb::pb1::PB1,
+            pub d1: portb::pb1::PB1 = 1,
             /// `#2`: `PB2`, `SCK`(SPI), `SCL`(I2C)
-            pub d2: portb::pb2::PB2,
+            pub d2: portb::pb2::PB2 = 2,
             /// `#3`: `PB3`
-            pub d3: portb::pb3::PB3,
+            pub d3: portb::pb3::PB3 = 3,
             /// `#4`: `PB4`
-            pub d4: portb::pb4::PB4,
+            pub d4: portb::pb4::PB4 = 4,
         }
     }
 }
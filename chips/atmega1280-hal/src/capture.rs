@@ -0,0 +1,49 @@
+//! Input capture on the 16-bit timers
+//!
+//! See [`avr_hal_generic::capture`] for details.
+//!
+//! | Driver | Timer | Pin |
+//! | --- | --- | --- |
+//! | `InputCapture1` | `TC1` | `PD4` (`ICP1`) |
+//! | `InputCapture3` | `TC3` | `PE7` (`ICP3`) |
+//! | `InputCapture4` | `TC4` | `PL0` (`ICP4`) |
+//! | `InputCapture5` | `TC5` | `PL1` (`ICP5`) |
+
+use crate::port::{portd, porte, portl};
+pub use avr_hal_generic::capture::*;
+
+avr_hal_generic::impl_input_capture! {
+    /// Input capture on `TC1` with pin `PD4` (`ICP1`)
+    pub struct InputCapture1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pin: portd::PD4,
+    }
+}
+
+avr_hal_generic::impl_input_capture! {
+    /// Input capture on `TC3` with pin `PE7` (`ICP3`)
+    pub struct InputCapture3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        pin: porte::PE7,
+    }
+}
+
+avr_hal_generic::impl_input_capture! {
+    /// Input capture on `TC4` with pin `PL0` (`ICP4`)
+    pub struct InputCapture4 {
+        peripheral: crate::pac::TC4,
+        register_suffix: 4,
+        pin: portl::PL0,
+    }
+}
+
+avr_hal_generic::impl_input_capture! {
+    /// Input capture on `TC5` with pin `PL1` (`ICP5`)
+    pub struct InputCapture5 {
+        peripheral: crate::pac::TC5,
+        register_suffix: 5,
+        pin: portl::PL1,
+    }
+}
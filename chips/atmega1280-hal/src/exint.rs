@@ -0,0 +1,127 @@
+//! External Interrupts and Pin-Change Interrupts
+//!
+//! | Interrupt | Pin |
+//! | --- | --- |
+//! | `INT0` - `INT3` | `PD0` - `PD3` |
+//! | `INT4` - `INT7` | `PE4` - `PE7` |
+//!
+//! Pin-change interrupt banks:
+//!
+//! | Bank | Pins |
+//! | --- | --- |
+//! | `PinChange0` | `PB0` - `PB7` |
+//! | `PinChange1` | `PE0`, `PJ0` - `PJ6` |
+//! | `PinChange2` | `PK0` - `PK7` |
+
+use crate::port::{portb, portd, porte, portj, portk};
+
+avr_hal_generic::impl_exint! {
+    pub struct ExtInt {
+        peripheral: crate::pac::EXINT,
+        interrupts: {
+            portd::PD0: INT0 {
+                sense: crate::pac::EXINT => eicra[0],
+                enable: eimsk[0],
+                flag: eifr[0],
+            },
+            portd::PD1: INT1 {
+                sense: crate::pac::EXINT => eicra[2],
+                enable: eimsk[1],
+                flag: eifr[1],
+            },
+            portd::PD2: INT2 {
+                sense: crate::pac::EXINT => eicra[4],
+                enable: eimsk[2],
+                flag: eifr[2],
+            },
+            portd::PD3: INT3 {
+                sense: crate::pac::EXINT => eicra[6],
+                enable: eimsk[3],
+                flag: eifr[3],
+            },
+            porte::PE4: INT4 {
+                sense: crate::pac::EXINT => eicrb[0],
+                enable: eimsk[4],
+                flag: eifr[4],
+            },
+            porte::PE5: INT5 {
+                sense: crate::pac::EXINT => eicrb[2],
+                enable: eimsk[5],
+                flag: eifr[5],
+            },
+            porte::PE6: INT6 {
+                sense: crate::pac::EXINT => eicrb[4],
+                enable: eimsk[6],
+                flag: eifr[6],
+            },
+            porte::PE7: INT7 {
+                sense: crate::pac::EXINT => eicrb[6],
+                enable: eimsk[7],
+                flag: eifr[7],
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_pin_change! {
+    peripheral: crate::pac::EXINT,
+    banks: {
+        /// Pin-change interrupt `PCINT0` (pins `PCINT0` - `PCINT7` on port B)
+        pub struct PinChange0 {
+            enable: pcicr[0],
+            flag: pcifr[0],
+            mask: pcmsk0,
+            state: || { unsafe { &*crate::pac::PORTB::ptr() }.pinb.read().bits() },
+            pins: {
+                portb::PB0: 0,
+                portb::PB1: 1,
+                portb::PB2: 2,
+                portb::PB3: 3,
+                portb::PB4: 4,
+                portb::PB5: 5,
+                portb::PB6: 6,
+                portb::PB7: 7,
+            },
+        }
+
+        /// Pin-change interrupt `PCINT1` (pins `PCINT8` - `PCINT15` on ports E and J)
+        pub struct PinChange1 {
+            enable: pcicr[1],
+            flag: pcifr[1],
+            mask: pcmsk1,
+            state: || {
+                let pine = unsafe { &*crate::pac::PORTE::ptr() }.pine.read().bits();
+                let pinj = unsafe { &*crate::pac::PORTJ::ptr() }.pinj.read().bits();
+                (pine & 0x01) | (pinj << 1)
+            },
+            pins: {
+                porte::PE0: 0,
+                portj::PJ0: 1,
+                portj::PJ1: 2,
+                portj::PJ2: 3,
+                portj::PJ3: 4,
+                portj::PJ4: 5,
+                portj::PJ5: 6,
+                portj::PJ6: 7,
+            },
+        }
+
+        /// Pin-change interrupt `PCINT2` (pins `PCINT16` - `PCINT23` on port K)
+        pub struct PinChange2 {
+            enable: pcicr[2],
+            flag: pcifr[2],
+            mask: pcmsk2,
+            state: || { unsafe { &*crate::pac::PORTK::ptr() }.pink.read().bits() },
+            pins: {
+                portk::PK0: 0,
+                portk::PK1: 1,
+                portk::PK2: 2,
+                portk::PK3: 3,
+                portk::PK4: 4,
+                portk::PK5: 5,
+                portk::PK6: 6,
+                portk::PK7: 7,
+            },
+        }
+    },
+}
@@ -10,7 +10,15 @@ pub use avr_device::entry;
 pub use avr_hal_generic::clock;
 pub use avr_hal_generic::delay;
 
+pub mod adc;
+pub mod capture;
+pub mod comparator;
+pub mod exint;
 pub mod port;
+pub mod rtc;
+pub mod systime;
+pub mod timer;
+pub mod tone;
 pub mod usart;
 
 pub mod prelude {
@@ -0,0 +1,32 @@
+//! System time service on `TC0`
+//!
+//! See [`avr_hal_generic::systime`] for details.  The compare-match interrupt `TIMER0_COMPA` must
+//! call [`SysTime::on_interrupt()`].
+
+avr_hal_generic::impl_systime! {
+    /// System time service providing `millis()` and `micros()` using `TC0`
+    pub struct SysTime {
+        timer: crate::pac::TC0,
+        start: |tim, top| {
+            tim.tccr0b.reset();
+            // WGM01 selects CTC mode
+            tim.tccr0a.write(|w| unsafe { w.bits(0b0000_0010) });
+            tim.tcnt0.reset();
+            tim.ocr0a.write(|w| unsafe { w.bits(top) });
+            tim.tifr0.write(|w| w.ocf0a().set_bit());
+            tim.timsk0.write(|w| w.ocie0a().set_bit());
+            // Prescaler 64
+            tim.tccr0b.write(|w| unsafe { w.bits(0b011) });
+        },
+        stop: |tim| {
+            tim.tccr0b.reset();
+            tim.timsk0.reset();
+        },
+        counter: |tim| {
+            tim.tcnt0.read().bits()
+        },
+        is_pending: |tim| {
+            tim.tifr0.read().ocf0a().bit_is_set()
+        },
+    }
+}
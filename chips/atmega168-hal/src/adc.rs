@@ -7,10 +7,15 @@ use crate::pac::adc::admux::MUX_A;
 avr_hal_generic::impl_adc! {
     pub struct Adc {
         type ChannelID = MUX_A;
+        type ReferenceVoltage = avr_hal_generic::adc::ReferenceVoltage;
+        type TriggerSource = avr_hal_generic::adc::TriggerSource;
         peripheral: crate::pac::ADC,
         set_mux: |peripheral, id| {
             peripheral.admux.modify(|_, w| w.mux().variant(id));
         },
+        settling: |id| { id == MUX_A::ADC_VBG },
+        bandgap: MUX_A::ADC_VBG,
+        sleep_control: (crate::pac::CPU, smcr),
         pins: {
             pc0: (PC0, MUX_A::ADC0, didr0::adc0d),
             pc1: (PC1, MUX_A::ADC1, didr0::adc1d),
@@ -0,0 +1,10 @@
+use crate::port::portd::{PD6, PD7};
+
+avr_hal_generic::impl_analog_comparator! {
+    pub struct AnalogComparator {
+        peripheral: crate::pac::AC,
+        ain0: (PD6, didr1::ain0d),
+        ain1: (PD7, didr1::ain1d),
+        input_capture: true,
+    }
+}
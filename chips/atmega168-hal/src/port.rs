@@ -1,5 +1,5 @@
 //! `PORTB` - `PORTD` digital IO
-pub use avr_hal_generic::port::mode;
+pub use avr_hal_generic::port::{mode, DynamicMode, ModeError};
 
 pub trait PortExt {
     type Parts;
@@ -46,7 +46,6 @@ avr_hal_generic::impl_pwm! {
         type Duty: u8,
         timer: crate::pac::TC0,
         init: |tim, prescaler| {
-            tim.tccr0a.modify(|_, w| w.wgm0().pwm_fast());
             tim.tccr0b.modify(|_, w| match prescaler {
                 Prescaler::Direct => w.cs0().direct(),
                 Prescaler::Prescale8 => w.cs0().prescale_8(),
@@ -55,19 +54,48 @@ avr_hal_generic::impl_pwm! {
                 Prescaler::Prescale1024 => w.cs0().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            tim.tccr0a.modify(|_, w| match mode {
+                WaveformMode::Fast => w.wgm0().pwm_fast(),
+                // Phase and frequency correct PWM is not available on 8-bit timers
+                WaveformMode::PhaseCorrect | WaveformMode::PhaseFrequencyCorrect => {
+                    w.wgm0().pwm_phase()
+                }
+            });
+            tim.tccr0b.modify(|_, w| w.wgm02().bit(variable_top));
+        },
+        top: |tim| {
+            if tim.tccr0b.read().wgm02().bit_is_set() {
+                tim.ocr0a.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.ocr0a.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             portd::PD6: {
                 ocr: ocr0a,
-                into_pwm: |tim| if enable {
-                    tim.tccr0a.modify(|_, w| w.com0a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr0a.modify(|_, w| if inverted {
+                        w.com0a().match_set()
+                    } else {
+                        w.com0a().match_clear()
+                    });
                 } else {
                     tim.tccr0a.modify(|_, w| w.com0a().disconnected());
                 },
+                holds_top: true,
             },
             portd::PD5: {
                 ocr: ocr0b,
-                into_pwm: |tim| if enable {
-                    tim.tccr0a.modify(|_, w| w.com0b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr0a.modify(|_, w| if inverted {
+                        w.com0b().match_set()
+                    } else {
+                        w.com0b().match_clear()
+                    });
                 } else {
                     tim.tccr0a.modify(|_, w| w.com0b().disconnected());
                 },
@@ -94,31 +122,55 @@ avr_hal_generic::impl_pwm! {
         type Duty: u16,
         timer: crate::pac::TC1,
         init: |tim, prescaler| {
-            tim.tccr1a.modify(|_, w| w.wgm1().bits(0b01));
-            tim.tccr1b.modify(|_, w| {
-                w.wgm1().bits(0b01);
-                match prescaler {
-                    Prescaler::Direct => w.cs1().direct(),
-                    Prescaler::Prescale8 => w.cs1().prescale_8(),
-                    Prescaler::Prescale64 => w.cs1().prescale_64(),
-                    Prescaler::Prescale256 => w.cs1().prescale_256(),
-                    Prescaler::Prescale1024 => w.cs1().prescale_1024(),
-                }
+            tim.tccr1b.modify(|_, w| match prescaler {
+                Prescaler::Direct => w.cs1().direct(),
+                Prescaler::Prescale8 => w.cs1().prescale_8(),
+                Prescaler::Prescale64 => w.cs1().prescale_64(),
+                Prescaler::Prescale256 => w.cs1().prescale_256(),
+                Prescaler::Prescale1024 => w.cs1().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            if mode == WaveformMode::PhaseFrequencyCorrect && !variable_top {
+                // This mode always takes TOP from ICR1
+                tim.icr1.write(|w| unsafe { w.bits(0xff) });
+            }
+            let wgm = mode.wgm_16bit(variable_top);
+            tim.tccr1a.modify(|_, w| w.wgm1().bits(wgm & 0b11));
+            tim.tccr1b.modify(|_, w| w.wgm1().bits(wgm >> 2));
+        },
+        top: |tim| {
+            // WGM13 selects ICR1 as TOP
+            if tim.tccr1b.read().wgm1().bits() & 0b10 != 0 {
+                tim.icr1.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.icr1.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             portb::PB1: {
                 ocr: ocr1a,
-                into_pwm: |tim| if enable {
-                    tim.tccr1a.modify(|_, w| w.com1a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr1a.modify(|_, w| if inverted {
+                        w.com1a().match_set()
+                    } else {
+                        w.com1a().match_clear()
+                    });
                 } else {
                     tim.tccr1a.modify(|_, w| w.com1a().disconnected());
                 },
             },
             portb::PB2: {
                 ocr: ocr1b,
-                into_pwm: |tim| if enable {
-                    tim.tccr1a.modify(|_, w| w.com1b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr1a.modify(|_, w| if inverted {
+                        w.com1b().match_set()
+                    } else {
+                        w.com1b().match_clear()
+                    });
                 } else {
                     tim.tccr1a.modify(|_, w| w.com1b().disconnected());
                 },
@@ -146,7 +198,6 @@ avr_hal_generic::impl_pwm! {
         type Duty: u8,
         timer: crate::pac::TC2,
         init: |tim, prescaler| {
-            tim.tccr2a.modify(|_, w| w.wgm2().pwm_fast());
             tim.tccr2b.modify(|_, w| match prescaler {
                 Prescaler::Direct => w.cs2().direct(),
                 Prescaler::Prescale8 => w.cs2().prescale_8(),
@@ -155,19 +206,48 @@ avr_hal_generic::impl_pwm! {
                 Prescaler::Prescale1024 => w.cs2().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            tim.tccr2a.modify(|_, w| match mode {
+                WaveformMode::Fast => w.wgm2().pwm_fast(),
+                // Phase and frequency correct PWM is not available on 8-bit timers
+                WaveformMode::PhaseCorrect | WaveformMode::PhaseFrequencyCorrect => {
+                    w.wgm2().pwm_phase()
+                }
+            });
+            tim.tccr2b.modify(|_, w| w.wgm22().bit(variable_top));
+        },
+        top: |tim| {
+            if tim.tccr2b.read().wgm22().bit_is_set() {
+                tim.ocr2a.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.ocr2a.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             portb::PB3: {
                 ocr: ocr2a,
-                into_pwm: |tim| if enable {
-                    tim.tccr2a.modify(|_, w| w.com2a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr2a.modify(|_, w| if inverted {
+                        w.com2a().match_set()
+                    } else {
+                        w.com2a().match_clear()
+                    });
                 } else {
                     tim.tccr2a.modify(|_, w| w.com2a().disconnected());
                 },
+                holds_top: true,
             },
             portd::PD3: {
                 ocr: ocr2b,
-                into_pwm: |tim| if enable {
-                    tim.tccr2a.modify(|_, w| w.com2b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr2a.modify(|_, w| if inverted {
+                        w.com2b().match_set()
+                    } else {
+                        w.com2b().match_clear()
+                    });
                 } else {
                     tim.tccr2a.modify(|_, w| w.com2b().disconnected());
                 },
@@ -0,0 +1,45 @@
+//! Real-time clock on `TC2` with a 32.768 kHz crystal
+//!
+//! See [`avr_hal_generic::rtc`] for details.  The overflow interrupt `TIMER2_OVF` must call
+//! [`Rtc::on_interrupt()`].
+
+pub use avr_hal_generic::rtc::*;
+
+/// `ASSR` update busy flags for `TCNT2`, `OCR2A`, `OCR2B`, `TCCR2A` and `TCCR2B`
+const ASSR_BUSY: u8 = 0b0001_1111;
+
+avr_hal_generic::impl_rtc! {
+    /// Real-time clock using `TC2` in asynchronous mode
+    pub struct Rtc {
+        timer: crate::pac::TC2,
+        start: |tim| {
+            tim.timsk2.reset();
+            // AS2 clocks the timer from the crystal on TOSC1/TOSC2
+            tim.assr.write(|w| unsafe { w.bits(0b0010_0000) });
+            tim.tcnt2.reset();
+            tim.tccr2a.reset();
+            // Prescaler 128, the 8-bit counter overflows once per second
+            tim.tccr2b.write(|w| unsafe { w.bits(0b101) });
+            // The registers are only transferred with the next cycles of the crystal
+            while tim.assr.read().bits() & ASSR_BUSY != 0 {}
+            tim.tifr2.write(|w| unsafe { w.bits(0b111) });
+            tim.timsk2.write(|w| w.toie2().set_bit());
+        },
+        stop: |tim| {
+            tim.timsk2.reset();
+            tim.tccr2b.reset();
+            while tim.assr.read().bits() & ASSR_BUSY != 0 {}
+        },
+        sync: |tim| {
+            // Rewrite TCCR2A and wait for the update, which takes one cycle of the crystal
+            tim.tccr2a.reset();
+            while tim.assr.read().bits() & ASSR_BUSY != 0 {}
+        },
+        counter: |tim| {
+            tim.tcnt2.read().bits()
+        },
+        is_pending: |tim| {
+            tim.tifr2.read().tov2().bit_is_set()
+        },
+    }
+}
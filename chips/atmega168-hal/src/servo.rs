@@ -0,0 +1,38 @@
+//! Servo control on the 16-bit timers
+//!
+//! See [`avr_hal_generic::servo`] for details.
+//!
+//! | Driver | Timer | Pins |
+//! | --- | --- | --- |
+//! | `Servo1` | `TC1` | `PB1`, `PB2` |
+//! | `MultiServo1` | `TC1` | Any output pin |
+
+use crate::port::portb;
+pub use avr_hal_generic::servo::*;
+
+avr_hal_generic::impl_servo! {
+    /// Servo driver on `TC1` (pins `PB1`, `PB2`)
+    pub struct Servo1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pins: {
+            portb::PB1: {
+                channel: a,
+                method: into_servo,
+            },
+            portb::PB2: {
+                channel: b,
+                method: into_servo,
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_multi_servo! {
+    /// Software-multiplexed servo driver on `TC1` for up to 12 servos
+    pub struct MultiServo1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
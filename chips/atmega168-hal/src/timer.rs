@@ -0,0 +1,73 @@
+//! Timer/Counter peripherals as count-down timers
+//!
+//! Each timer implements `embedded_hal::timer::CountDown` and `embedded_hal::timer::Periodic`.
+//! The prescaler and TOP value are chosen automatically for the requested duration.
+//!
+//! # Example
+//! ```
+//! let mut timer1 = Timer1::<MHz16>::new(dp.TC1);
+//!
+//! timer1.start(100.ms());
+//! loop {
+//!     nb::block!(timer1.wait()).void_unwrap();
+//!     led.toggle().void_unwrap();
+//! }
+//! ```
+//!
+//! | Timer | Counter | Longest period at 16 MHz |
+//! | --- | --- | --- |
+//! | `Timer0` | 8-bit | 16.4 ms |
+//! | `Timer1` | 16-bit | 4.19 s |
+//! | `Timer2` | 8-bit | 16.4 ms |
+
+pub use avr_hal_generic::timer::*;
+
+avr_hal_generic::impl_timer_traditional! {
+    /// Use `TC0` as a count-down timer (8-bit)
+    pub struct Timer0 {
+        peripheral: crate::pac::TC0,
+        register_suffix: 0,
+        counter: u8,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+    }
+}
+
+avr_hal_generic::impl_timer_traditional! {
+    /// Use `TC1` as a count-down timer (16-bit)
+    pub struct Timer1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+    }
+}
+
+avr_hal_generic::impl_timer_traditional! {
+    /// Use `TC2` as a count-down timer (8-bit)
+    pub struct Timer2 {
+        peripheral: crate::pac::TC2,
+        register_suffix: 2,
+        counter: u8,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            32 => 0b011,
+            64 => 0b100,
+            128 => 0b101,
+            256 => 0b110,
+            1024 => 0b111,
+        },
+    }
+}
@@ -16,6 +16,23 @@ pub enum AdcMux {
     Adc6 = 0b000110,
     Adc7 = 0b000111,
 
+    Adc1Minus0Gain10 = 0b001001,
+    Adc1Minus0Gain200 = 0b001011,
+    Adc3Minus2Gain10 = 0b001101,
+    Adc3Minus2Gain200 = 0b001111,
+    Adc0Minus1Gain1 = 0b010000,
+    Adc2Minus1Gain1 = 0b010010,
+    Adc3Minus1Gain1 = 0b010011,
+    Adc4Minus1Gain1 = 0b010100,
+    Adc5Minus1Gain1 = 0b010101,
+    Adc6Minus1Gain1 = 0b010110,
+    Adc7Minus1Gain1 = 0b010111,
+    Adc0Minus2Gain1 = 0b011000,
+    Adc1Minus2Gain1 = 0b011001,
+    Adc3Minus2Gain1 = 0b011011,
+    Adc4Minus2Gain1 = 0b011100,
+    Adc5Minus2Gain1 = 0b011101,
+
     AdcVbg = 0b011110,
     AdcGnd = 0b011111,
 
@@ -27,11 +44,30 @@ pub enum AdcMux {
     Adc13 = 0b100101,
     Adc14 = 0b100110,
     Adc15 = 0b100111,
+
+    Adc9Minus8Gain10 = 0b101001,
+    Adc9Minus8Gain200 = 0b101011,
+    Adc11Minus10Gain10 = 0b101101,
+    Adc11Minus10Gain200 = 0b101111,
+    Adc8Minus9Gain1 = 0b110000,
+    Adc10Minus9Gain1 = 0b110010,
+    Adc11Minus9Gain1 = 0b110011,
+    Adc12Minus9Gain1 = 0b110100,
+    Adc13Minus9Gain1 = 0b110101,
+    Adc14Minus9Gain1 = 0b110110,
+    Adc15Minus9Gain1 = 0b110111,
+    Adc8Minus10Gain1 = 0b111000,
+    Adc9Minus10Gain1 = 0b111001,
+    Adc11Minus10Gain1 = 0b111011,
+    Adc12Minus10Gain1 = 0b111100,
+    Adc13Minus10Gain1 = 0b111101,
 }
 
 avr_hal_generic::impl_adc! {
     pub struct Adc {
         type ChannelID = AdcMux;
+        type ReferenceVoltage = avr_hal_generic::adc::ReferenceVoltage;
+        type TriggerSource = avr_hal_generic::adc::TriggerSource;
         peripheral: crate::pac::ADC,
         set_mux: |peripheral, id| {
             let id = id as u8;
@@ -39,6 +75,13 @@ avr_hal_generic::impl_adc! {
             // n.b. the high bit of ADMUX[MUX] is in the ADCSRB register
             peripheral.adcsrb.modify(|_, w| w.mux5().bit(id & 0x20 != 0));
         },
+        // The bandgap reference and all differential channels need to settle
+        settling: |id| {
+            let mux = id as u8 & 0x1F;
+            mux >= 0b01000 && id != AdcMux::AdcGnd
+        },
+        bandgap: AdcMux::AdcVbg,
+        sleep_control: (crate::pac::CPU, smcr),
         pins: {
             pf0: (PF0, AdcMux::Adc0, didr0::adc0d),
             pf1: (PF1, AdcMux::Adc1, didr0::adc1d),
@@ -85,3 +128,54 @@ pub mod channel {
         }
     }
 }
+
+// Differential channels
+//
+// | Positive | Negative | Gain |
+// | --- | --- | --- |
+// | `PF1` | `PF0` | 10x, 200x |
+// | `PF3` | `PF2` | 1x, 10x, 200x |
+// | `PF0`, `PF2`-`PF7` | `PF1` | 1x |
+// | `PF0`, `PF1`, `PF4`, `PF5` | `PF2` | 1x |
+// | `PK1` | `PK0` | 10x, 200x |
+// | `PK3` | `PK2` | 1x, 10x, 200x |
+// | `PK0`, `PK2`-`PK7` | `PK1` | 1x |
+// | `PK0`, `PK1`, `PK4`, `PK5` | `PK2` | 1x |
+avr_hal_generic::impl_adc_differential! {
+    adc: Adc,
+    channel_id: AdcMux,
+    pairs: {
+        PF1 - PF0, X10: AdcMux::Adc1Minus0Gain10,
+        PF1 - PF0, X200: AdcMux::Adc1Minus0Gain200,
+        PF3 - PF2, X10: AdcMux::Adc3Minus2Gain10,
+        PF3 - PF2, X200: AdcMux::Adc3Minus2Gain200,
+        PF0 - PF1, X1: AdcMux::Adc0Minus1Gain1,
+        PF2 - PF1, X1: AdcMux::Adc2Minus1Gain1,
+        PF3 - PF1, X1: AdcMux::Adc3Minus1Gain1,
+        PF4 - PF1, X1: AdcMux::Adc4Minus1Gain1,
+        PF5 - PF1, X1: AdcMux::Adc5Minus1Gain1,
+        PF6 - PF1, X1: AdcMux::Adc6Minus1Gain1,
+        PF7 - PF1, X1: AdcMux::Adc7Minus1Gain1,
+        PF0 - PF2, X1: AdcMux::Adc0Minus2Gain1,
+        PF1 - PF2, X1: AdcMux::Adc1Minus2Gain1,
+        PF3 - PF2, X1: AdcMux::Adc3Minus2Gain1,
+        PF4 - PF2, X1: AdcMux::Adc4Minus2Gain1,
+        PF5 - PF2, X1: AdcMux::Adc5Minus2Gain1,
+        PK1 - PK0, X10: AdcMux::Adc9Minus8Gain10,
+        PK1 - PK0, X200: AdcMux::Adc9Minus8Gain200,
+        PK3 - PK2, X10: AdcMux::Adc11Minus10Gain10,
+        PK3 - PK2, X200: AdcMux::Adc11Minus10Gain200,
+        PK0 - PK1, X1: AdcMux::Adc8Minus9Gain1,
+        PK2 - PK1, X1: AdcMux::Adc10Minus9Gain1,
+        PK3 - PK1, X1: AdcMux::Adc11Minus9Gain1,
+        PK4 - PK1, X1: AdcMux::Adc12Minus9Gain1,
+        PK5 - PK1, X1: AdcMux::Adc13Minus9Gain1,
+        PK6 - PK1, X1: AdcMux::Adc14Minus9Gain1,
+        PK7 - PK1, X1: AdcMux::Adc15Minus9Gain1,
+        PK0 - PK2, X1: AdcMux::Adc8Minus10Gain1,
+        PK1 - PK2, X1: AdcMux::Adc9Minus10Gain1,
+        PK3 - PK2, X1: AdcMux::Adc11Minus10Gain1,
+        PK4 - PK2, X1: AdcMux::Adc12Minus10Gain1,
+        PK5 - PK2, X1: AdcMux::Adc13Minus10Gain1,
+    }
+}
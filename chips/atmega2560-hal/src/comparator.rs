@@ -0,0 +1,10 @@
+use crate::port::porte::{PE2, PE3};
+
+avr_hal_generic::impl_analog_comparator! {
+    pub struct AnalogComparator {
+        peripheral: crate::pac::AC,
+        ain0: (PE2, didr1::ain0d),
+        ain1: (PE3, didr1::ain1d),
+        input_capture: true,
+    }
+}
@@ -56,7 +56,6 @@ avr_hal_generic::impl_pwm! {
         type Duty: u8,
         timer: crate::pac::TC0,
         init: |tim, prescaler| {
-            tim.tccr0a.modify(|_, w| w.wgm0().pwm_fast());
             tim.tccr0b.modify(|_, w| match prescaler {
                 Prescaler::Direct => w.cs0().direct(),
                 Prescaler::Prescale8 => w.cs0().prescale_8(),
@@ -65,19 +64,48 @@ avr_hal_generic::impl_pwm! {
                 Prescaler::Prescale1024 => w.cs0().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            tim.tccr0a.modify(|_, w| match mode {
+                WaveformMode::Fast => w.wgm0().pwm_fast(),
+                // Phase and frequency correct PWM is not available on 8-bit timers
+                WaveformMode::PhaseCorrect | WaveformMode::PhaseFrequencyCorrect => {
+                    w.wgm0().pwm_phase()
+                }
+            });
+            tim.tccr0b.modify(|_, w| w.wgm02().bit(variable_top));
+        },
+        top: |tim| {
+            if tim.tccr0b.read().wgm02().bit_is_set() {
+                tim.ocr0a.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.ocr0a.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             portb::PB7: {
                 ocr: ocr0a,
-                into_pwm: |tim| if enable {
-                    tim.tccr0a.modify(|_, w| w.com0a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr0a.modify(|_, w| if inverted {
+                        w.com0a().match_set()
+                    } else {
+                        w.com0a().match_clear()
+                    });
                 } else {
                     tim.tccr0a.modify(|_, w| w.com0a().disconnected());
                 },
+                holds_top: true,
             },
             portg::PG5: {
                 ocr: ocr0b,
-                into_pwm: |tim| if enable {
-                    tim.tccr0a.modify(|_, w| w.com0b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr0a.modify(|_, w| if inverted {
+                        w.com0b().match_set()
+                    } else {
+                        w.com0b().match_clear()
+                    });
                 } else {
                     tim.tccr0a.modify(|_, w| w.com0b().disconnected());
                 },
@@ -107,39 +135,67 @@ avr_hal_generic::impl_pwm! {
         type Duty: u16,
         timer: crate::pac::TC1,
         init: |tim, prescaler| {
-            tim.tccr1a.modify(|_, w| w.wgm1().bits(0b01));
-            tim.tccr1b.modify(|_, w|  {
-                w.wgm1().bits(0b01);
-                match prescaler {
-                    Prescaler::Direct => w.cs1().direct(),
-                    Prescaler::Prescale8 => w.cs1().prescale_8(),
-                    Prescaler::Prescale64 => w.cs1().prescale_64(),
-                    Prescaler::Prescale256 => w.cs1().prescale_256(),
-                    Prescaler::Prescale1024 => w.cs1().prescale_1024(),
-                }
+            tim.tccr1b.modify(|_, w| match prescaler {
+                Prescaler::Direct => w.cs1().direct(),
+                Prescaler::Prescale8 => w.cs1().prescale_8(),
+                Prescaler::Prescale64 => w.cs1().prescale_64(),
+                Prescaler::Prescale256 => w.cs1().prescale_256(),
+                Prescaler::Prescale1024 => w.cs1().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            if mode == WaveformMode::PhaseFrequencyCorrect && !variable_top {
+                // This mode always takes TOP from ICR1
+                tim.icr1.write(|w| unsafe { w.bits(0xff) });
+            }
+            let wgm = mode.wgm_16bit(variable_top);
+            tim.tccr1a.modify(|_, w| w.wgm1().bits(wgm & 0b11));
+            tim.tccr1b.modify(|_, w| w.wgm1().bits(wgm >> 2));
+        },
+        top: |tim| {
+            // WGM13 selects ICR1 as TOP
+            if tim.tccr1b.read().wgm1().bits() & 0b10 != 0 {
+                tim.icr1.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.icr1.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             portb::PB5: {
                 ocr: ocr1a,
-                into_pwm: |tim| if enable {
-                    tim.tccr1a.modify(|_, w| w.com1a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr1a.modify(|_, w| if inverted {
+                        w.com1a().match_set()
+                    } else {
+                        w.com1a().match_clear()
+                    });
                 } else {
                     tim.tccr1a.modify(|_, w| w.com1a().disconnected());
                 },
             },
             portb::PB6: {
                 ocr: ocr1b,
-                into_pwm: |tim| if enable {
-                    tim.tccr1a.modify(|_, w| w.com1b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr1a.modify(|_, w| if inverted {
+                        w.com1b().match_set()
+                    } else {
+                        w.com1b().match_clear()
+                    });
                 } else {
                     tim.tccr1a.modify(|_, w| w.com1b().disconnected());
                 },
             },
             portb::PB7: {
                 ocr: ocr1c,
-                into_pwm1: |tim| if enable {
-                    tim.tccr1a.modify(|_, w| w.com1c().match_clear());
+                into_pwm1: |tim, inverted| if enable {
+                    tim.tccr1a.modify(|_, w| if inverted {
+                        w.com1c().match_set()
+                    } else {
+                        w.com1c().match_clear()
+                    });
                 } else {
                     tim.tccr1a.modify(|_, w| w.com1c().disconnected());
                 },
@@ -167,31 +223,56 @@ avr_hal_generic::impl_pwm! {
         type Duty: u8,
         timer: crate::pac::TC2,
         init: |tim, prescaler| {
-            tim.tccr2a.modify(|_, w| w.wgm2().bits(0b01));
-            tim.tccr2b.modify(|_, w| {
-                w.wgm22().clear_bit();
-                match prescaler {
-                    Prescaler::Direct => w.cs2().direct(),
-                    Prescaler::Prescale8 => w.cs2().prescale_8(),
-                    Prescaler::Prescale64 => w.cs2().prescale_64(),
-                    Prescaler::Prescale256 => w.cs2().prescale_256(),
-                    Prescaler::Prescale1024 => w.cs2().prescale_1024(),
+            tim.tccr2b.modify(|_, w| match prescaler {
+                Prescaler::Direct => w.cs2().direct(),
+                Prescaler::Prescale8 => w.cs2().prescale_8(),
+                Prescaler::Prescale64 => w.cs2().prescale_64(),
+                Prescaler::Prescale256 => w.cs2().prescale_256(),
+                Prescaler::Prescale1024 => w.cs2().prescale_1024(),
+            });
+        },
+        waveform: |tim, mode, variable_top| {
+            tim.tccr2a.modify(|_, w| match mode {
+                WaveformMode::Fast => w.wgm2().bits(0b11),
+                // Phase and frequency correct PWM is not available on 8-bit timers
+                WaveformMode::PhaseCorrect | WaveformMode::PhaseFrequencyCorrect => {
+                    w.wgm2().bits(0b01)
                 }
             });
+            tim.tccr2b.modify(|_, w| w.wgm22().bit(variable_top));
+        },
+        top: |tim| {
+            if tim.tccr2b.read().wgm22().bit_is_set() {
+                tim.ocr2a.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.ocr2a.write(|w| unsafe { w.bits(top) });
         },
         pins: {
             portb::PB4: {
                 ocr: ocr2a,
-                into_pwm: |tim| if enable {
-                    tim.tccr2a.modify(|_, w| w.com2a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr2a.modify(|_, w| if inverted {
+                        w.com2a().match_set()
+                    } else {
+                        w.com2a().match_clear()
+                    });
                 } else {
                     tim.tccr2a.modify(|_, w| w.com2a().disconnected());
                 },
+                holds_top: true,
             },
             porth::PH6: {
                 ocr: ocr2b,
-                into_pwm: |tim| if enable {
-                    tim.tccr2a.modify(|_, w| w.com2b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr2a.modify(|_, w| if inverted {
+                        w.com2b().match_set()
+                    } else {
+                        w.com2b().match_clear()
+                    });
                 } else {
                     tim.tccr2a.modify(|_, w| w.com2b().disconnected());
                 },
@@ -219,39 +300,67 @@ avr_hal_generic::impl_pwm! {
         type Duty: u16,
         timer: crate::pac::TC3,
         init: |tim, prescaler| {
-            tim.tccr3a.modify(|_, w| w.wgm3().bits(0b01));
-            tim.tccr3b.modify(|_, w| {
-                w.wgm3().bits(0b01);
-                match prescaler {
-                    Prescaler::Direct => w.cs3().direct(),
-                    Prescaler::Prescale8 => w.cs3().prescale_8(),
-                    Prescaler::Prescale64 => w.cs3().prescale_64(),
-                    Prescaler::Prescale256 => w.cs3().prescale_256(),
-                    Prescaler::Prescale1024 => w.cs3().prescale_1024(),
-                }
+            tim.tccr3b.modify(|_, w| match prescaler {
+                Prescaler::Direct => w.cs3().direct(),
+                Prescaler::Prescale8 => w.cs3().prescale_8(),
+                Prescaler::Prescale64 => w.cs3().prescale_64(),
+                Prescaler::Prescale256 => w.cs3().prescale_256(),
+                Prescaler::Prescale1024 => w.cs3().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            if mode == WaveformMode::PhaseFrequencyCorrect && !variable_top {
+                // This mode always takes TOP from ICR3
+                tim.icr3.write(|w| unsafe { w.bits(0xff) });
+            }
+            let wgm = mode.wgm_16bit(variable_top);
+            tim.tccr3a.modify(|_, w| w.wgm3().bits(wgm & 0b11));
+            tim.tccr3b.modify(|_, w| w.wgm3().bits(wgm >> 2));
+        },
+        top: |tim| {
+            // WGM33 selects ICR3 as TOP
+            if tim.tccr3b.read().wgm3().bits() & 0b10 != 0 {
+                tim.icr3.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.icr3.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             porte::PE3: {
                 ocr: ocr3a,
-                into_pwm: |tim| if enable {
-                    tim.tccr3a.modify(|_, w| w.com3a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr3a.modify(|_, w| if inverted {
+                        w.com3a().match_set()
+                    } else {
+                        w.com3a().match_clear()
+                    });
                 } else {
                     tim.tccr3a.modify(|_, w| w.com3a().disconnected());
                 },
             },
             porte::PE4: {
                 ocr: ocr3b,
-                into_pwm: |tim| if enable {
-                    tim.tccr3a.modify(|_, w| w.com3b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr3a.modify(|_, w| if inverted {
+                        w.com3b().match_set()
+                    } else {
+                        w.com3b().match_clear()
+                    });
                 } else {
                     tim.tccr3a.modify(|_, w| w.com3b().disconnected());
                 },
             },
             porte::PE5: {
                 ocr: ocr3c,
-                into_pwm: |tim| if enable {
-                    tim.tccr3a.modify(|_, w| w.com3c().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr3a.modify(|_, w| if inverted {
+                        w.com3c().match_set()
+                    } else {
+                        w.com3c().match_clear()
+                    });
                 } else {
                     tim.tccr3a.modify(|_, w| w.com3c().disconnected());
                 },
@@ -279,39 +388,67 @@ avr_hal_generic::impl_pwm! {
         type Duty: u16,
         timer: crate::pac::TC4,
         init: |tim, prescaler| {
-            tim.tccr4a.modify(|_, w| w.wgm4().bits(0b01));
-            tim.tccr4b.modify(|_, w| {
-                w.wgm4().bits(0b01);
-                match prescaler {
-                    Prescaler::Direct => w.cs4().direct(),
-                    Prescaler::Prescale8 => w.cs4().prescale_8(),
-                    Prescaler::Prescale64 => w.cs4().prescale_64(),
-                    Prescaler::Prescale256 => w.cs4().prescale_256(),
-                    Prescaler::Prescale1024 => w.cs4().prescale_1024(),
-                }
+            tim.tccr4b.modify(|_, w| match prescaler {
+                Prescaler::Direct => w.cs4().direct(),
+                Prescaler::Prescale8 => w.cs4().prescale_8(),
+                Prescaler::Prescale64 => w.cs4().prescale_64(),
+                Prescaler::Prescale256 => w.cs4().prescale_256(),
+                Prescaler::Prescale1024 => w.cs4().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            if mode == WaveformMode::PhaseFrequencyCorrect && !variable_top {
+                // This mode always takes TOP from ICR4
+                tim.icr4.write(|w| unsafe { w.bits(0xff) });
+            }
+            let wgm = mode.wgm_16bit(variable_top);
+            tim.tccr4a.modify(|_, w| w.wgm4().bits(wgm & 0b11));
+            tim.tccr4b.modify(|_, w| w.wgm4().bits(wgm >> 2));
+        },
+        top: |tim| {
+            // WGM43 selects ICR4 as TOP
+            if tim.tccr4b.read().wgm4().bits() & 0b10 != 0 {
+                tim.icr4.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.icr4.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             porth::PH3: {
                 ocr: ocr4a,
-                into_pwm: |tim| if enable {
-                    tim.tccr4a.modify(|_, w| w.com4a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr4a.modify(|_, w| if inverted {
+                        w.com4a().match_set()
+                    } else {
+                        w.com4a().match_clear()
+                    });
                 } else {
                     tim.tccr4a.modify(|_, w| w.com4a().disconnected());
                 },
             },
             porth::PH4: {
                 ocr: ocr4b,
-                into_pwm: |tim| if enable {
-                    tim.tccr4a.modify(|_, w| w.com4b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr4a.modify(|_, w| if inverted {
+                        w.com4b().match_set()
+                    } else {
+                        w.com4b().match_clear()
+                    });
                 } else {
                     tim.tccr4a.modify(|_, w| w.com4b().disconnected());
                 },
             },
             porth::PH5: {
                 ocr: ocr4c,
-                into_pwm: |tim| if enable {
-                    tim.tccr4a.modify(|_, w| w.com4c().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr4a.modify(|_, w| if inverted {
+                        w.com4c().match_set()
+                    } else {
+                        w.com4c().match_clear()
+                    });
                 } else {
                     tim.tccr4a.modify(|_, w| w.com4c().disconnected());
                 },
@@ -339,39 +476,67 @@ avr_hal_generic::impl_pwm! {
         type Duty: u16,
         timer: crate::pac::TC5,
         init: |tim, prescaler| {
-            tim.tccr5a.modify(|_, w| w.wgm5().bits(0b01));
-            tim.tccr5b.modify(|_, w| {
-                w.wgm5().bits(0b01);
-                match prescaler {
-                    Prescaler::Direct => w.cs5().direct(),
-                    Prescaler::Prescale8 => w.cs5().prescale_8(),
-                    Prescaler::Prescale64 => w.cs5().prescale_64(),
-                    Prescaler::Prescale256 => w.cs5().prescale_256(),
-                    Prescaler::Prescale1024 => w.cs5().prescale_1024(),
-                }
+            tim.tccr5b.modify(|_, w| match prescaler {
+                Prescaler::Direct => w.cs5().direct(),
+                Prescaler::Prescale8 => w.cs5().prescale_8(),
+                Prescaler::Prescale64 => w.cs5().prescale_64(),
+                Prescaler::Prescale256 => w.cs5().prescale_256(),
+                Prescaler::Prescale1024 => w.cs5().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            if mode == WaveformMode::PhaseFrequencyCorrect && !variable_top {
+                // This mode always takes TOP from ICR5
+                tim.icr5.write(|w| unsafe { w.bits(0xff) });
+            }
+            let wgm = mode.wgm_16bit(variable_top);
+            tim.tccr5a.modify(|_, w| w.wgm5().bits(wgm & 0b11));
+            tim.tccr5b.modify(|_, w| w.wgm5().bits(wgm >> 2));
+        },
+        top: |tim| {
+            // WGM53 selects ICR5 as TOP
+            if tim.tccr5b.read().wgm5().bits() & 0b10 != 0 {
+                tim.icr5.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.icr5.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             portl::PL3: {
                 ocr: ocr5a,
-                into_pwm: |tim| if enable {
-                    tim.tccr5a.modify(|_, w| w.com5a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr5a.modify(|_, w| if inverted {
+                        w.com5a().match_set()
+                    } else {
+                        w.com5a().match_clear()
+                    });
                 } else {
                     tim.tccr5a.modify(|_, w| w.com5a().disconnected());
                 },
             },
             portl::PL4: {
                 ocr: ocr5b,
-                into_pwm: |tim| if enable {
-                    tim.tccr5a.modify(|_, w| w.com5b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr5a.modify(|_, w| if inverted {
+                        w.com5b().match_set()
+                    } else {
+                        w.com5b().match_clear()
+                    });
                 } else {
                     tim.tccr5a.modify(|_, w| w.com5b().disconnected());
                 },
             },
             portl::PL5: {
                 ocr: ocr5c,
-                into_pwm: |tim| if enable {
-                    tim.tccr5a.modify(|_, w| w.com5c().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr5a.modify(|_, w| if inverted {
+                        w.com5c().match_set()
+                    } else {
+                        w.com5c().match_clear()
+                    });
                 } else {
                     tim.tccr5a.modify(|_, w| w.com5c().disconnected());
                 },
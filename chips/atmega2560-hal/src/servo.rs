@@ -0,0 +1,141 @@
+//! Servo control on the 16-bit timers
+//!
+//! See [`avr_hal_generic::servo`] for details.
+//!
+//! | Driver | Timer | Pins |
+//! | --- | --- | --- |
+//! | `Servo1` | `TC1` | `PB5`, `PB6`, `PB7` |
+//! | `Servo3` | `TC3` | `PE3`, `PE4`, `PE5` |
+//! | `Servo4` | `TC4` | `PH3`, `PH4`, `PH5` |
+//! | `Servo5` | `TC5` | `PL3`, `PL4`, `PL5` |
+//! | `MultiServo1` | `TC1` | Any output pin |
+//! | `MultiServo3` | `TC3` | Any output pin |
+//! | `MultiServo4` | `TC4` | Any output pin |
+//! | `MultiServo5` | `TC5` | Any output pin |
+
+use crate::port::{portb, porte, porth, portl};
+pub use avr_hal_generic::servo::*;
+
+avr_hal_generic::impl_servo! {
+    /// Servo driver on `TC1` (pins `PB5`, `PB6`, `PB7`)
+    pub struct Servo1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pins: {
+            portb::PB5: {
+                channel: a,
+                method: into_servo,
+            },
+            portb::PB6: {
+                channel: b,
+                method: into_servo,
+            },
+            portb::PB7: {
+                channel: c,
+                method: into_servo,
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_multi_servo! {
+    /// Software-multiplexed servo driver on `TC1` for up to 12 servos
+    pub struct MultiServo1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_servo! {
+    /// Servo driver on `TC3` (pins `PE3`, `PE4`, `PE5`)
+    pub struct Servo3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        pins: {
+            porte::PE3: {
+                channel: a,
+                method: into_servo,
+            },
+            porte::PE4: {
+                channel: b,
+                method: into_servo,
+            },
+            porte::PE5: {
+                channel: c,
+                method: into_servo,
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_multi_servo! {
+    /// Software-multiplexed servo driver on `TC3` for up to 12 servos
+    pub struct MultiServo3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_servo! {
+    /// Servo driver on `TC4` (pins `PH3`, `PH4`, `PH5`)
+    pub struct Servo4 {
+        peripheral: crate::pac::TC4,
+        register_suffix: 4,
+        pins: {
+            porth::PH3: {
+                channel: a,
+                method: into_servo,
+            },
+            porth::PH4: {
+                channel: b,
+                method: into_servo,
+            },
+            porth::PH5: {
+                channel: c,
+                method: into_servo,
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_multi_servo! {
+    /// Software-multiplexed servo driver on `TC4` for up to 12 servos
+    pub struct MultiServo4 {
+        peripheral: crate::pac::TC4,
+        register_suffix: 4,
+        pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_servo! {
+    /// Servo driver on `TC5` (pins `PL3`, `PL4`, `PL5`)
+    pub struct Servo5 {
+        peripheral: crate::pac::TC5,
+        register_suffix: 5,
+        pins: {
+            portl::PL3: {
+                channel: a,
+                method: into_servo,
+            },
+            portl::PL4: {
+                channel: b,
+                method: into_servo,
+            },
+            portl::PL5: {
+                channel: c,
+                method: into_servo,
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_multi_servo! {
+    /// Software-multiplexed servo driver on `TC5` for up to 12 servos
+    pub struct MultiServo5 {
+        peripheral: crate::pac::TC5,
+        register_suffix: 5,
+        pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
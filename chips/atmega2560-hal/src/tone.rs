@@ -0,0 +1,133 @@
+//! Tone and square-wave generation with the timers
+//!
+//! See [`avr_hal_generic::tone`] for details.
+//!
+//! # Example
+//! ```
+//! let mut portb = dp.PORTB.split();
+//! let mut tone0 = Tone0::<MHz16>::new(dp.TC0, portb.pb7.into_output(&mut portb.ddr));
+//!
+//! tone0.play(1000.hz());
+//! ```
+//!
+//! | Driver | Timer | Hardware Pin |
+//! | --- | --- | --- |
+//! | `Tone0` / `SoftTone0` | `TC0` | `PB7` (`OC0A`) |
+//! | `Tone1` / `SoftTone1` | `TC1` | `PB5` (`OC1A`) |
+//! | `Tone2` / `SoftTone2` | `TC2` | `PB4` (`OC2A`) |
+//! | `Tone3` / `SoftTone3` | `TC3` | `PE3` (`OC3A`) |
+//! | `Tone4` / `SoftTone4` | `TC4` | `PH3` (`OC4A`) |
+//! | `Tone5` / `SoftTone5` | `TC5` | `PL3` (`OC5A`) |
+
+use crate::port::{portb, porte, porth, portl};
+pub use avr_hal_generic::tone::*;
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC0` (8-bit), toggling pin `PB7` in hardware
+    pub struct Tone0 {
+        peripheral: crate::pac::TC0,
+        register_suffix: 0,
+        counter: u8,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portb::PB7,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC1` (16-bit), toggling pin `PB5` in hardware
+    pub struct Tone1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portb::PB5,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC2` (8-bit), toggling pin `PB4` in hardware
+    pub struct Tone2 {
+        peripheral: crate::pac::TC2,
+        register_suffix: 2,
+        counter: u8,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            32 => 0b011,
+            64 => 0b100,
+            128 => 0b101,
+            256 => 0b110,
+            1024 => 0b111,
+        },
+        pin: portb::PB4,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC3` (16-bit), toggling pin `PE3` in hardware
+    pub struct Tone3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: porte::PE3,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC4` (16-bit), toggling pin `PH3` in hardware
+    pub struct Tone4 {
+        peripheral: crate::pac::TC4,
+        register_suffix: 4,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: porth::PH3,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC5` (16-bit), toggling pin `PL3` in hardware
+    pub struct Tone5 {
+        peripheral: crate::pac::TC5,
+        register_suffix: 5,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portl::PL3,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
@@ -31,8 +31,24 @@ pub mod port;
 #[cfg(feature = "device-selected")]
 pub mod adc;
 #[cfg(feature = "device-selected")]
+pub mod capture;
+#[cfg(feature = "device-selected")]
+pub mod comparator;
+#[cfg(feature = "device-selected")]
+pub mod exint;
+#[cfg(feature = "device-selected")]
 pub mod pwm;
 #[cfg(feature = "device-selected")]
+pub mod rtc;
+#[cfg(feature = "device-selected")]
+pub mod servo;
+#[cfg(feature = "device-selected")]
+pub mod systime;
+#[cfg(feature = "device-selected")]
+pub mod timer;
+#[cfg(feature = "device-selected")]
+pub mod tone;
+#[cfg(feature = "device-selected")]
 pub mod wdt;
 
 #[cfg(feature = "device-selected")]
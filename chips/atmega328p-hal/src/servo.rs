@@ -0,0 +1,106 @@
+//! Servo control on the 16-bit timers
+//!
+//! See [`avr_hal_generic::servo`] for details.
+//!
+//! | Driver | Timer | Pins |
+//! | --- | --- | --- |
+//! | `Servo1` | `TC1` | `PB1`, `PB2` |
+//! | `Servo3` | `TC3` | `PD0`, `PD2` (ATmega328PB only) |
+//! | `Servo4` | `TC4` | `PD1`, `PD2` (ATmega328PB only) |
+//! | `MultiServo1` | `TC1` | Any output pin |
+//! | `MultiServo3` | `TC3` | Any output pin (ATmega328PB only) |
+//! | `MultiServo4` | `TC4` | Any output pin (ATmega328PB only) |
+//!
+//! **Note**: For `PD2` with `Servo3` the method is called `into_servo3()`!
+//!
+//! **Note**: For `PD2` with `Servo4` the method is called `into_servo4()`!
+
+use crate::port::portb;
+#[cfg(feature = "atmega328pb")]
+use crate::port::portd;
+pub use avr_hal_generic::servo::*;
+
+avr_hal_generic::impl_servo! {
+    /// Servo driver on `TC1` (pins `PB1`, `PB2`)
+    pub struct Servo1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pins: {
+            portb::PB1: {
+                channel: a,
+                method: into_servo,
+            },
+            portb::PB2: {
+                channel: b,
+                method: into_servo,
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_multi_servo! {
+    /// Software-multiplexed servo driver on `TC1` for up to 12 servos
+    pub struct MultiServo1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+#[cfg(feature = "atmega328pb")]
+avr_hal_generic::impl_servo! {
+    /// Servo driver on `TC3` (pins `PD0`, `PD2`)
+    pub struct Servo3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        pins: {
+            portd::PD0: {
+                channel: a,
+                method: into_servo,
+            },
+            portd::PD2: {
+                channel: b,
+                method: into_servo3,
+            },
+        },
+    }
+}
+
+#[cfg(feature = "atmega328pb")]
+avr_hal_generic::impl_multi_servo! {
+    /// Software-multiplexed servo driver on `TC3` for up to 12 servos
+    pub struct MultiServo3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+#[cfg(feature = "atmega328pb")]
+avr_hal_generic::impl_servo! {
+    /// Servo driver on `TC4` (pins `PD1`, `PD2`)
+    pub struct Servo4 {
+        peripheral: crate::pac::TC4,
+        register_suffix: 4,
+        pins: {
+            portd::PD1: {
+                channel: a,
+                method: into_servo,
+            },
+            portd::PD2: {
+                channel: b,
+                method: into_servo4,
+            },
+        },
+    }
+}
+
+#[cfg(feature = "atmega328pb")]
+avr_hal_generic::impl_multi_servo! {
+    /// Software-multiplexed servo driver on `TC4` for up to 12 servos
+    pub struct MultiServo4 {
+        peripheral: crate::pac::TC4,
+        register_suffix: 4,
+        pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
@@ -0,0 +1,116 @@
+//! Tone and square-wave generation with the timers
+//!
+//! See [`avr_hal_generic::tone`] for details.
+//!
+//! # Example
+//! ```
+//! let mut portd = dp.PORTD.split();
+//! let mut tone0 = Tone0::<MHz16>::new(dp.TC0, portd.pd6.into_output(&mut portd.ddr));
+//!
+//! tone0.play(1000.hz());
+//! ```
+//!
+//! | Driver | Timer | Hardware Pin |
+//! | --- | --- | --- |
+//! | `Tone0` / `SoftTone0` | `TC0` | `PD6` (`OC0A`) |
+//! | `Tone1` / `SoftTone1` | `TC1` | `PB1` (`OC1A`) |
+//! | `Tone2` / `SoftTone2` | `TC2` | `PB3` (`OC2A`) |
+//! | `Tone3` / `SoftTone3` | `TC3` | `PD0` (`OC3A`) (ATmega328PB only) |
+//! | `Tone4` / `SoftTone4` | `TC4` | `PD1` (`OC4A`) (ATmega328PB only) |
+
+use crate::port::{portb, portd};
+pub use avr_hal_generic::tone::*;
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC0` (8-bit), toggling pin `PD6` in hardware
+    pub struct Tone0 {
+        peripheral: crate::pac::TC0,
+        register_suffix: 0,
+        counter: u8,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portd::PD6,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC1` (16-bit), toggling pin `PB1` in hardware
+    pub struct Tone1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portb::PB1,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC2` (8-bit), toggling pin `PB3` in hardware
+    pub struct Tone2 {
+        peripheral: crate::pac::TC2,
+        register_suffix: 2,
+        counter: u8,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            32 => 0b011,
+            64 => 0b100,
+            128 => 0b101,
+            256 => 0b110,
+            1024 => 0b111,
+        },
+        pin: portb::PB3,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+#[cfg(feature = "atmega328pb")]
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC3` (16-bit), toggling pin `PD0` in hardware
+    pub struct Tone3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portd::PD0,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+#[cfg(feature = "atmega328pb")]
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC4` (16-bit), toggling pin `PD1` in hardware
+    pub struct Tone4 {
+        peripheral: crate::pac::TC4,
+        register_suffix: 4,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portd::PD1,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
@@ -15,6 +15,14 @@ pub enum AdcMux {
     Adc6 = 0b000110,
     Adc7 = 0b000111,
 
+    Adc1Minus0Gain10 = 0b001001,
+    Adc1Minus0Gain200 = 0b001011,
+    Adc0Minus1Gain1 = 0b010000,
+    Adc4Minus1Gain1 = 0b010100,
+    Adc5Minus1Gain1 = 0b010101,
+    Adc6Minus1Gain1 = 0b010110,
+    Adc7Minus1Gain1 = 0b010111,
+
     AdcVbg = 0b011110,
     AdcGnd = 0b011111,
 
@@ -25,18 +33,56 @@ pub enum AdcMux {
     Adc12 = 0b100100,
     Adc13 = 0b100101,
 
+    Adc1Minus0Gain40 = 0b100110,
     AdcTemp = 0b100111,
+
+    Adc4Minus0Gain10 = 0b101000,
+    Adc5Minus0Gain10 = 0b101001,
+    Adc6Minus0Gain10 = 0b101010,
+    Adc7Minus0Gain10 = 0b101011,
+    Adc4Minus1Gain10 = 0b101100,
+    Adc5Minus1Gain10 = 0b101101,
+    Adc6Minus1Gain10 = 0b101110,
+    Adc7Minus1Gain10 = 0b101111,
+    Adc4Minus0Gain40 = 0b110000,
+    Adc5Minus0Gain40 = 0b110001,
+    Adc6Minus0Gain40 = 0b110010,
+    Adc7Minus0Gain40 = 0b110011,
+    Adc4Minus1Gain40 = 0b110100,
+    Adc5Minus1Gain40 = 0b110101,
+    Adc6Minus1Gain40 = 0b110110,
+    Adc7Minus1Gain40 = 0b110111,
+    Adc4Minus0Gain200 = 0b111000,
+    Adc5Minus0Gain200 = 0b111001,
+    Adc6Minus0Gain200 = 0b111010,
+    Adc7Minus0Gain200 = 0b111011,
+    Adc4Minus1Gain200 = 0b111100,
+    Adc5Minus1Gain200 = 0b111101,
+    Adc6Minus1Gain200 = 0b111110,
+    Adc7Minus1Gain200 = 0b111111,
 }
 
 avr_hal_generic::impl_adc! {
     pub struct Adc {
         type ChannelID = AdcMux;
+        type ReferenceVoltage = avr_hal_generic::adc::ReferenceVoltage;
+        type TriggerSource = avr_hal_generic::adc::TriggerSource;
         peripheral: crate::pac::ADC,
         set_mux: |peripheral, id| {
             let id = id as u8;
             peripheral.admux.modify(|_, w| w.mux().bits(id & 0x1f));
             peripheral.adcsrb.modify(|_, w| w.mux5().bit(id & 0x20 != 0));
         },
+        // Everything except the single-ended and GND channels needs to settle
+        settling: |id| {
+            let mux = id as u8;
+            let single_ended = mux <= AdcMux::Adc7 as u8
+                || (mux >= AdcMux::Adc8 as u8 && mux <= AdcMux::Adc13 as u8);
+            !single_ended && id != AdcMux::AdcGnd
+        },
+        bandgap: AdcMux::AdcVbg,
+        temperature: AdcMux::AdcTemp,
+        sleep_control: (crate::pac::CPU, smcr),
         pins: {
             pf0: (PF0, AdcMux::Adc0, didr0::adc0d),
             pf1: (PF1, AdcMux::Adc1, didr0::adc1d),
@@ -54,6 +100,70 @@ avr_hal_generic::impl_adc! {
     }
 }
 
+impl Adc {
+    /// Enable or disable the ADC high speed mode (`ADHSM`).
+    ///
+    /// High speed mode allows higher ADC clock rates (and thus sample rates) at the cost of
+    /// increased power consumption.
+    pub fn set_high_speed(&mut self, enable: bool) {
+        self.peripheral.adcsrb.modify(|_, w| w.adhsm().bit(enable));
+    }
+}
+
+// Differential channels
+//
+// | Positive | Negative | Gain |
+// | --- | --- | --- |
+// | `PF1` | `PF0` | 10x, 40x, 200x |
+// | `PF0`, `PF4`-`PF7` | `PF1` | 1x |
+// | `PF4`-`PF7` | `PF0` | 10x, 40x, 200x |
+// | `PF4`-`PF7` | `PF1` | 10x, 40x, 200x |
+avr_hal_generic::impl_adc_differential! {
+    adc: Adc,
+    channel_id: AdcMux,
+    pairs: {
+        PF1 - PF0, X10: AdcMux::Adc1Minus0Gain10,
+        PF1 - PF0, X40: AdcMux::Adc1Minus0Gain40,
+        PF1 - PF0, X200: AdcMux::Adc1Minus0Gain200,
+        PF0 - PF1, X1: AdcMux::Adc0Minus1Gain1,
+        PF4 - PF1, X1: AdcMux::Adc4Minus1Gain1,
+        PF5 - PF1, X1: AdcMux::Adc5Minus1Gain1,
+        PF6 - PF1, X1: AdcMux::Adc6Minus1Gain1,
+        PF7 - PF1, X1: AdcMux::Adc7Minus1Gain1,
+        PF4 - PF0, X10: AdcMux::Adc4Minus0Gain10,
+        PF5 - PF0, X10: AdcMux::Adc5Minus0Gain10,
+        PF6 - PF0, X10: AdcMux::Adc6Minus0Gain10,
+        PF7 - PF0, X10: AdcMux::Adc7Minus0Gain10,
+        PF4 - PF1, X10: AdcMux::Adc4Minus1Gain10,
+        PF5 - PF1, X10: AdcMux::Adc5Minus1Gain10,
+        PF6 - PF1, X10: AdcMux::Adc6Minus1Gain10,
+        PF7 - PF1, X10: AdcMux::Adc7Minus1Gain10,
+        PF4 - PF0, X40: AdcMux::Adc4Minus0Gain40,
+        PF5 - PF0, X40: AdcMux::Adc5Minus0Gain40,
+        PF6 - PF0, X40: AdcMux::Adc6Minus0Gain40,
+        PF7 - PF0, X40: AdcMux::Adc7Minus0Gain40,
+        PF4 - PF1, X40: AdcMux::Adc4Minus1Gain40,
+        PF5 - PF1, X40: AdcMux::Adc5Minus1Gain40,
+        PF6 - PF1, X40: AdcMux::Adc6Minus1Gain40,
+        PF7 - PF1, X40: AdcMux::Adc7Minus1Gain40,
+        PF4 - PF0, X200: AdcMux::Adc4Minus0Gain200,
+        PF5 - PF0, X200: AdcMux::Adc5Minus0Gain200,
+        PF6 - PF0, X200: AdcMux::Adc6Minus0Gain200,
+        PF7 - PF0, X200: AdcMux::Adc7Minus0Gain200,
+        PF4 - PF1, X200: AdcMux::Adc4Minus1Gain200,
+        PF5 - PF1, X200: AdcMux::Adc5Minus1Gain200,
+        PF6 - PF1, X200: AdcMux::Adc6Minus1Gain200,
+        PF7 - PF1, X200: AdcMux::Adc7Minus1Gain200,
+    }
+}
+
+/// Typical temperature sensor characteristics
+///
+/// Derived from the typical sensor values given in the ATmega32U4 datasheet.  These can be off by
+/// more than ±10 °C for an individual part, so calibrate the sensor if accuracy matters.
+pub const TEMPERATURE_TYPICAL: TemperatureCalibration =
+    TemperatureCalibration::typical(322, 1216);
+
 /// Additional channels
 ///
 /// This module contains ADC channels, additional to the direct pin channels.
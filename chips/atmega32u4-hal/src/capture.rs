@@ -0,0 +1,29 @@
+//! Input capture on the 16-bit timers
+//!
+//! See [`avr_hal_generic::capture`] for details.
+//!
+//! | Driver | Timer | Pin |
+//! | --- | --- | --- |
+//! | `InputCapture1` | `TC1` | `PD4` (`ICP1`) |
+//! | `InputCapture3` | `TC3` | `PC7` (`ICP3`) |
+
+use crate::port::{portc, portd};
+pub use avr_hal_generic::capture::*;
+
+avr_hal_generic::impl_input_capture! {
+    /// Input capture on `TC1` with pin `PD4` (`ICP1`)
+    pub struct InputCapture1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pin: portd::PD4,
+    }
+}
+
+avr_hal_generic::impl_input_capture! {
+    /// Input capture on `TC3` with pin `PC7` (`ICP3`)
+    pub struct InputCapture3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        pin: portc::PC7,
+    }
+}
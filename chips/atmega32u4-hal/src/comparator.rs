@@ -0,0 +1,14 @@
+//! Analog Comparator
+//!
+//! The ATmega32U4 has no `AIN1` pin; the negative comparator input is always taken from the ADC
+//! multiplexer (see `Adc::into_comparator_input()`).
+
+use crate::port::porte::PE6;
+
+avr_hal_generic::impl_analog_comparator! {
+    pub struct AnalogComparator {
+        peripheral: crate::pac::AC,
+        ain0: (PE6, didr1::ain0d),
+        input_capture: true,
+    }
+}
@@ -0,0 +1,70 @@
+//! External Interrupts and Pin-Change Interrupts
+//!
+//! | Interrupt | Pin |
+//! | --- | --- |
+//! | `INT0` - `INT3` | `PD0` - `PD3` |
+//! | `INT6` | `PE6` |
+//!
+//! Pin-change interrupt banks:
+//!
+//! | Bank | Pins |
+//! | --- | --- |
+//! | `PinChange0` | `PB0` - `PB7` |
+
+use crate::port::{portb, portd, porte};
+
+avr_hal_generic::impl_exint! {
+    pub struct ExtInt {
+        peripheral: crate::pac::EXINT,
+        interrupts: {
+            portd::PD0: INT0 {
+                sense: crate::pac::EXINT => eicra[0],
+                enable: eimsk[0],
+                flag: eifr[0],
+            },
+            portd::PD1: INT1 {
+                sense: crate::pac::EXINT => eicra[2],
+                enable: eimsk[1],
+                flag: eifr[1],
+            },
+            portd::PD2: INT2 {
+                sense: crate::pac::EXINT => eicra[4],
+                enable: eimsk[2],
+                flag: eifr[2],
+            },
+            portd::PD3: INT3 {
+                sense: crate::pac::EXINT => eicra[6],
+                enable: eimsk[3],
+                flag: eifr[3],
+            },
+            porte::PE6: INT6 {
+                sense: crate::pac::EXINT => eicrb[4],
+                enable: eimsk[6],
+                flag: eifr[6],
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_pin_change! {
+    peripheral: crate::pac::EXINT,
+    banks: {
+        /// Pin-change interrupt `PCINT0` (pins `PCINT0` - `PCINT7` on port B)
+        pub struct PinChange0 {
+            enable: pcicr[0],
+            flag: pcifr[0],
+            mask: pcmsk0,
+            state: || { unsafe { &*crate::pac::PORTB::ptr() }.pinb.read().bits() },
+            pins: {
+                portb::PB0: 0,
+                portb::PB1: 1,
+                portb::PB2: 2,
+                portb::PB3: 3,
+                portb::PB4: 4,
+                portb::PB5: 5,
+                portb::PB6: 6,
+                portb::PB7: 7,
+            },
+        }
+    },
+}
@@ -0,0 +1,516 @@
+//! High-speed PWM with `TC4`
+//!
+//! Timer/Counter4 of the ATmega32U4 is quite different from the other timers:
+//!
+//! - It can be clocked from the PLL at up to 64 MHz instead of the IO clock.
+//! - Its counter and compare registers are 10 bits wide.  In enhanced mode, the compare registers
+//!   have an additional bit which moves the compare match by half a clock cycle.
+//! - Each of its channels can drive a pair of complementary outputs (`OC4x` and `!OC4x`) with a
+//!   configurable dead time between switching one output off and the other one on.  This is what
+//!   is needed to drive the half-bridges of a BLDC motor or an H-bridge.
+//!
+//! `pwm::Timer4Pwm` only uses this timer like the other 8-bit timers.  The driver in this module
+//! exposes all of the above.  The PWM methods are from `embedded_hal::PwmPin`, the duty cycle is a
+//! `u16`.  The maximum duty cycle is the TOP value, or `2 * TOP + 1` in enhanced mode.
+//!
+//! # Example
+//! ```
+//! let mut portb = dp.PORTB.split();
+//! let mut timer4 = Timer4::<MHz16>::new(
+//!     dp.TC4,
+//!     &dp.PLL,
+//!     Config {
+//!         dead_time: DeadTime::symmetric(8),
+//!         ..Config::default()
+//!     },
+//! );
+//!
+//! let mut half_bridge = timer4.complementary_b(
+//!     portb.pb6.into_output(&mut portb.ddr),
+//!     portb.pb5.into_output(&mut portb.ddr),
+//! );
+//!
+//! half_bridge.set_duty(half_bridge.get_max_duty() / 2);
+//! half_bridge.enable();
+//! ```
+//!
+//! | Channel | `OC4x` | `!OC4x` | Single Output | Complementary Outputs |
+//! | --- | --- | --- | --- | --- |
+//! | `A` | `PC7` | `PC6` | `.channel_a()` | `.complementary_a()` |
+//! | `B` | `PB6` | `PB5` | `.channel_b()` | `.complementary_b()` |
+//! | `D` | `PD7` | `PD6` | `.channel_d()` | `.complementary_d()` |
+
+use crate::port::{mode, portb, portc, portd};
+use avr_hal_generic::clock::Clock;
+pub use avr_hal_generic::pwm::{Prescaler, WaveformMode};
+
+// TCCR4A / TCCR4C
+const COM_MASK: u8 = 0b11;
+/// `OC4x` cleared on compare match, `!OC4x` not connected
+const COM_SINGLE: u8 = 0b10;
+/// `OC4x` cleared and `!OC4x` set on compare match
+const COM_COMPLEMENTARY: u8 = 0b01;
+
+// TCCR4B
+const DTPS4_SHIFT: u8 = 4;
+
+// TCCR4D
+const WGM4_PWM_FAST: u8 = 0b00;
+const WGM4_PWM_PHASE: u8 = 0b01;
+
+// TCCR4E
+const ENHC4: u8 = 1 << 6;
+
+// PLLCSR
+const PINDIV: u8 = 1 << 4;
+const PLLE: u8 = 1 << 1;
+const PLOCK: u8 = 1 << 0;
+
+// PLLFRQ
+const PLLUSB: u8 = 1 << 6;
+const PLLTM_SHIFT: u8 = 4;
+const PLLTM_MASK: u8 = 0b11 << PLLTM_SHIFT;
+/// PLL output frequency of 96 MHz
+const PDIV_96MHZ: u8 = 0b1010;
+
+/// Clock source for `TC4`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSource {
+    /// The IO clock, like all other timers.
+    System,
+    /// The PLL output of 96 MHz divided by 2.
+    Pll48MHz,
+    /// The PLL output of 96 MHz divided by 1.5.  This is the fastest clock the timer supports.
+    Pll64MHz,
+}
+
+impl ClockSource {
+    /// Frequency of this clock source in Hz, before the timer's prescaler.
+    pub fn frequency<CLOCK: Clock>(self) -> u32 {
+        match self {
+            ClockSource::System => CLOCK::FREQ,
+            ClockSource::Pll48MHz => 48_000_000,
+            ClockSource::Pll64MHz => 64_000_000,
+        }
+    }
+
+    fn plltm(self) -> u8 {
+        match self {
+            ClockSource::System => 0b00,
+            ClockSource::Pll48MHz => 0b11,
+            ClockSource::Pll64MHz => 0b10,
+        }
+    }
+}
+
+/// Prescaler of the dead time generator
+///
+/// The dead time generator is clocked by the timer's clock source divided by this prescaler.  The
+/// timer's own prescaler does not apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadTimePrescaler {
+    /// No prescaling
+    Direct,
+    /// Divide the clock by 2.
+    Prescale2,
+    /// Divide the clock by 4.
+    Prescale4,
+    /// Divide the clock by 8.
+    Prescale8,
+}
+
+/// Dead time between complementary outputs
+///
+/// Both delays are given in ticks of the dead time generator, from 0 to 15.  With the 64 MHz PLL
+/// clock and no dead time prescaler, one tick is 15.6 ns.  Dead time is only inserted for channels
+/// with complementary outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadTime {
+    /// Delay of the rising edge of `OC4x`
+    pub output: u8,
+    /// Delay of the rising edge of `!OC4x`
+    pub complementary: u8,
+    /// Prescaler of the dead time generator
+    pub prescaler: DeadTimePrescaler,
+}
+
+impl DeadTime {
+    /// The same delay for both outputs without prescaling.
+    pub fn symmetric(ticks: u8) -> DeadTime {
+        DeadTime {
+            output: ticks,
+            complementary: ticks,
+            prescaler: DeadTimePrescaler::Direct,
+        }
+    }
+
+    fn dt4(self) -> u8 {
+        (self.output.min(15) << 4) | self.complementary.min(15)
+    }
+}
+
+impl Default for DeadTime {
+    fn default() -> DeadTime {
+        DeadTime::symmetric(0)
+    }
+}
+
+/// Configuration of `TC4`
+///
+/// The PWM frequency is `F_clk / (Prescaler * (TOP + 1))` in fast PWM mode and
+/// `F_clk / (2 * Prescaler * TOP)` in phase and frequency correct mode, where `F_clk` is the
+/// frequency of the clock source.  The default configuration runs fast PWM at 62.5 kHz with
+/// 10-bit resolution from the 64 MHz PLL clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Clock source for the timer
+    pub clock: ClockSource,
+    /// Prescaler for the clock source
+    pub prescaler: Prescaler,
+    /// Waveform generation mode; `PhaseCorrect` is the same as `PhaseFrequencyCorrect` here
+    pub mode: WaveformMode,
+    /// TOP value of the counter, at most `0x3ff`
+    pub top: u16,
+    /// Enhanced mode, which doubles the resolution of the duty cycle
+    pub enhanced: bool,
+    /// Dead time between complementary outputs
+    pub dead_time: DeadTime,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            clock: ClockSource::Pll64MHz,
+            prescaler: Prescaler::Direct,
+            mode: WaveformMode::Fast,
+            top: 0x3ff,
+            enhanced: false,
+            dead_time: DeadTime::default(),
+        }
+    }
+}
+
+/// `TC4` in high-speed PWM mode
+pub struct Timer4<CLOCK> {
+    timer: crate::pac::TC4,
+    config: Config,
+    _clock: core::marker::PhantomData<CLOCK>,
+}
+
+impl<CLOCK: Clock> Timer4<CLOCK> {
+    /// `PINDIV` setting of the PLL input prescaler for the system clock
+    const PLL_PINDIV: u8 = {
+        // Fails to compile when evaluated for a clock other than 8 MHz or 16 MHz
+        let _ = [()][(CLOCK::FREQ != 8_000_000 && CLOCK::FREQ != 16_000_000) as usize];
+        if CLOCK::FREQ == 16_000_000 {
+            PINDIV
+        } else {
+            0
+        }
+    };
+
+    /// Initialize `TC4` with the given configuration.
+    ///
+    /// If a PLL clock source is selected and the PLL is not running yet, it is started with an
+    /// output frequency of 96 MHz and the USB clock divided by 2, which is also the configuration
+    /// needed by the USB controller.  This requires an 8 MHz or 16 MHz system clock; other clocks
+    /// fail to compile.  A PLL which is already running is assumed to have this configuration.
+    pub fn new(timer: crate::pac::TC4, pll: &crate::pac::PLL, config: Config) -> Timer4<CLOCK> {
+        // Stop the timer while the clock source is changed
+        timer.tccr4b.reset();
+
+        if config.clock != ClockSource::System {
+            Self::start_pll(pll);
+        }
+        pll.pllfrq.modify(|r, w| unsafe {
+            w.bits((r.bits() & !PLLTM_MASK) | (config.clock.plltm() << PLLTM_SHIFT))
+        });
+
+        timer.tccr4a.reset();
+        timer.tccr4c.reset();
+        timer
+            .tccr4e
+            .write(|w| unsafe { w.bits(if config.enhanced { ENHC4 } else { 0 }) });
+        timer.tcnt4.reset();
+
+        let mut t = Timer4 {
+            timer,
+            config,
+            _clock: core::marker::PhantomData,
+        };
+        t.set_top(config.top);
+        t.set_mode(config.mode);
+        t.set_dead_time(config.dead_time);
+        t.set_prescaler(config.prescaler);
+        t
+    }
+
+    fn start_pll(pll: &crate::pac::PLL) {
+        if pll.pllcsr.read().bits() & PLOCK != 0 {
+            return;
+        }
+
+        let pindiv = Self::PLL_PINDIV;
+        pll.pllfrq.write(|w| unsafe { w.bits(PLLUSB | PDIV_96MHZ) });
+        pll.pllcsr.write(|w| unsafe { w.bits(pindiv) });
+        pll.pllcsr.write(|w| unsafe { w.bits(pindiv | PLLE) });
+        while pll.pllcsr.read().bits() & PLOCK == 0 {}
+    }
+
+    /// Stop the timer, disconnect all outputs and return the peripheral.
+    ///
+    /// The PLL is kept running as it might be in use by the USB controller.
+    pub fn release(self) -> crate::pac::TC4 {
+        self.timer.tccr4b.reset();
+        self.timer.tccr4a.reset();
+        self.timer.tccr4c.reset();
+        self.timer
+    }
+
+    /// The current configuration.
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    /// The PWM frequency resulting from the current configuration.
+    pub fn frequency(&self) -> avr_hal_generic::timer::Hertz {
+        let ticks = self.config.clock.frequency::<CLOCK>() / self.config.prescaler.divider();
+        let top = self.config.top as u32;
+        avr_hal_generic::timer::Hertz(match self.config.mode {
+            WaveformMode::Fast => ticks / (top + 1),
+            WaveformMode::PhaseCorrect | WaveformMode::PhaseFrequencyCorrect => {
+                ticks / (2 * top.max(1))
+            }
+        })
+    }
+
+    /// Change the TOP value of the counter; it is limited to `0x3ff`.
+    ///
+    /// This changes the PWM frequency and the maximum duty cycle of all channels.  Duty cycles
+    /// are not adjusted.
+    pub fn set_top(&mut self, top: u16) {
+        self.config.top = top.min(0x3ff);
+        write_10bit(self.config.top, |tc4, low| {
+            tc4.ocr4c.write(|w| unsafe { w.bits(low) })
+        });
+    }
+
+    /// The TOP value of the counter.
+    pub fn top(&self) -> u16 {
+        self.config.top
+    }
+
+    /// Change the prescaler.
+    pub fn set_prescaler(&mut self, prescaler: Prescaler) {
+        self.config.prescaler = prescaler;
+        self.write_tccr4b();
+    }
+
+    /// Change the waveform generation mode.
+    pub fn set_mode(&mut self, mode: WaveformMode) {
+        self.config.mode = mode;
+        let wgm = match mode {
+            WaveformMode::Fast => WGM4_PWM_FAST,
+            WaveformMode::PhaseCorrect | WaveformMode::PhaseFrequencyCorrect => WGM4_PWM_PHASE,
+        };
+        self.timer.tccr4d.write(|w| unsafe { w.bits(wgm) });
+    }
+
+    /// Change the dead time between complementary outputs.
+    pub fn set_dead_time(&mut self, dead_time: DeadTime) {
+        self.config.dead_time = dead_time;
+        self.timer.dt4.write(|w| unsafe { w.bits(dead_time.dt4()) });
+        self.write_tccr4b();
+    }
+
+    fn write_tccr4b(&mut self) {
+        let cs: u8 = match self.config.prescaler {
+            Prescaler::Direct => 0b0001,
+            Prescaler::Prescale8 => 0b0100,
+            Prescaler::Prescale64 => 0b0111,
+            Prescaler::Prescale256 => 0b1001,
+            Prescaler::Prescale1024 => 0b1011,
+        };
+        let dtps: u8 = match self.config.dead_time.prescaler {
+            DeadTimePrescaler::Direct => 0b00,
+            DeadTimePrescaler::Prescale2 => 0b01,
+            DeadTimePrescaler::Prescale4 => 0b10,
+            DeadTimePrescaler::Prescale8 => 0b11,
+        };
+        self.timer
+            .tccr4b
+            .write(|w| unsafe { w.bits((dtps << DTPS4_SHIFT) | cs) });
+    }
+}
+
+/// Write a 10-bit (or 11-bit in enhanced mode) register of `TC4`.
+///
+/// The high bits go through the shared `TC4H` register first, so this must not be interrupted by
+/// another access to the timer.
+fn write_10bit(value: u16, write_low: impl FnOnce(&crate::pac::tc4::RegisterBlock, u8)) {
+    avr_device::interrupt::free(|_| {
+        let tc4 = unsafe { &*crate::pac::TC4::ptr() };
+        tc4.tc4h.write(|w| unsafe { w.bits((value >> 8) as u8) });
+        write_low(tc4, value as u8);
+    })
+}
+
+/// Read a 10-bit (or 11-bit in enhanced mode) register of `TC4`.
+fn read_10bit(read_low: impl FnOnce(&crate::pac::tc4::RegisterBlock) -> u8) -> u16 {
+    avr_device::interrupt::free(|_| {
+        let tc4 = unsafe { &*crate::pac::TC4::ptr() };
+        // Reading the low byte latches the high bits into TC4H
+        let low = read_low(tc4);
+        let high = tc4.tc4h.read().bits();
+        ((high as u16) << 8) | low as u16
+    })
+}
+
+macro_rules! impl_channel {
+    (
+        $(#[$channel_attr:meta])*
+        pub struct $Channel:ident {
+            pin: $port:ident::$Pin:ident,
+            complementary: $cport:ident::$CPin:ident,
+            ocr: $ocr:ident,
+            tccr: $tccr:ident,
+            com_shift: $com_shift:expr,
+            pwm_bit: $pwm_bit:expr,
+            single: $single:ident,
+            complementary_method: $complementary:ident,
+        }
+    ) => {
+        $(#[$channel_attr])*
+        pub struct $Channel {
+            pin: $port::$Pin<mode::Output>,
+            complementary: Option<$cport::$CPin<mode::Output>>,
+        }
+
+        impl<CLOCK: Clock> Timer4<CLOCK> {
+            /// Use the `OC4x` pin of this channel as a single PWM output.
+            pub fn $single(&mut self, pin: $port::$Pin<mode::Output>) -> $Channel {
+                $Channel {
+                    pin,
+                    complementary: None,
+                }
+            }
+
+            /// Use the `OC4x` and `!OC4x` pins of this channel as complementary PWM outputs with
+            /// dead time.
+            pub fn $complementary(
+                &mut self,
+                pin: $port::$Pin<mode::Output>,
+                complementary: $cport::$CPin<mode::Output>,
+            ) -> $Channel {
+                $Channel {
+                    pin,
+                    complementary: Some(complementary),
+                }
+            }
+        }
+
+        impl $Channel {
+            /// Disable the output and return the pins.
+            pub fn release(
+                mut self,
+            ) -> ($port::$Pin<mode::Output>, Option<$cport::$CPin<mode::Output>>) {
+                use avr_hal_generic::hal::PwmPin;
+
+                self.disable();
+                (self.pin, self.complementary)
+            }
+
+            fn set_com(&mut self, com: u8, pwm: bool) {
+                // SAFETY: The control register is shared with other channels, so the
+                // read-modify-write sequence is wrapped in a critical section.
+                avr_device::interrupt::free(|_| {
+                    let tc4 = unsafe { &*crate::pac::TC4::ptr() };
+                    tc4.$tccr.modify(|r, w| {
+                        let mut bits = r.bits() & !(COM_MASK << $com_shift) & !$pwm_bit;
+                        if pwm {
+                            bits |= (com << $com_shift) | $pwm_bit;
+                        }
+                        unsafe { w.bits(bits) }
+                    });
+                })
+            }
+        }
+
+        impl avr_hal_generic::hal::PwmPin for $Channel {
+            type Duty = u16;
+
+            fn enable(&mut self) {
+                let com = if self.complementary.is_some() {
+                    COM_COMPLEMENTARY
+                } else {
+                    COM_SINGLE
+                };
+                self.set_com(com, true);
+            }
+
+            fn disable(&mut self) {
+                self.set_com(0, false);
+            }
+
+            fn get_duty(&self) -> u16 {
+                read_10bit(|tc4| tc4.$ocr.read().bits())
+            }
+
+            fn get_max_duty(&self) -> u16 {
+                let top = read_10bit(|tc4| tc4.ocr4c.read().bits());
+                let tc4 = unsafe { &*crate::pac::TC4::ptr() };
+                if tc4.tccr4e.read().bits() & ENHC4 != 0 {
+                    2 * top + 1
+                } else {
+                    top
+                }
+            }
+
+            fn set_duty(&mut self, duty: u16) {
+                write_10bit(duty, |tc4, low| tc4.$ocr.write(|w| unsafe { w.bits(low) }));
+            }
+        }
+    };
+}
+
+impl_channel! {
+    /// Channel `A` of `TC4` on `PC7`, complementary output on `PC6`
+    pub struct ChannelA {
+        pin: portc::PC7,
+        complementary: portc::PC6,
+        ocr: ocr4a,
+        tccr: tccr4a,
+        com_shift: 6,
+        pwm_bit: 1 << 1,
+        single: channel_a,
+        complementary_method: complementary_a,
+    }
+}
+
+impl_channel! {
+    /// Channel `B` of `TC4` on `PB6`, complementary output on `PB5`
+    pub struct ChannelB {
+        pin: portb::PB6,
+        complementary: portb::PB5,
+        ocr: ocr4b,
+        tccr: tccr4a,
+        com_shift: 4,
+        pwm_bit: 1 << 0,
+        single: channel_b,
+        complementary_method: complementary_b,
+    }
+}
+
+impl_channel! {
+    /// Channel `D` of `TC4` on `PD7`, complementary output on `PD6`
+    pub struct ChannelD {
+        pin: portd::PD7,
+        complementary: portd::PD6,
+        ocr: ocr4d,
+        tccr: tccr4c,
+        com_shift: 2,
+        pwm_bit: 1 << 0,
+        single: channel_d,
+        complementary_method: complementary_d,
+    }
+}
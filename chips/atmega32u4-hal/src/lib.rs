@@ -11,8 +11,16 @@ pub use avr_hal_generic::clock;
 pub use avr_hal_generic::delay;
 
 pub mod adc;
+pub mod capture;
+pub mod comparator;
+pub mod exint;
+pub mod hspwm;
 pub mod port;
 pub mod pwm;
+pub mod servo;
+pub mod systime;
+pub mod timer;
+pub mod tone;
 pub mod wdt;
 
 pub mod prelude {
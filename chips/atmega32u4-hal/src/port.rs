@@ -4,7 +4,7 @@
 //!
 //! [1]: ../../avr_hal_generic/port/index.html
 
-pub use avr_hal_generic::port::mode;
+pub use avr_hal_generic::port::{mode, DynamicMode, ModeError};
 
 pub trait PortExt {
     type Parts;
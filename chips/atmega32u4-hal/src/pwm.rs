@@ -48,7 +48,6 @@ avr_hal_generic::impl_pwm! {
         type Duty: u8,
         timer: crate::pac::TC0,
         init: |tim, prescaler| {
-            tim.tccr0a.modify(|_, w| w.wgm0().pwm_fast());
             tim.tccr0b.modify(|_, w| match prescaler {
                 Prescaler::Direct => w.cs0().direct(),
                 Prescaler::Prescale8 => w.cs0().prescale_8(),
@@ -57,19 +56,48 @@ avr_hal_generic::impl_pwm! {
                 Prescaler::Prescale1024 => w.cs0().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            tim.tccr0a.modify(|_, w| match mode {
+                WaveformMode::Fast => w.wgm0().pwm_fast(),
+                // Phase and frequency correct PWM is not available on 8-bit timers
+                WaveformMode::PhaseCorrect | WaveformMode::PhaseFrequencyCorrect => {
+                    w.wgm0().pwm_phase()
+                }
+            });
+            tim.tccr0b.modify(|_, w| w.wgm02().bit(variable_top));
+        },
+        top: |tim| {
+            if tim.tccr0b.read().wgm02().bit_is_set() {
+                tim.ocr0a.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.ocr0a.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             portb::PB7: {
                 ocr: ocr0a,
-                into_pwm: |tim| if enable {
-                    tim.tccr0a.modify(|_, w| w.com0a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr0a.modify(|_, w| if inverted {
+                        w.com0a().match_set()
+                    } else {
+                        w.com0a().match_clear()
+                    });
                 } else {
                     tim.tccr0a.modify(|_, w| w.com0a().disconnected());
                 },
+                holds_top: true,
             },
             portd::PD0: {
                 ocr: ocr0b,
-                into_pwm: |tim| if enable {
-                    tim.tccr0a.modify(|_, w| w.com0b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr0a.modify(|_, w| if inverted {
+                        w.com0b().match_set()
+                    } else {
+                        w.com0b().match_clear()
+                    });
                 } else {
                     tim.tccr0a.modify(|_, w| w.com0b().disconnected());
                 },
@@ -99,39 +127,67 @@ avr_hal_generic::impl_pwm! {
         type Duty: u16,
         timer: crate::pac::TC1,
         init: |tim, prescaler| {
-            tim.tccr1a.modify(|_, w| w.wgm1().bits(0b01));
-            tim.tccr1b.modify(|_, w| {
-                w.wgm1().bits(0b01);
-                match prescaler {
-                    Prescaler::Direct => w.cs1().direct(),
-                    Prescaler::Prescale8 => w.cs1().prescale_8(),
-                    Prescaler::Prescale64 => w.cs1().prescale_64(),
-                    Prescaler::Prescale256 => w.cs1().prescale_256(),
-                    Prescaler::Prescale1024 => w.cs1().prescale_1024(),
-                }
+            tim.tccr1b.modify(|_, w| match prescaler {
+                Prescaler::Direct => w.cs1().direct(),
+                Prescaler::Prescale8 => w.cs1().prescale_8(),
+                Prescaler::Prescale64 => w.cs1().prescale_64(),
+                Prescaler::Prescale256 => w.cs1().prescale_256(),
+                Prescaler::Prescale1024 => w.cs1().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            if mode == WaveformMode::PhaseFrequencyCorrect && !variable_top {
+                // This mode always takes TOP from ICR1
+                tim.icr1.write(|w| unsafe { w.bits(0xff) });
+            }
+            let wgm = mode.wgm_16bit(variable_top);
+            tim.tccr1a.modify(|_, w| w.wgm1().bits(wgm & 0b11));
+            tim.tccr1b.modify(|_, w| w.wgm1().bits(wgm >> 2));
+        },
+        top: |tim| {
+            // WGM13 selects ICR1 as TOP
+            if tim.tccr1b.read().wgm1().bits() & 0b10 != 0 {
+                tim.icr1.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.icr1.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             portb::PB5: {
                 ocr: ocr1a,
-                into_pwm: |tim| if enable {
-                    tim.tccr1a.modify(|_, w| w.com1a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr1a.modify(|_, w| if inverted {
+                        w.com1a().match_set()
+                    } else {
+                        w.com1a().match_clear()
+                    });
                 } else {
                     tim.tccr1a.modify(|_, w| w.com1a().disconnected());
                 },
             },
             portb::PB6: {
                 ocr: ocr1b,
-                into_pwm: |tim| if enable {
-                    tim.tccr1a.modify(|_, w| w.com1b().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr1a.modify(|_, w| if inverted {
+                        w.com1b().match_set()
+                    } else {
+                        w.com1b().match_clear()
+                    });
                 } else {
                     tim.tccr1a.modify(|_, w| w.com1b().disconnected());
                 },
             },
             portb::PB7: {
                 ocr: ocr1c,
-                into_pwm1: |tim| if enable {
-                    tim.tccr1a.modify(|_, w| w.com1c().match_clear());
+                into_pwm1: |tim, inverted| if enable {
+                    tim.tccr1a.modify(|_, w| if inverted {
+                        w.com1c().match_set()
+                    } else {
+                        w.com1c().match_clear()
+                    });
                 } else {
                     tim.tccr1a.modify(|_, w| w.com1c().disconnected());
                 },
@@ -157,23 +213,43 @@ avr_hal_generic::impl_pwm! {
         type Duty: u16,
         timer: crate::pac::TC3,
         init: |tim, prescaler| {
-            tim.tccr3a.modify(|_, w| w.wgm3().bits(0b01));
-            tim.tccr3b.modify(|_, w| {
-                w.wgm3().bits(0b01);
-                match prescaler {
-                    Prescaler::Direct => w.cs3().direct(),
-                    Prescaler::Prescale8 => w.cs3().prescale_8(),
-                    Prescaler::Prescale64 => w.cs3().prescale_64(),
-                    Prescaler::Prescale256 => w.cs3().prescale_256(),
-                    Prescaler::Prescale1024 => w.cs3().prescale_1024(),
-                }
+            tim.tccr3b.modify(|_, w| match prescaler {
+                Prescaler::Direct => w.cs3().direct(),
+                Prescaler::Prescale8 => w.cs3().prescale_8(),
+                Prescaler::Prescale64 => w.cs3().prescale_64(),
+                Prescaler::Prescale256 => w.cs3().prescale_256(),
+                Prescaler::Prescale1024 => w.cs3().prescale_1024(),
             });
         },
+        waveform: |tim, mode, variable_top| {
+            if mode == WaveformMode::PhaseFrequencyCorrect && !variable_top {
+                // This mode always takes TOP from ICR3
+                tim.icr3.write(|w| unsafe { w.bits(0xff) });
+            }
+            let wgm = mode.wgm_16bit(variable_top);
+            tim.tccr3a.modify(|_, w| w.wgm3().bits(wgm & 0b11));
+            tim.tccr3b.modify(|_, w| w.wgm3().bits(wgm >> 2));
+        },
+        top: |tim| {
+            // WGM33 selects ICR3 as TOP
+            if tim.tccr3b.read().wgm3().bits() & 0b10 != 0 {
+                tim.icr3.read().bits()
+            } else {
+                0xff
+            }
+        },
+        set_top: |tim, top| {
+            tim.icr3.write(|w| unsafe { w.bits(top) });
+        },
         pins: {
             portc::PC6: {
                 ocr: ocr3a,
-                into_pwm: |tim| if enable {
-                    tim.tccr3a.modify(|_, w| w.com3a().match_clear());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr3a.modify(|_, w| if inverted {
+                        w.com3a().match_set()
+                    } else {
+                        w.com3a().match_clear()
+                    });
                 } else {
                     tim.tccr3a.modify(|_, w| w.com3a().disconnected());
                 },
@@ -201,6 +277,9 @@ avr_hal_generic::impl_pwm! {
     /// ```
     ///
     /// **Note**: For `PB6` the method is called `into_pwm6()`!
+    ///
+    /// For the PLL clock, 10-bit resolution and complementary outputs, use
+    /// [`hspwm::Timer4`](../hspwm/struct.Timer4.html) instead.
     pub struct Timer4Pwm {
         type Duty: u8,
         timer: crate::pac::TC4,
@@ -212,29 +291,56 @@ avr_hal_generic::impl_pwm! {
                     Prescaler::Prescale256 => w.cs4().prescale_256(),
                     Prescaler::Prescale1024 => w.cs4().prescale_1024(),
             });
-            tim.tccr4d.modify(|_, w| w.wgm4().pwm_fast());
+        },
+        waveform: |tim, mode, _variable_top| {
+            // TOP is always taken from OCR4C and there is no plain phase correct mode; the phase
+            // and frequency correct mode is equivalent as long as TOP is not changed.
+            tim.tccr4d.modify(|_, w| match mode {
+                WaveformMode::Fast => w.wgm4().pwm_fast(),
+                WaveformMode::PhaseCorrect | WaveformMode::PhaseFrequencyCorrect => {
+                    w.wgm4().pwm_phase()
+                }
+            });
+        },
+        top: |tim| {
+            tim.ocr4c.read().bits()
+        },
+        set_top: |tim, top| {
+            tim.ocr4c.write(|w| unsafe { w.bits(top) });
         },
         pins: {
             portc::PC7: {
                 ocr: ocr4a,
-                into_pwm: |tim| if enable {
-                    tim.tccr4a.modify(|_, w| w.com4a().match_clear().pwm4a().set_bit());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr4a.modify(|_, w| if inverted {
+                        w.com4a().match_set().pwm4a().set_bit()
+                    } else {
+                        w.com4a().match_clear().pwm4a().set_bit()
+                    });
                 } else {
                     tim.tccr4a.modify(|_, w| w.com4a().disconnected().pwm4a().clear_bit());
                 },
             },
             portb::PB6: {
                 ocr: ocr4b,
-                into_pwm4: |tim| if enable {
-                    tim.tccr4a.modify(|_, w| w.com4b().match_clear().pwm4b().set_bit());
+                into_pwm4: |tim, inverted| if enable {
+                    tim.tccr4a.modify(|_, w| if inverted {
+                        w.com4b().match_set().pwm4b().set_bit()
+                    } else {
+                        w.com4b().match_clear().pwm4b().set_bit()
+                    });
                 } else {
                     tim.tccr4a.modify(|_, w| w.com4b().disconnected().pwm4b().clear_bit());
                 },
             },
             portd::PD7: {
                 ocr: ocr4d,
-                into_pwm: |tim| if enable {
-                    tim.tccr4c.modify(|_, w| w.com4d().match_clear().pwm4d().set_bit());
+                into_pwm: |tim, inverted| if enable {
+                    tim.tccr4c.modify(|_, w| if inverted {
+                        w.com4d().match_set().pwm4d().set_bit()
+                    } else {
+                        w.com4d().match_clear().pwm4d().set_bit()
+                    });
                 } else {
                     tim.tccr4c.modify(|_, w| w.com4d().disconnected().pwm4d().clear_bit());
                 },
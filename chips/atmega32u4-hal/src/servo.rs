@@ -0,0 +1,67 @@
+//! Servo control on the 16-bit timers
+//!
+//! See [`avr_hal_generic::servo`] for details.
+//!
+//! | Driver | Timer | Pins |
+//! | --- | --- | --- |
+//! | `Servo1` | `TC1` | `PB5`, `PB6`, `PB7` |
+//! | `Servo3` | `TC3` | `PC6` |
+//! | `MultiServo1` | `TC1` | Any output pin |
+//! | `MultiServo3` | `TC3` | Any output pin |
+
+use crate::port::{portb, portc};
+pub use avr_hal_generic::servo::*;
+
+avr_hal_generic::impl_servo! {
+    /// Servo driver on `TC1` (pins `PB5`, `PB6`, `PB7`)
+    pub struct Servo1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pins: {
+            portb::PB5: {
+                channel: a,
+                method: into_servo,
+            },
+            portb::PB6: {
+                channel: b,
+                method: into_servo,
+            },
+            portb::PB7: {
+                channel: c,
+                method: into_servo,
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_multi_servo! {
+    /// Software-multiplexed servo driver on `TC1` for up to 12 servos
+    pub struct MultiServo1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_servo! {
+    /// Servo driver on `TC3` (pins `PC6`)
+    pub struct Servo3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        pins: {
+            portc::PC6: {
+                channel: a,
+                method: into_servo,
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_multi_servo! {
+    /// Software-multiplexed servo driver on `TC3` for up to 12 servos
+    pub struct MultiServo3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
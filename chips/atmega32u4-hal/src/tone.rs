@@ -0,0 +1,74 @@
+//! Tone and square-wave generation with the timers
+//!
+//! See [`avr_hal_generic::tone`] for details.
+//!
+//! # Example
+//! ```
+//! let mut portb = dp.PORTB.split();
+//! let mut tone0 = Tone0::<MHz16>::new(dp.TC0, portb.pb7.into_output(&mut portb.ddr));
+//!
+//! tone0.play(1000.hz());
+//! ```
+//!
+//! | Driver | Timer | Hardware Pin |
+//! | --- | --- | --- |
+//! | `Tone0` / `SoftTone0` | `TC0` | `PB7` (`OC0A`) |
+//! | `Tone1` / `SoftTone1` | `TC1` | `PB5` (`OC1A`) |
+//! | `Tone3` / `SoftTone3` | `TC3` | `PC6` (`OC3A`) |
+
+use crate::port::{portb, portc};
+pub use avr_hal_generic::tone::*;
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC0` (8-bit), toggling pin `PB7` in hardware
+    pub struct Tone0 {
+        peripheral: crate::pac::TC0,
+        register_suffix: 0,
+        counter: u8,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portb::PB7,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC1` (16-bit), toggling pin `PB5` in hardware
+    pub struct Tone1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portb::PB5,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC3` (16-bit), toggling pin `PC6` in hardware
+    pub struct Tone3 {
+        peripheral: crate::pac::TC3,
+        register_suffix: 3,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portc::PC6,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}
@@ -7,10 +7,16 @@ use crate::pac::adc::admux::MUX_A;
 avr_hal_generic::impl_adc! {
     pub struct Adc {
         type ChannelID = MUX_A;
+        type ReferenceVoltage = avr_hal_generic::adc::ReferenceVoltage;
+        type TriggerSource = avr_hal_generic::adc::TriggerSource;
         peripheral: crate::pac::ADC,
         set_mux: |peripheral, id| {
             peripheral.admux.modify(|_, w| w.mux().variant(id));
         },
+        settling: |id| { id == MUX_A::ADC_VBG || id == MUX_A::TEMPSENS },
+        bandgap: MUX_A::ADC_VBG,
+        temperature: MUX_A::TEMPSENS,
+        sleep_control: (crate::pac::CPU, smcr),
         pins: {
             pc0: (PC0, MUX_A::ADC0, didr0::adc0d),
             pc1: (PC1, MUX_A::ADC1, didr0::adc1d),
@@ -22,6 +28,13 @@ avr_hal_generic::impl_adc! {
     }
 }
 
+/// Typical temperature sensor characteristics
+///
+/// Derived from the typical sensor values given in the ATmega48P datasheet.  These can be off by
+/// more than ±10 °C for an individual part, so calibrate the sensor if accuracy matters.
+pub const TEMPERATURE_TYPICAL: TemperatureCalibration =
+    TemperatureCalibration::typical(268, 988);
+
 /// Additional channels
 ///
 /// This module contains ADC channels, additional to the direct pin channels.
@@ -13,9 +13,17 @@ pub use avr_hal_generic::delay;
 pub mod port;
 
 pub mod adc;
+pub mod capture;
+pub mod comparator;
+pub mod exint;
 pub mod pwm;
+pub mod rtc;
 
+pub mod servo;
 pub mod spi;
+pub mod systime;
+pub mod timer;
+pub mod tone;
 
 pub mod prelude {
     pub use avr_hal_generic::prelude::*;
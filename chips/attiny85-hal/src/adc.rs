@@ -0,0 +1,173 @@
+use crate::port::portb::{PB2, PB3, PB4, PB5};
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum AdcMux {
+    Adc0 = 0b0000,
+    Adc1 = 0b0001,
+    Adc2 = 0b0010,
+    Adc3 = 0b0011,
+
+    Adc2Minus3Gain1 = 0b0110,
+    Adc2Minus3Gain20 = 0b0111,
+    Adc0Minus1Gain1 = 0b1010,
+    Adc0Minus1Gain20 = 0b1011,
+
+    AdcVbg = 0b1100,
+    AdcGnd = 0b1101,
+    AdcTemp = 0b1111,
+}
+
+/// Select the voltage reference for the ADC peripheral
+///
+/// The internal voltage reference options may not be used if an external reference voltage is
+/// being applied to the AREF pin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceVoltage {
+    /// Voltage applied to the AREF pin (`PB0`).
+    Aref,
+    /// Default reference voltage, VCC.
+    AVcc,
+    /// Internal 1.1V reference voltage.
+    Internal,
+    /// Internal 2.56V reference voltage, without external bypass capacitor.
+    Internal2V56,
+    /// Internal 2.56V reference voltage, with external bypass capacitor at the AREF pin.
+    Internal2V56Bypass,
+}
+
+impl Default for ReferenceVoltage {
+    fn default() -> Self {
+        Self::AVcc
+    }
+}
+
+/// Event which starts a conversion in auto-triggered mode
+///
+/// The discriminant is the value of the `ADTS` bits.  See the generic
+/// [`TriggerSource`](avr_hal_generic::adc::TriggerSource) for how the sources are used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TriggerSource {
+    /// Start the next conversion as soon as the previous one is completed.
+    FreeRunning = 0b000,
+    /// Analog comparator output toggled.
+    AnalogComparator = 0b001,
+    /// External interrupt request 0.
+    ExternalInterrupt0 = 0b010,
+    /// Timer/Counter0 compare match A.
+    Timer0CompareA = 0b011,
+    /// Timer/Counter0 overflow.
+    Timer0Overflow = 0b100,
+    /// Timer/Counter0 compare match B.
+    Timer0CompareB = 0b101,
+    /// Pin change interrupt request.
+    PinChange = 0b110,
+}
+
+impl Default for TriggerSource {
+    fn default() -> Self {
+        Self::FreeRunning
+    }
+}
+
+avr_hal_generic::impl_adc! {
+    pub struct Adc {
+        type ChannelID = AdcMux;
+        type ReferenceVoltage = ReferenceVoltage;
+        type TriggerSource = TriggerSource;
+        peripheral: crate::pac::ADC,
+        set_mux: |peripheral, id| {
+            let id = id as u8;
+            // MUX[3:0] are the low bits of ADMUX
+            peripheral.admux.modify(|r, w| unsafe { w.bits((r.bits() & !0x0F) | id) });
+            // Differential channels are converted in bipolar mode (BIN, bit 7 of ADCSRB) so the
+            // result is a signed number
+            let bipolar = id >= 0b0100 && id <= 0b1011;
+            peripheral.adcsrb.modify(|r, w| unsafe {
+                w.bits(if bipolar { r.bits() | 0x80 } else { r.bits() & !0x80 })
+            });
+        },
+        settling: |id| { id as u8 >= 0b0100 && id != AdcMux::AdcGnd },
+        bandgap: AdcMux::AdcVbg,
+        temperature: AdcMux::AdcTemp,
+        set_reference: |peripheral, reference| {
+            // REFS1 is bit 7, REFS0 is bit 6 and REFS2 is bit 4 of ADMUX
+            let refs = match reference {
+                ReferenceVoltage::AVcc => 0b0000_0000,
+                ReferenceVoltage::Aref => 0b0100_0000,
+                ReferenceVoltage::Internal => 0b1000_0000,
+                ReferenceVoltage::Internal2V56 => 0b1001_0000,
+                ReferenceVoltage::Internal2V56Bypass => 0b1101_0000,
+            };
+            peripheral.admux.modify(|r, w| unsafe { w.bits((r.bits() & !0b1101_0000) | refs) });
+        },
+        sleep_control: (crate::pac::CPU, mcucr),
+        pins: {
+            pb5: (PB5, AdcMux::Adc0, didr0::adc0d),
+            pb2: (PB2, AdcMux::Adc1, didr0::adc1d),
+            pb4: (PB4, AdcMux::Adc2, didr0::adc2d),
+            pb3: (PB3, AdcMux::Adc3, didr0::adc3d),
+        }
+    }
+}
+
+/// Typical temperature sensor characteristics
+///
+/// Derived from the typical sensor values given in the ATtiny85 datasheet.  These can be off by
+/// more than ±10 °C for an individual part, so calibrate the sensor if accuracy matters.
+pub const TEMPERATURE_TYPICAL: TemperatureCalibration =
+    TemperatureCalibration::typical(272, 1120);
+
+/// Additional channels
+///
+/// This module contains ADC channels, additional to the direct pin channels.
+pub mod channel {
+    use super::AdcMux;
+    use avr_hal_generic::hal::adc::Channel;
+
+    /// Channel for the _Bandgap Reference Voltage_
+    pub struct Vbg;
+    impl Channel<super::Adc> for Vbg {
+        type ID = AdcMux;
+        fn channel() -> Self::ID {
+            AdcMux::AdcVbg
+        }
+    }
+
+    /// Channel for _GND_
+    pub struct Gnd;
+    impl Channel<super::Adc> for Gnd {
+        type ID = AdcMux;
+        fn channel() -> Self::ID {
+            AdcMux::AdcGnd
+        }
+    }
+
+    /// Channel for the built-in _Temperature Sensor_
+    pub struct Temperature;
+    impl Channel<super::Adc> for Temperature {
+        type ID = AdcMux;
+        fn channel() -> Self::ID {
+            AdcMux::AdcTemp
+        }
+    }
+}
+
+// Differential channels
+//
+// | Positive | Negative | Gain |
+// | --- | --- | --- |
+// | `PB4` (ADC2) | `PB3` (ADC3) | 1x, 20x |
+// | `PB5` (ADC0) | `PB2` (ADC1) | 1x, 20x |
+avr_hal_generic::impl_adc_differential! {
+    adc: Adc,
+    channel_id: AdcMux,
+    pairs: {
+        PB4 - PB3, X1: AdcMux::Adc2Minus3Gain1,
+        PB4 - PB3, X20: AdcMux::Adc2Minus3Gain20,
+        PB5 - PB2, X1: AdcMux::Adc0Minus1Gain1,
+        PB5 - PB2, X20: AdcMux::Adc0Minus1Gain20,
+    }
+}
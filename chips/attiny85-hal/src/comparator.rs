@@ -0,0 +1,9 @@
+use crate::port::portb::{PB0, PB1};
+
+avr_hal_generic::impl_analog_comparator! {
+    pub struct AnalogComparator {
+        peripheral: crate::pac::AC,
+        ain0: (PB0, didr0::ain0d),
+        ain1: (PB1, didr0::ain1d),
+    }
+}
@@ -0,0 +1,45 @@
+//! External Interrupts and Pin-Change Interrupts
+//!
+//! `INT0` is on pin `PB2`.  Its sense control bits are in `MCUCR`.
+//!
+//! Pin-change interrupt banks:
+//!
+//! | Bank | Pins |
+//! | --- | --- |
+//! | `PinChange0` | `PB0` - `PB5` |
+
+use crate::port::portb;
+
+avr_hal_generic::impl_exint! {
+    pub struct ExtInt {
+        peripheral: crate::pac::EXINT,
+        interrupts: {
+            portb::PB2: INT0 {
+                sense: crate::pac::CPU => mcucr[0],
+                enable: gimsk[6],
+                flag: gifr[6],
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_pin_change! {
+    peripheral: crate::pac::EXINT,
+    banks: {
+        /// Pin-change interrupt `PCINT0` (pins `PCINT0` - `PCINT5` on port B)
+        pub struct PinChange0 {
+            enable: gimsk[5],
+            flag: gifr[5],
+            mask: pcmsk,
+            state: || { unsafe { &*crate::pac::PORTB::ptr() }.pinb.read().bits() },
+            pins: {
+                portb::PB0: 0,
+                portb::PB1: 1,
+                portb::PB2: 2,
+                portb::PB3: 3,
+                portb::PB4: 4,
+                portb::PB5: 5,
+            },
+        }
+    },
+}
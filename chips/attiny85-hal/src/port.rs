@@ -4,7 +4,7 @@
 //!
 //! [1]: ../../avr_hal_generic/port/index.html
 
-pub use avr_hal_generic::port::mode;
+pub use avr_hal_generic::port::{mode, DynamicMode, ModeError};
 
 pub trait PortExt {
     type Parts;
@@ -12,11 +12,20 @@ pub trait PortExt {
     fn split(self) -> Self::Parts;
 }
 
+avr_hal_generic::impl_generic_pin! {
+    pub enum Pin {
+        B(crate::pac::PORTB, portb, pinb, ddrb),
+    }
+}
+
 avr_hal_generic::impl_port! {
     pub mod portb {
         #[port_ext]
         use super::PortExt;
 
+        #[generic_pin]
+        use Pin::B;
+
         impl PortExt for crate::pac::PORTB {
             regs: (pinb, ddrb, portb),
             pb0: (PB0, 0),
@@ -0,0 +1,115 @@
+//! Timer/Counter peripherals as count-down timers
+//!
+//! Each timer implements `embedded_hal::timer::CountDown` and `embedded_hal::timer::Periodic`.
+//! The prescaler and TOP value are chosen automatically for the requested duration.
+//!
+//! # Example
+//! ```
+//! let mut timer1 = Timer1::<MHz8>::new(dp.TC1);
+//!
+//! timer1.start(100.ms());
+//! loop {
+//!     nb::block!(timer1.wait()).void_unwrap();
+//!     led.toggle().void_unwrap();
+//! }
+//! ```
+//!
+//! | Timer | Counter | Longest period at 8 MHz |
+//! | --- | --- | --- |
+//! | `Timer0` | 8-bit | 32.8 ms |
+//! | `Timer1` | 8-bit | 524 ms |
+
+pub use avr_hal_generic::timer::*;
+
+avr_hal_generic::impl_timer! {
+    /// Use `TC0` as a count-down timer (8-bit)
+    pub struct Timer0 {
+        timer: crate::pac::TC0,
+        counter: u8,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        start: |tim, cs, top| {
+            tim.tccr0b.reset();
+            // WGM01 selects CTC mode
+            tim.tccr0a.write(|w| unsafe { w.bits(0b0000_0010) });
+            tim.tcnt0.reset();
+            tim.ocr0a.write(|w| unsafe { w.bits(top) });
+            tim.tifr.write(|w| w.ocf0a().set_bit().tov0().set_bit());
+            tim.tccr0b.write(|w| unsafe { w.bits(cs) });
+        },
+        stop: |tim| {
+            tim.tccr0b.reset();
+        },
+        is_elapsed: |tim| {
+            tim.tifr.read().ocf0a().bit_is_set()
+        },
+        clear: |tim| {
+            tim.tifr.write(|w| w.ocf0a().set_bit());
+        },
+        interrupt: |tim, event, state| {
+            // TIMSK is shared with TC1, so it must be modified in a critical section.
+            avr_device::interrupt::free(|_| match event {
+                Event::Timeout => tim.timsk.modify(|_, w| w.ocie0a().bit(state)),
+                Event::Overflow => tim.timsk.modify(|_, w| w.toie0().bit(state)),
+            })
+        },
+    }
+}
+
+avr_hal_generic::impl_timer! {
+    /// Use `TC1` as a count-down timer (8-bit)
+    ///
+    /// The counter is cleared on a match with `OCR1C`.  `OCR1A` is set to the same value so the
+    /// compare match A flag and interrupt (`TIMER1_COMPA`) signal the end of each period.
+    pub struct Timer1 {
+        timer: crate::pac::TC1,
+        counter: u8,
+        prescalers: {
+            1 => 0b0001,
+            2 => 0b0010,
+            4 => 0b0011,
+            8 => 0b0100,
+            16 => 0b0101,
+            32 => 0b0110,
+            64 => 0b0111,
+            128 => 0b1000,
+            256 => 0b1001,
+            512 => 0b1010,
+            1024 => 0b1011,
+            2048 => 0b1100,
+            4096 => 0b1101,
+            8192 => 0b1110,
+            16384 => 0b1111,
+        },
+        start: |tim, cs, top| {
+            tim.tccr1.reset();
+            tim.tcnt1.reset();
+            tim.ocr1a.write(|w| unsafe { w.bits(top) });
+            tim.ocr1c.write(|w| unsafe { w.bits(top) });
+            tim.tifr.write(|w| w.ocf1a().set_bit().tov1().set_bit());
+            // CTC1 and the clock select bits are all in TCCR1
+            tim.tccr1.write(|w| unsafe { w.bits(0b1000_0000 | cs) });
+        },
+        stop: |tim| {
+            tim.tccr1.reset();
+        },
+        is_elapsed: |tim| {
+            tim.tifr.read().ocf1a().bit_is_set()
+        },
+        clear: |tim| {
+            tim.tifr.write(|w| w.ocf1a().set_bit());
+        },
+        interrupt: |tim, event, state| {
+            // TIMSK is shared with TC0, so it must be modified in a critical section.
+            avr_device::interrupt::free(|_| match event {
+                Event::Timeout => tim.timsk.modify(|_, w| w.ocie1a().bit(state)),
+                Event::Overflow => tim.timsk.modify(|_, w| w.toie1().bit(state)),
+            })
+        },
+    }
+}
@@ -0,0 +1,115 @@
+use crate::port::porta::{PA0, PA1};
+use crate::port::portc::{PC0, PC1, PC2, PC3, PC4, PC5};
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum AdcMux {
+    Adc0 = 0b0000,
+    Adc1 = 0b0001,
+    Adc2 = 0b0010,
+    Adc3 = 0b0011,
+    Adc4 = 0b0100,
+    Adc5 = 0b0101,
+    Adc6 = 0b0110,
+    Adc7 = 0b0111,
+    AdcTemp = 0b1000,
+    AdcVbg = 0b1110,
+    AdcGnd = 0b1111,
+}
+
+/// Select the voltage reference for the ADC peripheral
+///
+/// ATtiny88 has no AREF pin, so only AVcc and the internal reference can be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceVoltage {
+    /// Default reference voltage.
+    AVcc,
+    /// Internal 1.1V reference voltage.
+    Internal,
+}
+
+impl Default for ReferenceVoltage {
+    fn default() -> Self {
+        Self::AVcc
+    }
+}
+
+avr_hal_generic::impl_adc! {
+    pub struct Adc {
+        type ChannelID = AdcMux;
+        type ReferenceVoltage = ReferenceVoltage;
+        type TriggerSource = avr_hal_generic::adc::TriggerSource;
+        peripheral: crate::pac::ADC,
+        set_mux: |peripheral, id| {
+            // MUX[3:0] are the low bits of ADMUX
+            peripheral.admux.modify(|r, w| unsafe { w.bits((r.bits() & !0x0F) | id as u8) });
+        },
+        settling: |id| { id == AdcMux::AdcVbg || id == AdcMux::AdcTemp },
+        bandgap: AdcMux::AdcVbg,
+        temperature: AdcMux::AdcTemp,
+        set_reference: |peripheral, reference| {
+            // REFS0 (bit 6 of ADMUX) selects AVcc, otherwise the internal reference is used
+            let avcc = match reference {
+                ReferenceVoltage::AVcc => true,
+                ReferenceVoltage::Internal => false,
+            };
+            peripheral.admux.modify(|r, w| unsafe {
+                w.bits(if avcc { r.bits() | 0x40 } else { r.bits() & !0x40 })
+            });
+        },
+        sleep_control: (crate::pac::CPU, smcr),
+        pins: {
+            pc0: (PC0, AdcMux::Adc0, didr0::adc0d),
+            pc1: (PC1, AdcMux::Adc1, didr0::adc1d),
+            pc2: (PC2, AdcMux::Adc2, didr0::adc2d),
+            pc3: (PC3, AdcMux::Adc3, didr0::adc3d),
+            pc4: (PC4, AdcMux::Adc4, didr0::adc4d),
+            pc5: (PC5, AdcMux::Adc5, didr0::adc5d),
+            pa0: (PA0, AdcMux::Adc6, didr0::adc6d),
+            pa1: (PA1, AdcMux::Adc7, didr0::adc7d),
+        }
+    }
+}
+
+/// Typical temperature sensor characteristics
+///
+/// Derived from the typical sensor values given in the ATtiny88 datasheet.  These can be off by
+/// more than ±10 °C for an individual part, so calibrate the sensor if accuracy matters.
+pub const TEMPERATURE_TYPICAL: TemperatureCalibration =
+    TemperatureCalibration::typical(272, 1120);
+
+/// Additional channels
+///
+/// This module contains ADC channels, additional to the direct pin channels.
+pub mod channel {
+    use super::AdcMux;
+    use avr_hal_generic::hal::adc::Channel;
+
+    /// Channel for the _Bandgap Reference Voltage_
+    pub struct Vbg;
+    impl Channel<super::Adc> for Vbg {
+        type ID = AdcMux;
+        fn channel() -> Self::ID {
+            AdcMux::AdcVbg
+        }
+    }
+
+    /// Channel for _GND_
+    pub struct Gnd;
+    impl Channel<super::Adc> for Gnd {
+        type ID = AdcMux;
+        fn channel() -> Self::ID {
+            AdcMux::AdcGnd
+        }
+    }
+
+    /// Channel for the built-in _Temperature Sensor_
+    pub struct Temperature;
+    impl Channel<super::Adc> for Temperature {
+        type ID = AdcMux;
+        fn channel() -> Self::ID {
+            AdcMux::AdcTemp
+        }
+    }
+}
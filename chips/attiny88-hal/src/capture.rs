@@ -0,0 +1,19 @@
+//! Input capture on the 16-bit timers
+//!
+//! See [`avr_hal_generic::capture`] for details.
+//!
+//! | Driver | Timer | Pin |
+//! | --- | --- | --- |
+//! | `InputCapture1` | `TC1` | `PB0` (`ICP1`) |
+
+use crate::port::portb;
+pub use avr_hal_generic::capture::*;
+
+avr_hal_generic::impl_input_capture! {
+    /// Input capture on `TC1` with pin `PB0` (`ICP1`)
+    pub struct InputCapture1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        pin: portb::PB0,
+    }
+}
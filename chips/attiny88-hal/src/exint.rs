@@ -0,0 +1,108 @@
+//! External Interrupts and Pin-Change Interrupts
+//!
+//! | Interrupt | Pin |
+//! | --- | --- |
+//! | `INT0` | `PD2` |
+//! | `INT1` | `PD3` |
+//!
+//! Pin-change interrupt banks:
+//!
+//! | Bank | Pins |
+//! | --- | --- |
+//! | `PinChange0` | `PB0` - `PB7` |
+//! | `PinChange1` | `PC0` - `PC7` |
+//! | `PinChange2` | `PD0` - `PD7` |
+//! | `PinChange3` | `PA0` - `PA3` |
+
+use crate::port::{porta, portb, portc, portd};
+
+avr_hal_generic::impl_exint! {
+    pub struct ExtInt {
+        peripheral: crate::pac::EXINT,
+        interrupts: {
+            portd::PD2: INT0 {
+                sense: crate::pac::EXINT => eicra[0],
+                enable: eimsk[0],
+                flag: eifr[0],
+            },
+            portd::PD3: INT1 {
+                sense: crate::pac::EXINT => eicra[2],
+                enable: eimsk[1],
+                flag: eifr[1],
+            },
+        },
+    }
+}
+
+avr_hal_generic::impl_pin_change! {
+    peripheral: crate::pac::EXINT,
+    banks: {
+        /// Pin-change interrupt `PCINT0` (pins `PCINT0` - `PCINT7` on port B)
+        pub struct PinChange0 {
+            enable: pcicr[0],
+            flag: pcifr[0],
+            mask: pcmsk0,
+            state: || { unsafe { &*crate::pac::PORTB::ptr() }.pinb.read().bits() },
+            pins: {
+                portb::PB0: 0,
+                portb::PB1: 1,
+                portb::PB2: 2,
+                portb::PB3: 3,
+                portb::PB4: 4,
+                portb::PB5: 5,
+                portb::PB6: 6,
+                portb::PB7: 7,
+            },
+        }
+
+        /// Pin-change interrupt `PCINT1` (pins `PCINT8` - `PCINT15` on port C)
+        pub struct PinChange1 {
+            enable: pcicr[1],
+            flag: pcifr[1],
+            mask: pcmsk1,
+            state: || { unsafe { &*crate::pac::PORTC::ptr() }.pinc.read().bits() },
+            pins: {
+                portc::PC0: 0,
+                portc::PC1: 1,
+                portc::PC2: 2,
+                portc::PC3: 3,
+                portc::PC4: 4,
+                portc::PC5: 5,
+                portc::PC6: 6,
+                portc::PC7: 7,
+            },
+        }
+
+        /// Pin-change interrupt `PCINT2` (pins `PCINT16` - `PCINT23` on port D)
+        pub struct PinChange2 {
+            enable: pcicr[2],
+            flag: pcifr[2],
+            mask: pcmsk2,
+            state: || { unsafe { &*crate::pac::PORTD::ptr() }.pind.read().bits() },
+            pins: {
+                portd::PD0: 0,
+                portd::PD1: 1,
+                portd::PD2: 2,
+                portd::PD3: 3,
+                portd::PD4: 4,
+                portd::PD5: 5,
+                portd::PD6: 6,
+                portd::PD7: 7,
+            },
+        }
+
+        /// Pin-change interrupt `PCINT3` (pins `PCINT24` - `PCINT27` on port A)
+        pub struct PinChange3 {
+            enable: pcicr[3],
+            flag: pcifr[3],
+            mask: pcmsk3,
+            state: || { unsafe { &*crate::pac::PORTA::ptr() }.pina.read().bits() },
+            pins: {
+                porta::PA0: 0,
+                porta::PA1: 1,
+                porta::PA2: 2,
+                porta::PA3: 3,
+            },
+        }
+    },
+}
@@ -10,7 +10,14 @@ pub use avr_device::entry;
 pub use avr_hal_generic::clock;
 pub use avr_hal_generic::delay;
 
+pub mod adc;
+pub mod capture;
+pub mod comparator;
+pub mod exint;
 pub mod port;
+pub mod systime;
+pub mod timer;
+pub mod tone;
 
 pub mod spi;
 
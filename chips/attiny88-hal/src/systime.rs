@@ -0,0 +1,30 @@
+//! System time service on `TC0`
+//!
+//! See [`avr_hal_generic::systime`] for details.  The compare-match interrupt `TIMER0_COMPA` must
+//! call [`SysTime::on_interrupt()`].
+
+avr_hal_generic::impl_systime! {
+    /// System time service providing `millis()` and `micros()` using `TC0`
+    pub struct SysTime {
+        timer: crate::pac::TC0,
+        start: |tim, top| {
+            tim.tccr0a.reset();
+            tim.tcnt0.reset();
+            tim.ocr0a.write(|w| unsafe { w.bits(top) });
+            tim.tifr0.write(|w| w.ocf0a().set_bit());
+            tim.timsk0.write(|w| w.ocie0a().set_bit());
+            // CTC0 and prescaler 64
+            tim.tccr0a.write(|w| unsafe { w.bits(0b0000_1011) });
+        },
+        stop: |tim| {
+            tim.tccr0a.reset();
+            tim.timsk0.reset();
+        },
+        counter: |tim| {
+            tim.tcnt0.read().bits()
+        },
+        is_pending: |tim| {
+            tim.tifr0.read().ocf0a().bit_is_set()
+        },
+    }
+}
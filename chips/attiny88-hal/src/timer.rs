@@ -0,0 +1,76 @@
+//! Timer/Counter peripherals as count-down timers
+//!
+//! Each timer implements `embedded_hal::timer::CountDown` and `embedded_hal::timer::Periodic`.
+//! The prescaler and TOP value are chosen automatically for the requested duration.
+//!
+//! # Example
+//! ```
+//! let mut timer1 = Timer1::<MHz8>::new(dp.TC1);
+//!
+//! timer1.start(100.ms());
+//! loop {
+//!     nb::block!(timer1.wait()).void_unwrap();
+//!     led.toggle().void_unwrap();
+//! }
+//! ```
+//!
+//! | Timer | Counter | Longest period at 8 MHz |
+//! | --- | --- | --- |
+//! | `Timer0` | 8-bit | 32.8 ms |
+//! | `Timer1` | 16-bit | 8.39 s |
+
+pub use avr_hal_generic::timer::*;
+
+avr_hal_generic::impl_timer! {
+    /// Use `TC0` as a count-down timer (8-bit)
+    pub struct Timer0 {
+        timer: crate::pac::TC0,
+        counter: u8,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        start: |tim, cs, top| {
+            tim.tccr0a.reset();
+            tim.tcnt0.reset();
+            tim.ocr0a.write(|w| unsafe { w.bits(top) });
+            tim.tifr0.write(|w| w.ocf0a().set_bit().tov0().set_bit());
+            // CTC0 and the clock select bits are all in TCCR0A
+            tim.tccr0a.write(|w| unsafe { w.bits(0b0000_1000 | cs) });
+        },
+        stop: |tim| {
+            tim.tccr0a.reset();
+        },
+        is_elapsed: |tim| {
+            tim.tifr0.read().ocf0a().bit_is_set()
+        },
+        clear: |tim| {
+            tim.tifr0.write(|w| w.ocf0a().set_bit());
+        },
+        interrupt: |tim, event, state| {
+            match event {
+                Event::Timeout => tim.timsk0.modify(|_, w| w.ocie0a().bit(state)),
+                Event::Overflow => tim.timsk0.modify(|_, w| w.toie0().bit(state)),
+            }
+        },
+    }
+}
+
+avr_hal_generic::impl_timer_traditional! {
+    /// Use `TC1` as a count-down timer (16-bit)
+    pub struct Timer1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+    }
+}
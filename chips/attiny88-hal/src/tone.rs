@@ -0,0 +1,36 @@
+//! Tone and square-wave generation with the timers
+//!
+//! See [`avr_hal_generic::tone`] for details.
+//!
+//! # Example
+//! ```
+//! let mut portb = dp.PORTB.split();
+//! let mut tone1 = Tone1::<MHz8>::new(dp.TC1, portb.pb1.into_output(&mut portb.ddr));
+//!
+//! tone1.play(1000.hz());
+//! ```
+//!
+//! | Driver | Timer | Hardware Pin |
+//! | --- | --- | --- |
+//! | `Tone1` / `SoftTone1` | `TC1` | `PB1` (`OC1A`) |
+
+use crate::port::portb;
+pub use avr_hal_generic::tone::*;
+
+avr_hal_generic::impl_tone_traditional! {
+    /// Tone output on `TC1` (16-bit), toggling pin `PB1` in hardware
+    pub struct Tone1 {
+        peripheral: crate::pac::TC1,
+        register_suffix: 1,
+        counter: u16,
+        prescalers: {
+            1 => 0b001,
+            8 => 0b010,
+            64 => 0b011,
+            256 => 0b100,
+            1024 => 0b101,
+        },
+        pin: portb::PB1,
+        soft_pin: crate::port::Pin<crate::port::mode::Output>,
+    }
+}